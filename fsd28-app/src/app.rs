@@ -4,9 +4,18 @@ use crate::components::{
     top_menu::TopMenu,
     roster_view::RosterView,
     units_view::UnitsView,
+    print_sheet_view::PrintSheetView,
+    slot_manager::SlotManager,
+    ruleset_editor::RulesetEditor,
 };
+use crate::download::download_bytes;
+use crate::local_storage::{autosave, backup, clear_backup, restore_autosave, Restored};
+use crate::share_link::{clear_share_code_from_url, copy_share_link, share_code_from_url};
 use crate::shared_messages::SharedMessage;
 use crate::model::Model;
+use crate::rules::SharedRules;
+use fsd28_lib::{get_themes, parse_ruleset, CardTheme, CollectionDiff, ConflictResolution, Ruleset, RulesetContext, SaveData, ThemesConfig};
+use fsd28_lib::utils::share_code::decode_share_code;
 
 // For browser debugging
 use web_sys::console;
@@ -18,9 +27,19 @@ use std::rc::Rc;
 enum AppStates {
     Roster,
     Units,
+    PrintSheet,
+    Editor,
     // Other states as needed
 }
 
+// What the file picked from the file dialog is loaded as
+#[derive(PartialEq)]
+enum LoadTarget {
+    Replace, // The current content
+    Merge,   // Into the current content
+    Ruleset,
+}
+
 pub struct App {
     // State Machine
     state: AppStates,
@@ -28,11 +47,33 @@ pub struct App {
     // The Model
     model: Model,
 
+    // The look of the cards, shared by the card views
+    card_themes: ThemesConfig,
+    card_theme: CardTheme,
+
+    // The rules the profiles are built with, provided to the views
+    rules: SharedRules,
+
+    // Classes and weapons being edited in the homebrew editor
+    homebrew: Ruleset,
+
     // input file
     file_input_ref: NodeRef,
 
     // Flags
     reset_selected: bool,
+    show_slots: bool,
+    load_target: LoadTarget,
+
+    // An imported file waiting for a conflict resolution, with its conflicts
+    pending_merge: Option<(SaveData, Vec<String>)>,
+
+    // A loaded file replacing the current profiles, with the changes it makes
+    pending_load: Option<(Model, Vec<String>)>,
+
+    // Shown when the stored data could only be partially loaded
+    recovery_notice: Option<String>,
+    ruleset_errors: Vec<String>, // Why the last ruleset file could not be used
 }
 
 
@@ -42,12 +83,60 @@ impl Component for App {
     type Properties = ();
 
     fn create(_: &Context<Self>) -> Self {
-        App { 
-            state: AppStates::Units, // Default state
-            model: Model::new(),
+
+        // The last session is restored from the local storage
+        let mut state = AppStates::Units; // Default state
+        let mut model = Model::new();
+        let mut recovery_notice = None;
+        match restore_autosave() {
+            Ok(Restored::Loaded(save_data)) => model = Model::from_save_data(save_data),
+            Ok(Restored::Recovered(save_data, skipped)) => {
+                model = Model::from_save_data(save_data);
+                recovery_notice = Some(recovery_message(skipped));
+            },
+            Ok(Restored::Nothing) => {},
+            Err(err) => console::log_1(&format!("Error restoring the last session: {}", err).into()),
+        }
+
+        // Opening a share link adds its content
+        let mut shared = false;
+        if let Some(code) = share_code_from_url() {
+            match decode_share_code(&code) {
+                Ok(save_data) => {
+                    if !save_data.rosters.is_empty() {
+                        state = AppStates::Roster;
+                    }
+                    model.add_missing(save_data);
+                    shared = true;
+                },
+                Err(err) => console::log_1(&format!("Error reading the share link: {}", err).into()),
+            }
+            clear_share_code_from_url();
+        }
+
+        let homebrew = model.active_ruleset();
+        let rules = Rc::new(RulesetContext::from_ruleset(homebrew.clone()));
+        let app = App { 
+            state,
+            model,
+            card_themes: get_themes(""),
+            card_theme: CardTheme::default(),
+            rules,
+            homebrew,
             file_input_ref: NodeRef::default(),
             reset_selected: false,
+            show_slots: false,
+            load_target: LoadTarget::Replace,
+            pending_merge: None,
+            pending_load: None,
+            recovery_notice,
+            ruleset_errors: Vec::new(),
+        };
+        // The shared content is kept even if the page is reloaded before any other change
+        if shared {
+            app.autosave();
         }
+        app
     }
 
     fn update(&mut self, _: &Context<Self>, msg : SharedMessage) -> bool {
@@ -63,12 +152,63 @@ impl Component for App {
                 true
             },
 
+            SharedMessage::ViewPrintSheet => {
+                self.state = AppStates::PrintSheet;
+                true
+            },
+
+            SharedMessage::ViewEditor => {
+                self.state = AppStates::Editor;
+                true
+            },
+
             // if loaded a file: 
-            SharedMessage::FileContentReceived(text) => {
+            SharedMessage::FileContentReceived(file_name, text) => {
+                if self.load_target == LoadTarget::Ruleset {
+                    // Rulesets can be JSON, YAML or TOML, as in the CLI
+                    match parse_ruleset(&file_name, &text) {
+                        // A ruleset with problems is opened in the editor instead
+                        Ok(ruleset) => {
+                            self.ruleset_errors = ruleset.validate();
+                            if self.ruleset_errors.is_empty() {
+                                self.model.ruleset = Some(ruleset.clone());
+                                self.refresh_rules();
+                                self.autosave();
+                            } else {
+                                self.ruleset_errors.insert(0, format!("{} has problems and was opened in the editor:", file_name));
+                                self.state = AppStates::Editor;
+                            }
+                            self.homebrew = ruleset;
+                        }
+                        Err(err) => self.ruleset_errors = vec![format!("Error reading the ruleset {}", err)],
+                    }
+                    return true;
+                }
+
+                if self.load_target == LoadTarget::Merge {
+                    match SaveData::from_json(&text) {
+                        Ok(save_data) => {
+                            let conflicts = self.model.to_save_data().conflicts(&save_data);
+                            if conflicts.is_empty() {
+                                self.merge(save_data, ConflictResolution::KeepBoth);
+                            } else {
+                                self.pending_merge = Some((save_data, conflicts));
+                            }
+                        }
+                        Err(err) => console::log_1(&format!("Error importing the file: {}", err).into()),
+                    }
+                    return true;
+                }
+
                 match Model::from_json(&text) {
                     Ok(model) => {
-                        self.reset_selected = true;
-                        self.model = model;
+                        // The changes are shown before replacing existing profiles
+                        let diff = CollectionDiff::between(&self.model.profiles, &model.profiles);
+                        if self.model.profiles.is_empty() || diff.is_empty() {
+                            self.load(model);
+                        } else {
+                            self.pending_load = Some((model, diff.lines()));
+                        }
                     }
 
                     Err(_e) => {
@@ -79,7 +219,12 @@ impl Component for App {
                 true
             }
 
-            SharedMessage::Load => {
+            SharedMessage::Load | SharedMessage::Import | SharedMessage::LoadRuleset => {
+                self.load_target = match msg {
+                    SharedMessage::Import => LoadTarget::Merge,
+                    SharedMessage::LoadRuleset => LoadTarget::Ruleset,
+                    _ => LoadTarget::Replace,
+                };
                 if let Some(input) = self.file_input_ref.cast::<web_sys::HtmlInputElement>() {
                     
                     // This will only trigger the file dialog. Everything else has been set up as a closure
@@ -89,32 +234,26 @@ impl Component for App {
                 true
             }
 
+            SharedMessage::ConfirmLoad(confirmed) => {
+                if let (Some((model, _)), true) = (self.pending_load.take(), confirmed) {
+                    self.load(model);
+                }
+                true
+            }
+
+            SharedMessage::ResolveMerge(resolution) => {
+                if let (Some((save_data, _)), Some(resolution)) = (self.pending_merge.take(), resolution) {
+                    self.merge(save_data, resolution);
+                }
+                true
+            }
+
             SharedMessage::Save => {
                 match self.model.to_json() {
                     Ok(json_string) => {
-                        let document = web_sys::window().unwrap().document().unwrap();
-                        let a = document.create_element("a")
-                            .unwrap()
-                            .dyn_into::<web_sys::HtmlAnchorElement>()
-                            .unwrap();
-                        
-                        // Convert the JSON string to a Blob
-                        let mut blob_parts: web_sys::BlobPropertyBag = web_sys::BlobPropertyBag::new();
-                        blob_parts.type_("application/json");
-                        let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&json_string.into()), &blob_parts).unwrap();
-                        
-                        // Create an Object URL from the Blob
-                        let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
-                        
-                        a.set_href(&url);
-                        a.set_download("profiles.json");
-                        a.set_attribute("style", "display: none").unwrap();
-                        document.body().unwrap().append_child(&a).unwrap();
-                        a.click();
-                        a.remove();
-                        
-                        // Clean up the Object URL to free resources
-                        web_sys::Url::revoke_object_url(&url).unwrap();
+                        if let Err(err) = download_bytes(json_string.as_bytes(), "application/json", "profiles.json") {
+                            console::log_1(&format!("Error saving profiles: {}", err).into());
+                        }
                     },
                     Err(e) => {
                         console::log_1(&format!("Error serializing profiles: {:?}", e).into());
@@ -123,9 +262,88 @@ impl Component for App {
                 false
             },
 
+            SharedMessage::CopyShareLink => {
+                match copy_share_link(&self.model.to_save_data()) {
+                    Ok(link) => console::log_1(&format!("Share link copied: {}", link).into()),
+                    Err(err) => console::log_1(&format!("Error sharing the profiles: {}", err).into()),
+                }
+                false
+            },
+
+            SharedMessage::UpdateRosters(updated_rosters) => {
+                self.model.rosters = updated_rosters;
+                self.autosave();
+                true
+            },
+
+            SharedMessage::ImportRoster(imported_profiles, roster) => {
+                self.model.profiles.extend(imported_profiles);
+                self.model.rosters.push(roster);
+                self.autosave();
+                true
+            },
+
+            SharedMessage::UpdateHomebrew(ruleset) => {
+                self.homebrew = ruleset;
+                true
+            },
+
+            // None goes back to the bundled classes and weapons
+            SharedMessage::ApplyRuleset(ruleset) => {
+                self.model.ruleset = ruleset;
+                self.refresh_rules();
+                self.autosave();
+                true
+            },
+
+            SharedMessage::SelectCardTheme(name) => {
+                if let Some(theme) = self.card_themes.find(&name) {
+                    self.card_theme = theme.clone();
+                }
+                true
+            },
+
             SharedMessage::UpdateProfiles(updated_profiles) => {
                 self.model.profiles = updated_profiles;
                 self.reset_selected = false;
+                self.autosave();
+                true
+            },
+
+            SharedMessage::OpenSlots => {
+                self.show_slots = true;
+                true
+            },
+
+            SharedMessage::CloseSlots => {
+                self.show_slots = false;
+                true
+            },
+
+            SharedMessage::SlotLoaded(save_data, skipped) => {
+                self.load(Model::from_save_data(save_data));
+                self.show_slots = false;
+                self.recovery_notice = (skipped > 0).then(|| recovery_message(skipped));
+                true
+            },
+
+            SharedMessage::DownloadBackup => {
+                if let Some(json) = backup() {
+                    if let Err(err) = download_bytes(json.as_bytes(), "application/json", "backup.json") {
+                        console::log_1(&format!("Error saving the backup: {}", err).into());
+                    }
+                }
+                false
+            },
+
+            SharedMessage::DismissRulesetErrors => {
+                self.ruleset_errors.clear();
+                true
+            },
+
+            SharedMessage::DismissRecovery => {
+                self.recovery_notice = None;
+                clear_backup();
                 true
             },
 
@@ -138,79 +356,199 @@ impl Component for App {
 
         // Loading the models necessary for the sub-views.
         let profiles = self.model.profiles.clone();
+        let card_theme_names: Vec<String> = self.card_themes.themes.iter().map(|theme| theme.name.clone()).collect();
 
         html! {
-            <div>
-                <div class="mobile-warning">
-                    { "This app is not designed for mobile (yet)." }
-                </div>
-                <div class = "app">
-                    <TopMenu 
-                        on_switch_to_roster = {ctx.link().callback(|_| SharedMessage::ViewRoster)} 
-                        on_switch_to_units = {ctx.link().callback(|_| SharedMessage::ViewUnits)} 
-                        on_save = {ctx.link().callback(|_| SharedMessage::Save)} 
-                        on_load = {ctx.link().callback(|_| SharedMessage::Load)} 
-                    />
-                    {
-                        match self.state {
-                            AppStates::Roster => html! { <RosterView 
-                                profiles={profiles} 
-                                on_profiles_changed={ctx.link().callback(SharedMessage::UpdateProfiles)}
-                                /> },
-                            AppStates::Units => html! { <UnitsView 
-                                profiles={profiles} 
-                                on_profiles_changed={ctx.link().callback(SharedMessage::UpdateProfiles)}
-                                reset_selected={self.reset_selected}
-                                /> },
+            <ContextProvider<SharedRules> context={self.rules.clone()}>
+                <div>
+                    <div class="mobile-warning">
+                        { "This app is not designed for mobile (yet)." }
+                    </div>
+                    <div class = "app">
+                        <TopMenu 
+                            on_switch_to_roster = {ctx.link().callback(|_| SharedMessage::ViewRoster)} 
+                            on_switch_to_units = {ctx.link().callback(|_| SharedMessage::ViewUnits)} 
+                            on_switch_to_print = {ctx.link().callback(|_| SharedMessage::ViewPrintSheet)} 
+                            on_switch_to_editor = {ctx.link().callback(|_| SharedMessage::ViewEditor)} 
+                            on_save = {ctx.link().callback(|_| SharedMessage::Save)} 
+                            on_load = {ctx.link().callback(|_| SharedMessage::Load)} 
+                            on_import = {ctx.link().callback(|_| SharedMessage::Import)} 
+                            on_share = {ctx.link().callback(|_| SharedMessage::CopyShareLink)} 
+                            on_slots = {ctx.link().callback(|_| SharedMessage::OpenSlots)} 
+                            on_load_ruleset = {ctx.link().callback(|_| SharedMessage::LoadRuleset)} 
+                            on_card_theme_change = {ctx.link().callback(|msg| msg)}
+                            card_themes = {card_theme_names}
+                            current_card_theme = {self.card_theme.name.clone()}
+                        />
+                        if let Some(notice) = &self.recovery_notice {
+                            <div class="recovery-notice">
+                                <span>{ notice }</span>
+                                <button onclick={ctx.link().callback(|_| SharedMessage::DownloadBackup)}>{ "Download Backup" }</button>
+                                <button onclick={ctx.link().callback(|_| SharedMessage::DismissRecovery)}>{ "Dismiss" }</button>
+                            </div>
+                        }
+                        if !self.ruleset_errors.is_empty() {
+                            <div class="recovery-notice ruleset-errors">
+                                <ul>
+                                    { for self.ruleset_errors.iter().map(|error| html! { <li>{ error }</li> }) }
+                                </ul>
+                                <button onclick={ctx.link().callback(|_| SharedMessage::DismissRulesetErrors)}>{ "Dismiss" }</button>
+                            </div>
+                        }
+                        if let Some((_, conflicts)) = &self.pending_merge {
+                            <div class="modal">
+                                <div class="modal-content merge-dialog">
+                                    <div class="modal-header">{"Already existing"}</div>
+                                    <div class="merge-conflicts">{ conflicts.join(", ") }</div>
+                                    <button onclick={ctx.link().callback(|_| SharedMessage::ResolveMerge(Some(ConflictResolution::KeepBoth)))}>{ "Keep Both" }</button>
+                                    <button onclick={ctx.link().callback(|_| SharedMessage::ResolveMerge(Some(ConflictResolution::Overwrite)))}>{ "Overwrite" }</button>
+                                    <button onclick={ctx.link().callback(|_| SharedMessage::ResolveMerge(Some(ConflictResolution::Skip)))}>{ "Skip" }</button>
+                                    <div class="modal-footer">
+                                        <div class="button" onclick={ctx.link().callback(|_| SharedMessage::ResolveMerge(None))}>{"Cancel"}</div>
+                                    </div>
+                                </div>
+                            </div>
+                        }
+                        if let Some((_, changes)) = &self.pending_load {
+                            <div class="modal">
+                                <div class="modal-content load-diff">
+                                    <div class="modal-header">{"Changes from the current profiles"}</div>
+                                    <pre class="load-diff-lines">{ changes.join("\n") }</pre>
+                                    <button onclick={ctx.link().callback(|_| SharedMessage::ConfirmLoad(true))}>{ "Load" }</button>
+                                    <div class="modal-footer">
+                                        <div class="button" onclick={ctx.link().callback(|_| SharedMessage::ConfirmLoad(false))}>{"Cancel"}</div>
+                                    </div>
+                                </div>
+                            </div>
+                        }
+                        if self.show_slots {
+                            <SlotManager
+                                save_data={self.model.to_save_data()}
+                                on_load={ctx.link().callback(|(save_data, skipped)| SharedMessage::SlotLoaded(save_data, skipped))}
+                                on_close={ctx.link().callback(|_| SharedMessage::CloseSlots)}
+                            />
+                        }
+                        {
+                            match self.state {
+                                AppStates::Roster => html! { <RosterView 
+                                    profiles={profiles} 
+                                    rosters={self.model.rosters.clone()}
+                                    on_rosters_changed={ctx.link().callback(SharedMessage::UpdateRosters)}
+                                    on_roster_imported={ctx.link().callback(|(profiles, roster)| SharedMessage::ImportRoster(profiles, roster))}
+                                    /> },
+                                AppStates::Units => html! { <UnitsView 
+                                    profiles={profiles} 
+                                    on_profiles_changed={ctx.link().callback(SharedMessage::UpdateProfiles)}
+                                    reset_selected={self.reset_selected}
+                                    card_theme={self.card_theme.clone()}
+                                    /> },
+                                AppStates::PrintSheet => html! { <PrintSheetView 
+                                    profiles={profiles} 
+                                    card_theme={self.card_theme.clone()}
+                                    /> },
+                                AppStates::Editor => html! { <RulesetEditor 
+                                    ruleset={self.homebrew.clone()}
+                                    on_ruleset_changed={ctx.link().callback(SharedMessage::UpdateHomebrew)}
+                                    on_ruleset_applied={ctx.link().callback(SharedMessage::ApplyRuleset)}
+                                    in_use={self.model.ruleset.as_ref() == Some(&self.homebrew)}
+                                    card_theme={self.card_theme.clone()}
+                                    /> },
+                            }
                         }
-                    }
 
-                    // File Selection Popup
-                    <input type="file" ref={self.file_input_ref.clone()} style="display: none" onchange={
-                        let link_clone_outer = ctx.link().clone(); // Clone the link outside of the callback
-                        let link_clone_inner = link_clone_outer.clone(); // Clone the link for the inner closure
-                        link_clone_outer.callback(move |event: web_sys::Event| {
-                            if let Some(target) = event.target() {
-                                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
-                                    if let Some(files) = input.files() {
-                                        if let Some(file) = files.get(0) {
+                        // File Selection Popup
+                        <input type="file" ref={self.file_input_ref.clone()} style="display: none" onchange={
+                            let link_clone_outer = ctx.link().clone(); // Clone the link outside of the callback
+                            let link_clone_inner = link_clone_outer.clone(); // Clone the link for the inner closure
+                            link_clone_outer.callback(move |event: web_sys::Event| {
+                                if let Some(target) = event.target() {
+                                    if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
+                                        if let Some(files) = input.files() {
+                                            if let Some(file) = files.get(0) {
                                             
-                                            // Get the name of the file
-                                            let file_name = file.name();
-                                            console::log_1(&format!("Selected file name: {}", file_name).into());
+                                                // Get the name of the file
+                                                let file_name = file.name();
+                                                console::log_1(&format!("Selected file name: {}", file_name).into());
                                 
-                                            // Read the content of the file
-                                            let file_reader = web_sys::FileReader::new().unwrap();
-                                            let file_reader_rc = Rc::new(file_reader); // Wrap the FileReader in an Rc
-                                            let file_reader_clone = file_reader_rc.clone(); // Clone the Rc for the closure
-
-                                            // Clone the link for the onload closure. Yep, another cloning.
-                                            let link_clone_for_onload = link_clone_inner.clone();
-                                            let onload_closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
-                                                if let Ok(result) = file_reader_clone.result() {
-                                                    if let Some(text) = result.as_string() {
+                                                // Read the content of the file
+                                                let file_reader = web_sys::FileReader::new().unwrap();
+                                                let file_reader_rc = Rc::new(file_reader); // Wrap the FileReader in an Rc
+                                                let file_reader_clone = file_reader_rc.clone(); // Clone the Rc for the closure
+
+                                                // Clone the link for the onload closure. Yep, another cloning.
+                                                let link_clone_for_onload = link_clone_inner.clone();
+                                                let onload_closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                                                    if let Ok(result) = file_reader_clone.result() {
+                                                        if let Some(text) = result.as_string() {
                                                         
-                                                        link_clone_for_onload.send_message(SharedMessage::FileContentReceived(text));
+                                                            link_clone_for_onload.send_message(SharedMessage::FileContentReceived(file_name.clone(), text));
+                                                        }
                                                     }
-                                                }
-                                            }) as Box<dyn FnMut(_)>);
+                                                }) as Box<dyn FnMut(_)>);
                                 
-                                            file_reader_rc.add_event_listener_with_callback("load", onload_closure.as_ref().unchecked_ref()).unwrap();
-                                            onload_closure.forget();
+                                                file_reader_rc.add_event_listener_with_callback("load", onload_closure.as_ref().unchecked_ref()).unwrap();
+                                                onload_closure.forget();
                                 
-                                            file_reader_rc.read_as_text(&file).unwrap();
+                                                file_reader_rc.read_as_text(&file).unwrap();
 
-                                            // Resetting the input file for hte next time
-                                            input.set_value("");
+                                                // Resetting the input file for hte next time
+                                                input.set_value("");
+                                            }
                                         }
                                     }
                                 }
-                            }
-                            SharedMessage::NoOp // Return a dummy message
-                        })
-                    }/>
+                                SharedMessage::NoOp // Return a dummy message
+                            })
+                        }/>
+                    </div>
                 </div>
-            </div>
+            </ContextProvider<SharedRules>>
         }
     }
-}
\ No newline at end of file
+}
+
+impl App {
+
+    fn load(&mut self, model: Model) {
+        self.reset_selected = true;
+        if let Some(ruleset) = &model.ruleset {
+            self.homebrew = ruleset.clone();
+        }
+        let ruleset_changed = model.ruleset != self.model.ruleset;
+        self.model = model;
+        if ruleset_changed {
+            self.refresh_rules();
+        }
+        self.autosave();
+    }
+
+    fn merge(&mut self, save_data: SaveData, resolution: ConflictResolution) {
+        let mut merged = self.model.to_save_data();
+        let report = merged.merge(save_data, resolution);
+        console::log_1(&format!("Imported: {}", report).into());
+        let ruleset_changed = merged.ruleset != self.model.ruleset;
+        self.model = Model::from_save_data(merged);
+        if ruleset_changed {
+            self.refresh_rules();
+        }
+        self.reset_selected = true;
+        self.autosave();
+    }
+
+    // Called when the ruleset of the model changes: the rules are parsed and indexed once here,
+    // with the glossary entries of the ruleset.
+    fn refresh_rules(&mut self) {
+        self.rules = Rc::new(RulesetContext::from_ruleset(self.model.active_ruleset()));
+    }
+
+    // Called after every change of the model, so that reloading the page doesn't lose anything.
+    fn autosave(&self) {
+        if let Err(err) = autosave(&self.model.to_save_data()) {
+            console::log_1(&format!("Error saving to the local storage: {}", err).into());
+        }
+    }
+}
+
+fn recovery_message(skipped: usize) -> String {
+    format!("{} profiles or rosters couldn't be read any more, probably after a ruleset update, and were left out. The original data is kept as a backup.", skipped)
+}
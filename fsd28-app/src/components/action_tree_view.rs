@@ -1,5 +1,6 @@
 use yew::prelude::*;
 use fsd28_lib::{Weapon, WeaponOption, Action}; 
+use fsd28_lib::models::profile::MAX_ACTIONS;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct ActionTreeViewProps {
@@ -101,7 +102,7 @@ impl ActionTreeView {
         let is_base_option_selected = all_options.iter().any(|opt| {
             opt.is_base && ctx.props().selected_actions.contains(&opt.action.name)
         });
-        let is_too_many_actions = ctx.props().selected_actions.len() >= 3; // TODO This should not be a magic number
+        let is_too_many_actions = ctx.props().selected_actions.len() >= MAX_ACTIONS;
 
         let is_available = !is_too_many_actions && (!is_there_base_option || option.is_base || is_base_option_selected);
 
@@ -6,46 +6,85 @@ use fsd28_lib::models::{
     damage_chart::DamageChart,
     damage_chart::Color,
 };
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, console};
+use fsd28_lib::{AbilityDisplay, CardTheme};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 use wasm_bindgen::JsCast;
+use yew::context::ContextHandle;
+
+use crate::rules::{subscribe_rules, SharedRules};
 
 #[derive(Properties, PartialEq)]
 pub struct CardGeneratorProps {
     pub profile: Profile,
+    #[prop_or_default]
+    pub ability_display: AbilityDisplay,
+    #[prop_or_default]
+    pub theme: CardTheme,
 }
 
 pub struct CardGenerator {
     canvas: NodeRef,
     ctx: Option<CanvasRenderingContext2d>,
+    theme: CardTheme,
+    rules: SharedRules, // For the rules text of the special abilities
+    _rules_handle: ContextHandle<SharedRules>,
+}
+
+pub enum Msg {
+    RulesChanged(SharedRules),
 }
 
-// Constants for card dimensions and layout
+// Constants for card dimensions and layout. Font sizes, colours and borders come from the CardTheme.
 const CARD_WIDTH: f64 = 750.0; // 2.5 inches at 300 DPI
 const CARD_HEIGHT: f64 = 1050.0; // 3.5 inches at 300 DPI
 const MARGIN: f64 = 30.0;
-const TITLE_SIZE: f64 = 60.0;
-const SUBTITLE_SIZE: f64 = 28.0;
-const TEXT_SIZE: f64 = 24.0;
-const ACTION_TITLE_SIZE: f64 = 36.0;
-const ACTION_DESCRIPTION_SIZE: f64 = 30.0;
-const ACTION_COUNTER_SIZE: f64 = 36.0;
-const ACTION_COST_SIZE: f64 = 50.0;
 const STAT_GRID_ROWS: usize = 2;
 const STAT_GRID_COLS: usize = 3;
 const DICE_BOX_SIZE_MM: f64 = 10.0; // 10mm
 const DICE_BOX_SIZE: f64 = DICE_BOX_SIZE_MM * 11.81; // Convert mm to pixels at 300 DPI
-const DICE_BOX_BORDER_WIDTH: f64 = 8.0;
-const DICE_BOX_CORNER_RADIUS: f64 = 10.0;
 const LINE_HEIGHT: f64 = 1.2; // Line height multiplier
 
 impl CardGenerator {
-    pub fn new() -> Self {
+    pub fn new(rules: SharedRules, rules_handle: ContextHandle<SharedRules>) -> Self {
         Self {
             canvas: NodeRef::default(),
             ctx: None,
+            theme: CardTheme::default(),
+            rules,
+            _rules_handle: rules_handle,
         }
     }
 
+    fn font(&self, size: f64) -> String {
+        format!("bold {}px {}", size, self.theme.font_family)
+    }
+
+    // Frame around the card, only drawn if the theme has one.
+    fn draw_frame(&self, ctx: &CanvasRenderingContext2d) {
+        let border = &self.theme.border;
+        if border.frame_width <= 0.0 {
+            return;
+        }
+        let inset = border.frame_width as f64 / 2.0;
+        let radius = border.frame_radius as f64;
+        let (left, top, right, bottom) = (inset, inset, CARD_WIDTH - inset, CARD_HEIGHT - inset);
+
+        ctx.set_stroke_style(&self.theme.accent_color().into());
+        ctx.set_line_width(border.frame_width as f64);
+        ctx.begin_path();
+        ctx.move_to(left + radius, top);
+        ctx.line_to(right - radius, top);
+        ctx.quadratic_curve_to(right, top, right, top + radius);
+        ctx.line_to(right, bottom - radius);
+        ctx.quadratic_curve_to(right, bottom, right - radius, bottom);
+        ctx.line_to(left + radius, bottom);
+        ctx.quadratic_curve_to(left, bottom, left, bottom - radius);
+        ctx.line_to(left, top + radius);
+        ctx.quadratic_curve_to(left, top, left + radius, top);
+        ctx.close_path();
+        ctx.stroke();
+    }
+
     pub fn initialize_canvas(&mut self) {
         if let Some(canvas) = self.canvas.cast::<HtmlCanvasElement>() {
             canvas.set_width(CARD_WIDTH as u32);
@@ -54,7 +93,7 @@ impl CardGenerator {
         }
     }
 
-    pub fn generate_card(&self, profile: &Profile) {
+    pub fn generate_card(&self, profile: &Profile, ability_display: AbilityDisplay) {
         if let Some(ctx) = &self.ctx {
             // Clear canvas
             ctx.clear_rect(0.0, 0.0, CARD_WIDTH, CARD_HEIGHT);
@@ -62,9 +101,11 @@ impl CardGenerator {
             // Get the final profile with modifiers applied
             let final_profile = profile.get_final_profile();
 
-            // Draw card background
-            ctx.set_fill_style(&"white".into());
+            // Draw card background and frame
+            ctx.set_fill_style(&self.theme.background.as_str().into());
             ctx.fill_rect(0.0, 0.0, CARD_WIDTH, CARD_HEIGHT);
+            self.draw_frame(ctx);
+            ctx.set_fill_style(&self.theme.text_color.as_str().into());
 
             // Draw title and subtitle
             self.draw_title(ctx, &final_profile.name, &final_profile.description);
@@ -79,7 +120,7 @@ impl CardGenerator {
             self.draw_actions(ctx, &final_profile.actions);
 
             // Draw special abilities
-            self.draw_special_abilities(ctx, &final_profile.special_abilities);
+            self.draw_special_abilities(ctx, &final_profile.special_abilities, ability_display);
 
             // Draw damage chart
             self.draw_damage_chart(ctx, &final_profile.damage_chart);
@@ -88,8 +129,8 @@ impl CardGenerator {
 
     fn draw_title(&self, ctx: &CanvasRenderingContext2d, title: &str, subtitle: &str) {
         // Set up the font
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", TITLE_SIZE));
-        ctx.set_fill_style(&"black".into());
+        ctx.set_font(&self.font(self.theme.sizes.title as f64));
+        ctx.set_fill_style(&self.theme.accent_color().into());
         ctx.set_text_align("center");
         ctx.set_text_baseline("top");
 
@@ -99,26 +140,28 @@ impl CardGenerator {
 
         // Draw title with word wrapping and all caps
         let uppercase_title = title.to_uppercase();
-        self.draw_wrapped_text(ctx, &uppercase_title, title_x, title_y, CARD_WIDTH - 2.0 * MARGIN, TITLE_SIZE);
+        self.draw_wrapped_text(ctx, &uppercase_title, title_x, title_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.title as f64);
 
         // Draw subtitle - moved up and added small caps
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", SUBTITLE_SIZE));
+        ctx.set_font(&self.font(self.theme.sizes.subtitle as f64));
+        ctx.set_fill_style(&self.theme.text_color.as_str().into());
         ctx.set_text_align("center");
-        let subtitle_y = title_y + TITLE_SIZE * LINE_HEIGHT - 10.0; // Moved up by 10px
+        let subtitle_y = title_y + self.theme.sizes.title as f64 * LINE_HEIGHT - 10.0; // Moved up by 10px
         let small_caps_subtitle = subtitle.to_uppercase();
-        self.draw_wrapped_text(ctx, &small_caps_subtitle, title_x, subtitle_y, CARD_WIDTH - 2.0 * MARGIN, SUBTITLE_SIZE);
+        self.draw_wrapped_text(ctx, &small_caps_subtitle, title_x, subtitle_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.subtitle as f64);
     }
 
     fn draw_points_label(&self, ctx: &CanvasRenderingContext2d, points: &u32) {
         // Set up the font and style
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", SUBTITLE_SIZE));
-        ctx.set_fill_style(&"black".into());
+        ctx.set_font(&self.font(self.theme.sizes.subtitle as f64));
+        ctx.set_fill_style(&self.theme.accent_color().into());
         ctx.set_text_align("right");
         ctx.set_text_baseline("top");
 
         // Draw the points with small caps styling
         let points_text = format!("{} PTS", points);
         ctx.fill_text(&points_text, CARD_WIDTH - MARGIN, MARGIN).unwrap();
+        ctx.set_fill_style(&self.theme.text_color.as_str().into());
     }
 
     fn draw_wrapped_text(&self, ctx: &CanvasRenderingContext2d, text: &str, x: f64, y: f64, max_width: f64, font_size: f64) {
@@ -153,12 +196,12 @@ impl CardGenerator {
     }
 
     fn draw_stats_grid(&self, ctx: &CanvasRenderingContext2d, stats: &Characteristics) {
-        let grid_start_y = MARGIN + TITLE_SIZE * LINE_HEIGHT + SUBTITLE_SIZE * LINE_HEIGHT + 20.0;
+        let grid_start_y = MARGIN + self.theme.sizes.title as f64 * LINE_HEIGHT + self.theme.sizes.subtitle as f64 * LINE_HEIGHT + 20.0;
         let cell_width = (CARD_WIDTH - 2.0 * MARGIN) / STAT_GRID_COLS as f64;
         let cell_height = 80.0;
 
         // Draw grid lines
-        ctx.set_stroke_style(&"black".into());
+        ctx.set_stroke_style(&self.theme.text_color.as_str().into());
         ctx.set_line_width(1.0);
 
         // Draw stats
@@ -177,18 +220,18 @@ impl CardGenerator {
                 let y = grid_start_y + i as f64 * cell_height;
                 
                 // Draw stat name
-                ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", TEXT_SIZE));
+                ctx.set_font(&self.font(self.theme.sizes.text as f64));
                 ctx.fill_text(stat[0], x, y + 20.0).unwrap();
                 
                 // Draw stat value with larger font
-                ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", TEXT_SIZE * 2.0));
+                ctx.set_font(&self.font(self.theme.sizes.text as f64 * 2.0));
                 ctx.fill_text(stat[1], x, y + 50.0).unwrap();
             }
         }
     }
 
-    fn draw_actions(&self, ctx: &CanvasRenderingContext2d, actions: &Vec<Action>) {
-        let actions_start_y = MARGIN + TITLE_SIZE * LINE_HEIGHT + SUBTITLE_SIZE * LINE_HEIGHT + 20.0 + 
+    fn draw_actions(&self, ctx: &CanvasRenderingContext2d, actions: &[Action]) {
+        let actions_start_y = MARGIN + self.theme.sizes.title as f64 * LINE_HEIGHT + self.theme.sizes.subtitle as f64 * LINE_HEIGHT + 20.0 + 
                               STAT_GRID_ROWS as f64 * 80.0 + MARGIN;
         let mut current_y = actions_start_y;
 
@@ -197,7 +240,7 @@ impl CardGenerator {
             let action_height = self.calculate_action_height(ctx, action);
             
             // Draw the S1, S2, S3 label centered vertically and rotated
-            ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", TEXT_SIZE));
+            ctx.set_font(&self.font(self.theme.sizes.text as f64));
             ctx.set_text_align("center");
             ctx.set_text_baseline("middle");
             let label = format!("S{}", i + 1);
@@ -219,19 +262,19 @@ impl CardGenerator {
 
     fn calculate_action_height(&self, ctx: &CanvasRenderingContext2d, action: &Action) -> f64 {
         // Calculate text height
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", ACTION_TITLE_SIZE));
-        let title_height = ACTION_TITLE_SIZE * LINE_HEIGHT;
+        ctx.set_font(&self.font(self.theme.sizes.action_title as f64));
+        let title_height = self.theme.sizes.action_title as f64 * LINE_HEIGHT;
         
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", ACTION_DESCRIPTION_SIZE));
+        ctx.set_font(&self.font(self.theme.sizes.action_description as f64));
         let description_height = self.calculate_wrapped_text_height(ctx, &action.text, 
             CARD_WIDTH - (MARGIN + 60.0 + action.cost.goon.len() as f64 * (DICE_BOX_SIZE + 10.0) + 20.0 + MARGIN), 
-            ACTION_DESCRIPTION_SIZE);
+            self.theme.sizes.action_description as f64);
 
         // Return the maximum of box height and text height, plus some padding
         f64::max(DICE_BOX_SIZE, title_height + description_height) + 20.0
     }
 
-    fn calculate_wrapped_text_height(&self, ctx: &CanvasRenderingContext2d, text: &str, max_width: f64, font_size: f64) -> f64 {
+    fn calculate_wrapped_text_height(&self, _ctx: &CanvasRenderingContext2d, text: &str, max_width: f64, font_size: f64) -> f64 {
         let chars_per_line = (max_width / (font_size * 0.6)) as usize;
         let words: Vec<&str> = text.split_whitespace().collect();
         let mut current_line = String::new();
@@ -281,16 +324,16 @@ impl CardGenerator {
         };
         
         // Calculate text block height
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", ACTION_TITLE_SIZE));
-        let title_height = ACTION_TITLE_SIZE * LINE_HEIGHT;
+        ctx.set_font(&self.font(self.theme.sizes.action_title as f64));
+        let title_height = self.theme.sizes.action_title as f64 * LINE_HEIGHT;
         
         // Try to fit the text with dynamic font size reduction
-        let mut current_font_size = ACTION_DESCRIPTION_SIZE;
+        let mut current_font_size = self.theme.sizes.action_description as f64;
         let mut description_height;
         
         // Try up to 2 font size reductions
         for _ in 0..2 {
-            ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", current_font_size));
+            ctx.set_font(&self.font(current_font_size));
             description_height = self.calculate_wrapped_text_height(ctx, &action.text, text_width, current_font_size);
             
             // If the text fits within the action box height, we're good
@@ -308,13 +351,13 @@ impl CardGenerator {
         let text_start_y = vertical_center - total_text_height/2.0;
 
         // Draw title
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", ACTION_TITLE_SIZE));
+        ctx.set_font(&self.font(self.theme.sizes.action_title as f64));
         ctx.set_text_align("left");
         ctx.set_text_baseline("top");
         ctx.fill_text(&action.name, x_cursor, text_start_y).unwrap();
 
         // Draw description with potentially reduced font size
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", current_font_size));
+        ctx.set_font(&self.font(current_font_size));
         self.draw_wrapped_text(ctx, &action.text, x_cursor, 
             text_start_y + title_height, 
             text_width, 
@@ -327,20 +370,20 @@ impl CardGenerator {
     }
 
     fn draw_dice_box(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64, range: &(u32, u32)) {
-        ctx.set_stroke_style(&"black".into());
-        ctx.set_line_width(DICE_BOX_BORDER_WIDTH);
+        ctx.set_stroke_style(&self.theme.text_color.as_str().into());
+        ctx.set_line_width(self.theme.border.dice_box_width as f64);
 
         // Draw rounded rectangle
         ctx.begin_path();
-        ctx.move_to(x + DICE_BOX_CORNER_RADIUS, y);
-        ctx.line_to(x + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS, y);
-        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y, x + DICE_BOX_SIZE, y + DICE_BOX_CORNER_RADIUS);
-        ctx.line_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS);
-        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE, x + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS, y + DICE_BOX_SIZE);
-        ctx.line_to(x + DICE_BOX_CORNER_RADIUS, y + DICE_BOX_SIZE);
-        ctx.quadratic_curve_to(x, y + DICE_BOX_SIZE, x, y + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS);
-        ctx.line_to(x, y + DICE_BOX_CORNER_RADIUS);
-        ctx.quadratic_curve_to(x, y, x + DICE_BOX_CORNER_RADIUS, y);
+        ctx.move_to(x + self.theme.border.dice_box_radius as f64, y);
+        ctx.line_to(x + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64, y);
+        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y, x + DICE_BOX_SIZE, y + self.theme.border.dice_box_radius as f64);
+        ctx.line_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64);
+        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE, x + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64, y + DICE_BOX_SIZE);
+        ctx.line_to(x + self.theme.border.dice_box_radius as f64, y + DICE_BOX_SIZE);
+        ctx.quadratic_curve_to(x, y + DICE_BOX_SIZE, x, y + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64);
+        ctx.line_to(x, y + self.theme.border.dice_box_radius as f64);
+        ctx.quadratic_curve_to(x, y, x + self.theme.border.dice_box_radius as f64, y);
         ctx.close_path();
         ctx.stroke();
 
@@ -353,7 +396,7 @@ impl CardGenerator {
         };
 
         // Center the cost number in the box
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", ACTION_COST_SIZE));
+        ctx.set_font(&self.font(self.theme.sizes.action_cost as f64));
         ctx.set_text_align("center");
         ctx.set_text_baseline("middle");
         
@@ -374,44 +417,60 @@ impl CardGenerator {
     }
 
     fn draw_prepared_box(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64) {
-        ctx.set_stroke_style(&"black".into());
-        ctx.set_line_width(DICE_BOX_BORDER_WIDTH);
+        ctx.set_stroke_style(&self.theme.text_color.as_str().into());
+        ctx.set_line_width(self.theme.border.dice_box_width as f64);
 
         // Draw rounded rectangle
         ctx.begin_path();
-        ctx.move_to(x + DICE_BOX_CORNER_RADIUS, y);
-        ctx.line_to(x + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS, y);
-        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y, x + DICE_BOX_SIZE, y + DICE_BOX_CORNER_RADIUS);
-        ctx.line_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS);
-        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE, x + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS, y + DICE_BOX_SIZE);
-        ctx.line_to(x + DICE_BOX_CORNER_RADIUS, y + DICE_BOX_SIZE);
-        ctx.quadratic_curve_to(x, y + DICE_BOX_SIZE, x, y + DICE_BOX_SIZE - DICE_BOX_CORNER_RADIUS);
-        ctx.line_to(x, y + DICE_BOX_CORNER_RADIUS);
-        ctx.quadratic_curve_to(x, y, x + DICE_BOX_CORNER_RADIUS, y);
+        ctx.move_to(x + self.theme.border.dice_box_radius as f64, y);
+        ctx.line_to(x + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64, y);
+        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y, x + DICE_BOX_SIZE, y + self.theme.border.dice_box_radius as f64);
+        ctx.line_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64);
+        ctx.quadratic_curve_to(x + DICE_BOX_SIZE, y + DICE_BOX_SIZE, x + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64, y + DICE_BOX_SIZE);
+        ctx.line_to(x + self.theme.border.dice_box_radius as f64, y + DICE_BOX_SIZE);
+        ctx.quadratic_curve_to(x, y + DICE_BOX_SIZE, x, y + DICE_BOX_SIZE - self.theme.border.dice_box_radius as f64);
+        ctx.line_to(x, y + self.theme.border.dice_box_radius as f64);
+        ctx.quadratic_curve_to(x, y, x + self.theme.border.dice_box_radius as f64, y);
         ctx.close_path();
         ctx.stroke();
     }
 
-    fn draw_special_abilities(&self, ctx: &CanvasRenderingContext2d, abilities: &Vec<String>) {
-        if abilities.is_empty() {
+    fn draw_special_abilities(&self, ctx: &CanvasRenderingContext2d, abilities: &[String], ability_display: AbilityDisplay) {
+        let lines = self.rules.abilities().format_abilities(abilities, ability_display);
+        if lines.is_empty() {
             return;
         }
 
         // Position above the damage profile numbers
         let abilities_start_y = CARD_HEIGHT - 60.0 - 20.0;
         
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", ACTION_TITLE_SIZE));
-        ctx.set_text_align("center"); // Center the text
-        
-        let abilities_text = abilities.join(", ");
-        let text_height = self.calculate_wrapped_text_height(ctx, &abilities_text, 
-            CARD_WIDTH - 2.0 * MARGIN, ACTION_TITLE_SIZE);
-        
-        // Draw the text aligned to the bottom and centered
-        self.draw_wrapped_text(ctx, &abilities_text, CARD_WIDTH/2.0, 
-            abilities_start_y - text_height,
-            CARD_WIDTH - 2.0 * MARGIN, 
-            ACTION_TITLE_SIZE);
+        // Just the names are centered, expanded abilities are stacked on the left with a smaller font
+        if ability_display == AbilityDisplay::Names {
+            ctx.set_font(&self.font(self.theme.sizes.action_title as f64));
+            ctx.set_text_align("center"); // Center the text
+
+            let text_height = self.calculate_wrapped_text_height(ctx, &lines[0],
+                CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.action_title as f64);
+
+            // Draw the text aligned to the bottom and centered
+            self.draw_wrapped_text(ctx, &lines[0], CARD_WIDTH/2.0,
+                abilities_start_y - text_height,
+                CARD_WIDTH - 2.0 * MARGIN,
+                self.theme.sizes.action_title as f64);
+        }
+        else {
+            ctx.set_font(&self.font(self.theme.sizes.text as f64));
+            ctx.set_text_align("left");
+
+            let text_height: f64 = lines.iter()
+                .map(|line| self.calculate_wrapped_text_height(ctx, line, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text as f64))
+                .sum();
+            let mut current_y = abilities_start_y - text_height;
+            for line in &lines {
+                self.draw_wrapped_text(ctx, line, MARGIN, current_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text as f64);
+                current_y += self.calculate_wrapped_text_height(ctx, line, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text as f64);
+            }
+        }
     }
 
     fn draw_damage_chart(&self, ctx: &CanvasRenderingContext2d, chart: &DamageChart) {
@@ -421,7 +480,7 @@ impl CardGenerator {
         const BAR_HEIGHT: f64 = 80.0; // Increased from 30.0 to 40.0
 
         // Draw numbers 1-6
-        ctx.set_font(&format!("bold {}px Arial", TEXT_SIZE));
+        ctx.set_font(&self.font(self.theme.sizes.text as f64));
         for i in 1..=6 {
             let x = MARGIN + (i - 1) as f64 * column_width + column_width/2.0 - 5.0;
             ctx.fill_text(&i.to_string(), x, chart_start_y - 20.0).unwrap(); // Moved numbers up by 20px
@@ -436,21 +495,17 @@ impl CardGenerator {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_damage_interval(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64, width: f64, color: &Color, text: &str, padding: f64, height: f64) {
-        let color_str = match color {
-            Color::Red => "#951c07",
-            Color::Yellow => "#ab7a1e",
-            Color::Green => "#5a7e26",
-        };
-        const EFFECT_SIZE: f64 = 42.0; // Increased from 30.0 to 40.0
+        let color_str = self.theme.damage_palette.color(color);
         
         // Draw the colored rectangle with padding
         ctx.set_fill_style(&color_str.into());
         ctx.fill_rect(x + padding, y + padding, width - 2.0 * padding, 200.0);
 
         // Draw the text centered both horizontally and vertically
-        ctx.set_fill_style(&"white".into());
-        ctx.set_font(&format!("bold {}px 'Trebuchet MS', sans-serif", EFFECT_SIZE));
+        ctx.set_fill_style(&self.theme.damage_text_color.as_str().into());
+        ctx.set_font(&self.font(self.theme.sizes.damage_effect as f64));
         ctx.set_text_align("center");
         ctx.set_text_baseline("middle");
         ctx.fill_text(&text.to_uppercase(), x + width/2.0, y + height/2.0 - 10.).unwrap();
@@ -458,16 +513,27 @@ impl CardGenerator {
 }
 
 impl Component for CardGenerator {
-    type Message = ();
+    type Message = Msg;
     type Properties = CardGeneratorProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self::new()
+    fn create(ctx: &Context<Self>) -> Self {
+        let (rules, rules_handle) = subscribe_rules(ctx, Msg::RulesChanged);
+        Self::new(rules, rules_handle)
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::RulesChanged(rules) => {
+                self.rules = rules;
+                true
+            }
+        }
     }
 
     fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        self.theme = ctx.props().theme.clone();
         self.initialize_canvas();
-        self.generate_card(&ctx.props().profile);
+        self.generate_card(&ctx.props().profile, ctx.props().ability_display);
     }
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
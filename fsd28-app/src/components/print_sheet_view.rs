@@ -0,0 +1,318 @@
+use yew::prelude::*;
+use wasm_bindgen::prelude::*;
+use fsd28_lib::models::profile::Profile;
+use fsd28_lib::{AbilityDisplay, CardTheme};
+use fsd28_lib::utils::pdf_card_generator::{create_pdf_cards_bytes, PdfCardOptions};
+use fsd28_lib::utils::png_card_generator::{
+    CardBack,
+    CardOptions,
+    collect_reference_entries,
+    encode_png,
+    render_card_back,
+    render_card_png,
+    render_reference_cards,
+};
+
+use crate::download::{download_bytes, png_data_url};
+use crate::rules::{subscribe_rules, SharedRules};
+use yew::context::ContextHandle;
+
+// For browser debugging
+use web_sys::console;
+
+// Handling the FileReader in the onload closure
+use std::rc::Rc;
+
+#[derive(Properties, PartialEq)]
+pub struct PrintSheetViewProps {
+    pub profiles: Vec<Profile>,
+    pub card_theme: CardTheme,
+}
+
+// Lays out all the cards at their physical size (2.5 x 3.5 inches), ready for the browser print dialog.
+pub struct PrintSheetView {
+    card_urls: Vec<String>,
+    back_url: Option<String>,
+    include_reference: bool,
+    include_back: bool,
+    back: CardBack,
+    card_options: CardOptions,
+    logo_input_ref: NodeRef,
+    _rules_handle: ContextHandle<SharedRules>,
+}
+
+pub enum Msg {
+    RulesChanged(SharedRules),
+    Print,
+    DownloadPdf,
+    ToggleReference,
+    ToggleBack,
+    ToggleNotes,
+    AbilityDisplayChanged(AbilityDisplay),
+    UpdateFactionName(String),
+    UpdateRosterName(String),
+    SelectLogo,
+    LogoLoaded(Vec<u8>),
+    RemoveLogo,
+    NoOp,
+}
+
+impl Component for PrintSheetView {
+    type Message = Msg;
+    type Properties = PrintSheetViewProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (rules, rules_handle) = subscribe_rules(ctx, Msg::RulesChanged);
+        let mut view = Self {
+            card_urls: Vec::new(),
+            back_url: None,
+            include_reference: true,
+            include_back: false,
+            back: CardBack::default(),
+            card_options: CardOptions {
+                abilities: rules.abilities().clone(),
+                theme: ctx.props().card_theme.clone(),
+                ..CardOptions::default()
+            },
+            logo_input_ref: NodeRef::default(),
+            _rules_handle: rules_handle,
+        };
+        view.render_cards(&ctx.props().profiles);
+        view
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _: &Self::Properties) -> bool {
+        self.card_options.theme = ctx.props().card_theme.clone();
+        self.render_cards(&ctx.props().profiles);
+        self.render_back();
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Print => {
+                if let Some(window) = web_sys::window() {
+                    if window.print().is_err() {
+                        console::log_1(&"Couldn't open the print dialog".to_string().into());
+                    }
+                }
+                false
+            }
+
+            Msg::DownloadPdf => {
+                let options = PdfCardOptions {
+                    card: self.card_options.clone(),
+                    include_reference: self.include_reference,
+                    back: self.include_back.then(|| self.back.clone()),
+                };
+                let result = create_pdf_cards_bytes(&ctx.props().profiles, &options)
+                    .map_err(|err| err.to_string())
+                    .and_then(|pdf| download_bytes(&pdf, "application/pdf", "cards.pdf"));
+                if let Err(err) = result {
+                    console::log_1(&format!("Error downloading the cards: {}", err).into());
+                }
+                false
+            }
+
+            Msg::ToggleReference => {
+                self.include_reference = !self.include_reference;
+                self.render_cards(&ctx.props().profiles);
+                true
+            }
+
+            Msg::ToggleBack => {
+                self.include_back = !self.include_back;
+                self.render_back();
+                true
+            }
+
+            Msg::ToggleNotes => {
+                self.card_options.show_notes = !self.card_options.show_notes;
+                self.render_cards(&ctx.props().profiles);
+                true
+            }
+
+            Msg::AbilityDisplayChanged(ability_display) => {
+                self.card_options.ability_display = ability_display;
+                self.render_cards(&ctx.props().profiles);
+                true
+            }
+
+            Msg::UpdateFactionName(name) => {
+                self.back.faction_name = name;
+                self.render_back();
+                true
+            }
+
+            Msg::UpdateRosterName(name) => {
+                self.back.roster_name = name;
+                self.render_back();
+                true
+            }
+
+            Msg::SelectLogo => {
+                if let Some(input) = self.logo_input_ref.cast::<web_sys::HtmlInputElement>() {
+                    input.click();
+                }
+                false
+            }
+
+            Msg::LogoLoaded(png) => {
+                self.back.logo_png = Some(png);
+                self.render_back();
+                true
+            }
+
+            Msg::RemoveLogo => {
+                self.back.logo_png = None;
+                self.render_back();
+                true
+            }
+
+            // The cards are drawn with the rules text of the new abilities
+            Msg::RulesChanged(rules) => {
+                self.card_options.abilities = rules.abilities().clone();
+                self.render_cards(&ctx.props().profiles);
+                true
+            }
+
+            Msg::NoOp => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="print-sheet-view">
+                <div class="print-sheet-toolbar no-print">
+                    <span>{ format!("{} cards", self.card_urls.len()) }</span>
+                    <label class="custom-checkbox">
+                        { "Reference cards" }
+                        <input type="checkbox" checked={self.include_reference} onclick={ctx.link().callback(|_| Msg::ToggleReference)} />
+                        <span class="checkmark"></span>
+                    </label>
+                    <label class="custom-checkbox">
+                        { "Card backs" }
+                        <input type="checkbox" checked={self.include_back} onclick={ctx.link().callback(|_| Msg::ToggleBack)} />
+                        <span class="checkmark"></span>
+                    </label>
+                    <label class="custom-checkbox">
+                        { "Notes and tags" }
+                        <input type="checkbox" checked={self.card_options.show_notes} onclick={ctx.link().callback(|_| Msg::ToggleNotes)} />
+                        <span class="checkmark"></span>
+                    </label>
+                    <select onchange={ctx.link().callback(|e: Event| {
+                        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                        Msg::AbilityDisplayChanged(select.value().parse().unwrap_or_default())
+                    })}>
+                        <option value="names" selected={self.card_options.ability_display == AbilityDisplay::Names}>{"Ability Names"}</option>
+                        <option value="rules" selected={self.card_options.ability_display == AbilityDisplay::RulesText}>{"Ability Rules Text"}</option>
+                        <option value="footnotes" selected={self.card_options.ability_display == AbilityDisplay::Footnotes}>{"Ability Footnotes"}</option>
+                    </select>
+                    <button onclick={ctx.link().callback(|_| Msg::Print)}>{ "Print" }</button>
+                    <button onclick={ctx.link().callback(|_| Msg::DownloadPdf)}>{ "Download PDF" }</button>
+                </div>
+                if self.include_back {
+                    { self.view_back_form(ctx) }
+                }
+                if self.card_urls.is_empty() {
+                    <div class="no-selection no-print">
+                        { "There are no cards to print" }
+                    </div>
+                } else {
+                    <div class="print-sheet">
+                        { for self.card_urls.iter().map(|url| html! {
+                            <img class="print-card" src={url.clone()} />
+                        }) }
+                    </div>
+                    if let Some(back_url) = &self.back_url {
+                        <div class="print-sheet print-sheet-backs">
+                            { for self.card_urls.iter().map(|_| html! {
+                                <img class="print-card" src={back_url.clone()} />
+                            }) }
+                        </div>
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+impl PrintSheetView {
+
+    fn render_cards(&mut self, profiles: &[Profile]) {
+        self.card_urls = profiles.iter()
+            .filter_map(|profile| match render_card_png(profile, &self.card_options) {
+                Ok(png) => Some(png_data_url(&png)),
+                Err(err) => {
+                    console::log_1(&format!("Error rendering the card of {}: {}", profile.name, err).into());
+                    None
+                }
+            })
+            .collect();
+
+        if self.include_reference {
+            let reference_cards = render_reference_cards(&collect_reference_entries(profiles, &self.card_options.abilities), &self.card_options);
+            self.card_urls.extend(reference_cards.iter().filter_map(|card| encode_png(card).ok().map(|png| png_data_url(&png))));
+        }
+    }
+
+    fn render_back(&mut self) {
+        self.back_url = if self.include_back {
+            encode_png(&render_card_back(&self.back, &self.card_options)).ok().map(|png| png_data_url(&png))
+        } else {
+            None
+        };
+    }
+
+    fn view_back_form(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="print-sheet-toolbar no-print">
+                <label class="label" for="faction-name">{"FACTION:"}</label>
+                <input type="text" id="faction-name"
+                    value={self.back.faction_name.clone()}
+                    oninput={ctx.link().callback(|e: InputEvent| {
+                        let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                        Msg::UpdateFactionName(input.value())
+                    })} />
+                <label class="label" for="roster-name">{"ROSTER:"}</label>
+                <input type="text" id="roster-name"
+                    value={self.back.roster_name.clone()}
+                    oninput={ctx.link().callback(|e: InputEvent| {
+                        let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                        Msg::UpdateRosterName(input.value())
+                    })} />
+                if self.back.logo_png.is_some() {
+                    <button onclick={ctx.link().callback(|_| Msg::RemoveLogo)}>{ "Remove Logo" }</button>
+                } else {
+                    <button onclick={ctx.link().callback(|_| Msg::SelectLogo)}>{ "Select Logo" }</button>
+                }
+
+                // Logo Selection Popup
+                <input type="file" accept="image/png" ref={self.logo_input_ref.clone()} style="display: none" onchange={
+                    let link = ctx.link().clone();
+                    ctx.link().callback(move |event: web_sys::Event| {
+                        let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                            let file_reader = Rc::new(web_sys::FileReader::new().unwrap());
+                            let file_reader_clone = file_reader.clone();
+                            let link_clone = link.clone();
+                            let onload_closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                                if let Ok(result) = file_reader_clone.result() {
+                                    link_clone.send_message(Msg::LogoLoaded(js_sys::Uint8Array::new(&result).to_vec()));
+                                }
+                            }) as Box<dyn FnMut(_)>);
+
+                            file_reader.add_event_listener_with_callback("load", onload_closure.as_ref().unchecked_ref()).unwrap();
+                            onload_closure.forget();
+                            file_reader.read_as_array_buffer(&file).unwrap();
+
+                            // Resetting the input file for the next time
+                            input.set_value("");
+                        }
+                        Msg::NoOp
+                    })
+                }/>
+            </div>
+        }
+    }
+}
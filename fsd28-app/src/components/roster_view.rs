@@ -1,69 +1,217 @@
 use yew::prelude::*;
 use fsd28_lib::models::profile::Profile;
+use fsd28_lib::{Roster, RosterEntry, SaveData};
+use fsd28_lib::utils::roster_exporter::{export_roster, RosterFormat};
+use fsd28_lib::utils::roster_importer::import_roster_text;
+
+use crate::download::download_bytes;
+use crate::share_link::copy_share_link;
+use crate::rules::{subscribe_rules, SharedRules};
+use yew::context::ContextHandle;
+
+// For browser debugging
+use web_sys::console;
 
 #[derive(Properties, PartialEq)]
 pub struct RosterViewProps {
     pub profiles: Vec<Profile>,
-    pub on_profiles_changed: Callback<Vec<Profile>>,
+    pub rosters: Vec<Roster>,
+    pub on_rosters_changed: Callback<Vec<Roster>>,
+    pub on_roster_imported: Callback<(Vec<Profile>, Roster)>,
 }
 
+// The rosters live in the App model, only the selection is kept here.
 pub struct RosterView {
-    selected_profiles: Vec<Profile>,
+    selected_roster: Option<String>, // Roster ID
+    export_format: RosterFormat,
+    import_text: String,
+    import_issues: Vec<String>, // Problems of the last import
+    rules: SharedRules, // For the exported weapons and the imported lists
+    _rules_handle: ContextHandle<SharedRules>,
 }
 
 pub enum Msg {
-    ToggleProfile(Profile),
+    RulesChanged(SharedRules),
+    SelectRoster(String),
+    CreateRoster,
+    DeleteSelectedRoster,
+
+    // Roster details
+    UpdateName(String),
+    UpdateFaction(String),
+    UpdatePointsLimit(String),
+    UpdateNotes(String),
+
+    // Roster entries, by profile ID
+    AddProfile(String),
+    SetCount(String, u32),
+    RemoveProfile(String),
+
+    ExportFormatChanged(RosterFormat),
     ExportList,
+    CopyShareLink,
+
+    UpdateImportText(String),
+    ImportList,
 }
 
 impl Component for RosterView {
     type Message = Msg;
     type Properties = RosterViewProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let (rules, rules_handle) = subscribe_rules(ctx, Msg::RulesChanged);
         Self {
-            selected_profiles: Vec::new(),
+            selected_roster: ctx.props().rosters.first().map(|roster| roster.id.clone()),
+            export_format: RosterFormat::Text,
+            import_text: String::new(),
+            import_issues: Vec::new(),
+            rules,
+            _rules_handle: rules_handle,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::ToggleProfile(profile) => {
-                if let Some(pos) = self.selected_profiles.iter().position(|p| p.name == profile.name) {
-                    self.selected_profiles.remove(pos);
-                } else {
-                    self.selected_profiles.push(profile);
+            Msg::RulesChanged(rules) => {
+                self.rules = rules;
+                false
+            }
+
+            Msg::SelectRoster(roster_id) => {
+                self.selected_roster = Some(roster_id);
+                true
+            }
+
+            Msg::CreateRoster => {
+                let mut rosters = ctx.props().rosters.clone();
+                let roster = Roster::new(format!("NEW_ROSTER_{}", rosters.len() + 1));
+                self.selected_roster = Some(roster.id.clone());
+                rosters.push(roster);
+                ctx.props().on_rosters_changed.emit(rosters);
+                true
+            }
+
+            Msg::DeleteSelectedRoster => {
+                if let Some(roster_id) = self.selected_roster.take() {
+                    let mut rosters = ctx.props().rosters.clone();
+                    rosters.retain(|roster| roster.id != roster_id);
+                    ctx.props().on_rosters_changed.emit(rosters);
                 }
                 true
             }
+
+            Msg::UpdateName(name) => self.update_selected_roster(ctx, |roster| roster.name = name),
+            Msg::UpdateFaction(faction) => self.update_selected_roster(ctx, |roster| roster.faction = faction),
+            Msg::UpdatePointsLimit(limit) => self.update_selected_roster(ctx, |roster| roster.points_limit = limit.trim().parse().ok()),
+            Msg::UpdateNotes(notes) => self.update_selected_roster(ctx, |roster| roster.notes = notes),
+            Msg::AddProfile(profile_id) => self.update_selected_roster(ctx, |roster| roster.add_profile(&profile_id)),
+            Msg::SetCount(profile_id, count) => self.update_selected_roster(ctx, |roster| roster.set_count(&profile_id, count)),
+            Msg::RemoveProfile(profile_id) => self.update_selected_roster(ctx, |roster| roster.remove_profile(&profile_id)),
+
+            Msg::ExportFormatChanged(format) => {
+                self.export_format = format;
+                false
+            }
+
             Msg::ExportList => {
-                // TODO: Implement export functionality
+                if let Some(roster) = self.selected(ctx) {
+                    let format = self.export_format;
+                    let file_name = format!("{}.{}", roster.name, format.extension());
+                    let result = export_roster(roster, &ctx.props().profiles, self.rules.weapons_config(), format)
+                        .map_err(|err| err.to_string())
+                        .and_then(|content| download_bytes(&content, format.mime_type(), &file_name));
+                    if let Err(err) = result {
+                        console::log_1(&format!("Error exporting the roster: {}", err).into());
+                    }
+                }
+                false
+            }
+
+            // Only the selected roster and its profiles are shared
+            Msg::CopyShareLink => {
+                if let Some(roster) = self.selected(ctx) {
+                    match copy_share_link(&SaveData::from_roster(roster, &ctx.props().profiles)) {
+                        Ok(link) => console::log_1(&format!("Share link copied: {}", link).into()),
+                        Err(err) => console::log_1(&format!("Error sharing the roster: {}", err).into()),
+                    }
+                }
+                false
+            }
+
+            Msg::UpdateImportText(text) => {
+                self.import_text = text;
+                false
+            }
+
+            Msg::ImportList => {
+                let import = import_roster_text(&self.import_text, self.rules.classes_config(), self.rules.weapons_config());
+                self.import_issues = import.issues.iter().map(|issue| issue.to_string()).collect();
+                if !import.profiles.is_empty() {
+                    self.selected_roster = Some(import.roster.id.clone());
+                    self.import_text.clear();
+                    ctx.props().on_roster_imported.emit((import.profiles, import.roster));
+                }
                 true
             }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let total_points: u32 = self.selected_profiles.iter()
-            .map(|p| p.get_final_profile().cost)
-            .sum();
-
         html! {
             <div class="roster-view">
                 <div class="left-bar">
                     <div class="profiles-list">
-                        { for ctx.props().profiles.iter().map(|profile| self.view_profile_button(profile, ctx.link())) }
+                        { for ctx.props().rosters.iter().map(|roster| self.view_roster_button(roster, ctx.link())) }
+                    </div>
+                    <div class="profile-list-buttons">
+                        <button onclick={ctx.link().callback(|_| Msg::CreateRoster)}>{"Create New"}</button>
+                        <button onclick={ctx.link().callback(|_| Msg::DeleteSelectedRoster)}>{"Delete Selected"}</button>
                     </div>
                 </div>
                 <div class="center-bar">
-                    <div class="total-points">
-                        { format!("Total Points: {}", total_points) }
-                    </div>
+                    if let Some(roster) = self.selected(ctx) {
+                        { self.view_roster(roster, ctx) }
+                    } else {
+                        <div class="no-selection">
+                            { "Select or create a roster" }
+                        </div>
+                    }
                 </div>
                 <div class="right-bar">
+                    if self.selected(ctx).is_some() {
+                        <div class="profiles-list">
+                            { for ctx.props().profiles.iter().map(|profile| self.view_profile_button(profile, ctx.link())) }
+                        </div>
+                    }
+                    <select onchange={ctx.link().callback(|e: Event| {
+                        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                        Msg::ExportFormatChanged(select.value().parse().unwrap_or(RosterFormat::Text))
+                    })}>
+                        <option value="text" selected={self.export_format == RosterFormat::Text}>{"Text"}</option>
+                        <option value="markdown" selected={self.export_format == RosterFormat::Markdown}>{"Markdown"}</option>
+                        <option value="pdf" selected={self.export_format == RosterFormat::Pdf}>{"PDF"}</option>
+                    </select>
                     <button onclick={ctx.link().callback(|_| Msg::ExportList)}>
                         { "Export List" }
                     </button>
+                    <button onclick={ctx.link().callback(|_| Msg::CopyShareLink)}>
+                        { "Copy Share Link" }
+                    </button>
+                    <textarea class="roster-import" placeholder="2x Enforcer - Assault Rifle: Suppression Burst"
+                        value={self.import_text.clone()}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+                            Msg::UpdateImportText(input.value())
+                        })} />
+                    <button onclick={ctx.link().callback(|_| Msg::ImportList)}>
+                        { "Import List" }
+                    </button>
+                    if !self.import_issues.is_empty() {
+                        <ul class="roster-violations">
+                            { for self.import_issues.iter().map(|issue| html! { <li>{ issue }</li> }) }
+                        </ul>
+                    }
                 </div>
             </div>
         }
@@ -71,16 +219,128 @@ impl Component for RosterView {
 }
 
 impl RosterView {
+
+    fn selected<'a>(&self, ctx: &'a Context<Self>) -> Option<&'a Roster> {
+        let roster_id = self.selected_roster.as_ref()?;
+        ctx.props().rosters.iter().find(|roster| &roster.id == roster_id)
+    }
+
+    // Applies the change to a copy of the selected roster and sends all the rosters up.
+    fn update_selected_roster(&self, ctx: &Context<Self>, change: impl FnOnce(&mut Roster)) -> bool {
+        let mut rosters = ctx.props().rosters.clone();
+        if let Some(roster) = rosters.iter_mut().find(|roster| Some(&roster.id) == self.selected_roster.as_ref()) {
+            change(roster);
+            ctx.props().on_rosters_changed.emit(rosters);
+        }
+        false
+    }
+
+    fn view_roster_button(&self, roster: &Roster, link: &yew::html::Scope<Self>) -> Html {
+        let is_selected = self.selected_roster.as_ref() == Some(&roster.id);
+        let roster_id = roster.id.clone();
+        html! {
+            <button
+                class={classes!("button", is_selected.then_some("selected"))}
+                onclick={link.callback(move |_| Msg::SelectRoster(roster_id.clone()))} >
+                { &roster.name }
+            </button>
+        }
+    }
+
     fn view_profile_button(&self, profile: &Profile, link: &yew::html::Scope<Self>) -> Html {
-        let is_selected = self.selected_profiles.iter().any(|p| p.name == profile.name);
-        let profile_clone = profile.clone();
+        let profile_id = profile.id.clone();
         let final_profile = profile.get_final_profile();
         html! {
             <button
-                class={classes!("button", is_selected.then_some("selected"))}
-                onclick={link.callback(move |_| Msg::ToggleProfile(profile_clone.clone()))} >
+                class="button"
+                title="Add to the roster"
+                onclick={link.callback(move |_| Msg::AddProfile(profile_id.clone()))} >
                 { format!("{} ({} points)", &profile.name, &final_profile.cost) }
             </button>
         }
     }
+
+    fn view_roster(&self, roster: &Roster, ctx: &Context<Self>) -> Html {
+        let profiles = &ctx.props().profiles;
+        let rules = self.rules.roster_rules();
+        let violations = roster.validate(profiles, rules);
+        let total_points = roster.total_cost(profiles);
+        let total_text = match roster.effective_points_limit(rules) {
+            Some(limit) => format!("Total Points: {} / {}", total_points, limit),
+            None => format!("Total Points: {}", total_points),
+        };
+
+        html! {
+            <div class="roster-details">
+                <div class="form-group">
+                    <label class="label" for="roster-name">{"NAME:"}</label>
+                    <input type="text" id="roster-name" value={roster.name.clone()}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                            Msg::UpdateName(input.value())
+                        })} />
+                    <label class="label" for="roster-faction">{"FACTION:"}</label>
+                    <input type="text" id="roster-faction" value={roster.faction.clone()}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                            Msg::UpdateFaction(input.value())
+                        })} />
+                    <label class="label" for="roster-limit">{"POINTS LIMIT:"}</label>
+                    <input type="number" id="roster-limit" min="0"
+                        placeholder={rules.points_limit.map(|limit| limit.to_string())}
+                        value={roster.points_limit.map_or(String::new(), |limit| limit.to_string())}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                            Msg::UpdatePointsLimit(input.value())
+                        })} />
+                </div>
+
+                <div class="roster-entries">
+                    if roster.entries.is_empty() {
+                        <div>{ "Click on the profiles on the right to add them to the roster" }</div>
+                    }
+                    { for roster.resolve(profiles).into_iter().map(|(entry, profile)| self.view_entry(entry, profile, ctx.link())) }
+                </div>
+
+                <div class={classes!("total-points", (!violations.is_empty()).then_some("invalid"))}>
+                    { total_text }
+                    { format!(" - {} models", roster.models_count()) }
+                </div>
+                if !violations.is_empty() {
+                    <ul class="roster-violations">
+                        { for violations.iter().map(|violation| html! { <li>{ violation }</li> }) }
+                    </ul>
+                }
+
+                <label class="label" for="roster-notes">{"NOTES:"}</label>
+                <textarea id="roster-notes" value={roster.notes.clone()}
+                    oninput={ctx.link().callback(|e: InputEvent| {
+                        let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+                        Msg::UpdateNotes(input.value())
+                    })} />
+            </div>
+        }
+    }
+
+    fn view_entry(&self, entry: &RosterEntry, profile: Option<&Profile>, link: &yew::html::Scope<Self>) -> Html {
+        let (name, cost) = match profile {
+            Some(profile) => (profile.name.clone(), Roster::entry_cost(entry, profile)),
+            None => ("(deleted profile)".to_string(), 0),
+        };
+        let count = entry.count;
+        let decrease_id = entry.profile_id.clone();
+        let increase_id = entry.profile_id.clone();
+        let remove_id = entry.profile_id.clone();
+
+        html! {
+            <div class="roster-entry">
+                <button onclick={link.callback(move |_| Msg::SetCount(decrease_id.clone(), count - 1))}>{ "-" }</button>
+                <span class="roster-entry-count">{ format!("{}x", count) }</span>
+                <button onclick={link.callback(move |_| Msg::SetCount(increase_id.clone(), count + 1))}>{ "+" }</button>
+                <span class="roster-entry-name">{ name }</span>
+                <span class="roster-entry-cost">{ format!("{} points", cost) }</span>
+                <button onclick={link.callback(move |_| Msg::RemoveProfile(remove_id.clone()))}>{ "Remove" }</button>
+            </div>
+        }
+    }
 }
@@ -0,0 +1,670 @@
+use yew::prelude::*;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use fsd28_lib::models::action::{Action, ActionCost};
+use fsd28_lib::models::characteristics::Characteristics;
+use fsd28_lib::models::class::Tier;
+use fsd28_lib::models::damage_chart::{Color, DamageChart};
+use fsd28_lib::models::dice_value::{DiceValue, DIE_SHAPES};
+use fsd28_lib::models::profile::MAX_ACTIONS;
+use fsd28_lib::utils::unique_name::unique_name;
+use fsd28_lib::{CardTheme, Class, Modifier, Profile, Ruleset, Weapon, WeaponOption};
+
+use crate::components::card_generator::CardGenerator;
+use crate::download::download_bytes;
+
+#[derive(Properties, PartialEq)]
+pub struct RulesetEditorProps {
+    pub ruleset: Ruleset,
+    pub on_ruleset_changed: Callback<Ruleset>,
+    pub on_ruleset_applied: Callback<Option<Ruleset>>, // None for the bundled one
+    pub in_use: bool, // The edited ruleset is the one the profiles are built with
+    pub card_theme: CardTheme,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Section {
+    Classes,
+    Weapons,
+}
+
+pub enum ClassField {
+    Name(String),
+    Tier(Tier),
+    Cost(u32),
+    Cmd(u32),
+    Def(u32),
+    Move(u32),
+    SaveShape(u32),
+    SaveArmor(u32),
+    ShootShape(u32),
+    ShootNumber(u32),
+    MeleeShape(u32),
+    MeleeNumber(u32),
+    Abilities(String), // Comma separated
+}
+
+pub enum IntervalField {
+    Faces(u32),
+    Color(Color),
+    Text(String),
+}
+
+pub enum ModifierField {
+    Id(String),
+    Points(u32),
+    Effects(String), // As JSON
+}
+
+pub enum OptionField {
+    Name(String),
+    Text(String),
+    Points(u32),
+    Slot(bool),
+    Base(bool),
+    Ranges(Tier, String), // As "1-3, 5" or "FREE"
+}
+
+// Editor for homebrew classes and weapons, exported as a ruleset file.
+pub struct RulesetEditor {
+    section: Section,
+    selected_class: usize,
+    selected_weapon: usize,
+    error: Option<String>, // A field that couldn't be read, or a failed export
+}
+
+pub enum Msg {
+    SelectSection(Section),
+    Select(usize),
+    Add,
+    Duplicate,
+    Remove,
+    EditClass(ClassField),
+    AddInterval,
+    RemoveInterval(usize),
+    EditInterval(usize, IntervalField),
+    AddModifier,
+    RemoveModifier(usize),
+    EditModifier(usize, ModifierField),
+    RenameWeapon(String),
+    AddOption,
+    RemoveOption(usize),
+    EditOption(usize, OptionField),
+    ResetToBundled,
+    Apply,
+    UseBundled,
+    Export,
+}
+
+impl Component for RulesetEditor {
+    type Message = Msg;
+    type Properties = RulesetEditorProps;
+
+    fn create(_: &Context<Self>) -> Self {
+        RulesetEditor { section: Section::Classes, selected_class: 0, selected_weapon: 0, error: None }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let mut ruleset = ctx.props().ruleset.clone();
+        self.error = None;
+        match msg {
+            Msg::SelectSection(section) => {
+                self.section = section;
+                return true;
+            },
+
+            Msg::Select(index) => {
+                match self.section {
+                    Section::Classes => self.selected_class = index,
+                    Section::Weapons => self.selected_weapon = index,
+                }
+                return true;
+            },
+
+            Msg::Add => match self.section {
+                Section::Classes => {
+                    ruleset.classes.push(new_class(&ruleset));
+                    self.selected_class = ruleset.classes.len() - 1;
+                },
+                Section::Weapons => {
+                    ruleset.weapons.push(Weapon { name: unique_name("New Weapon", |candidate| ruleset.weapons.iter().any(|weapon| weapon.name == candidate)), options: Vec::new() });
+                    self.selected_weapon = ruleset.weapons.len() - 1;
+                },
+            },
+
+            Msg::Duplicate => match self.section {
+                Section::Classes => {
+                    if let Some(class) = ruleset.classes.get(self.selected_class).cloned() {
+                        let name = unique_name(&class.name, |candidate| ruleset.classes.iter().any(|other| other.name == candidate));
+                        ruleset.classes.push(Class { name, ..class });
+                        self.selected_class = ruleset.classes.len() - 1;
+                    }
+                },
+                Section::Weapons => {
+                    if let Some(weapon) = ruleset.weapons.get(self.selected_weapon).cloned() {
+                        let name = unique_name(&weapon.name, |candidate| ruleset.weapons.iter().any(|other| other.name == candidate));
+                        ruleset.weapons.push(Weapon { name, ..weapon });
+                        self.selected_weapon = ruleset.weapons.len() - 1;
+                    }
+                },
+            },
+
+            Msg::Remove => match self.section {
+                Section::Classes => {
+                    if self.selected_class < ruleset.classes.len() {
+                        ruleset.classes.remove(self.selected_class);
+                        self.selected_class = self.selected_class.saturating_sub(1);
+                    }
+                },
+                Section::Weapons => {
+                    if self.selected_weapon < ruleset.weapons.len() {
+                        ruleset.weapons.remove(self.selected_weapon);
+                        self.selected_weapon = self.selected_weapon.saturating_sub(1);
+                    }
+                },
+            },
+
+            Msg::EditClass(field) => {
+                let Some(class) = ruleset.classes.get_mut(self.selected_class) else { return false };
+                let stats = &mut class.characteristics;
+                match field {
+                    ClassField::Name(name) => class.name = name,
+                    ClassField::Tier(tier) => class.tier = tier,
+                    ClassField::Cost(cost) => class.cost = cost,
+                    ClassField::Cmd(value) => stats.stat_cmd = value,
+                    ClassField::Def(value) => stats.stat_def = value,
+                    ClassField::Move(value) => stats.stat_move = value,
+                    ClassField::SaveShape(shape) => stats.stat_save = armor_die(shape, stats.stat_save.armor()),
+                    ClassField::SaveArmor(armor) => stats.stat_save = armor_die(stats.stat_save.shape(), armor),
+                    ClassField::ShootShape(shape) => stats.stat_shoot = dice(shape, stats.stat_shoot.number()),
+                    ClassField::ShootNumber(number) => stats.stat_shoot = dice(stats.stat_shoot.shape(), number),
+                    ClassField::MeleeShape(shape) => stats.stat_melee = dice(shape, stats.stat_melee.number()),
+                    ClassField::MeleeNumber(number) => stats.stat_melee = dice(stats.stat_melee.shape(), number),
+                    ClassField::Abilities(text) => {
+                        class.special_abilities = text.split(',')
+                            .map(|ability| ability.trim().to_string())
+                            .filter(|ability| !ability.is_empty())
+                            .collect();
+                    },
+                }
+            },
+
+            Msg::AddInterval => {
+                let Some(class) = ruleset.classes.get_mut(self.selected_class) else { return false };
+                class.damage_profile.intervals.push((1, Color::Green, "PIN".to_string()));
+            },
+
+            Msg::RemoveInterval(index) => {
+                let Some(class) = ruleset.classes.get_mut(self.selected_class) else { return false };
+                if index < class.damage_profile.intervals.len() {
+                    class.damage_profile.intervals.remove(index);
+                }
+            },
+
+            Msg::EditInterval(index, field) => {
+                let Some(interval) = ruleset.classes.get_mut(self.selected_class)
+                    .and_then(|class| class.damage_profile.intervals.get_mut(index)) else { return false };
+                match field {
+                    IntervalField::Faces(faces) => interval.0 = faces,
+                    IntervalField::Color(color) => interval.1 = color,
+                    IntervalField::Text(text) => interval.2 = text,
+                }
+            },
+
+            Msg::AddModifier => {
+                let Some(class) = ruleset.classes.get_mut(self.selected_class) else { return false };
+                let id = unique_name("New Modifier", |candidate| class.modifiers.iter().any(|modifier| modifier.id == candidate));
+                class.modifiers.push(Modifier { id, points: 1, effects: serde_json::json!({}) });
+            },
+
+            Msg::RemoveModifier(index) => {
+                let Some(class) = ruleset.classes.get_mut(self.selected_class) else { return false };
+                if index < class.modifiers.len() {
+                    class.modifiers.remove(index);
+                }
+            },
+
+            Msg::EditModifier(index, field) => {
+                let Some(modifier) = ruleset.classes.get_mut(self.selected_class)
+                    .and_then(|class| class.modifiers.get_mut(index)) else { return false };
+                match field {
+                    ModifierField::Id(id) => modifier.id = id,
+                    ModifierField::Points(points) => modifier.points = points,
+                    ModifierField::Effects(text) => match serde_json::from_str(&text) {
+                        Ok(effects) => modifier.effects = effects,
+                        Err(err) => {
+                            self.error = Some(format!("The effects of {} are not valid JSON: {}", modifier.id, err));
+                            return true;
+                        },
+                    },
+                }
+            },
+
+            Msg::RenameWeapon(name) => {
+                let Some(weapon) = ruleset.weapons.get_mut(self.selected_weapon) else { return false };
+                weapon.name = name;
+            },
+
+            Msg::AddOption => {
+                let Some(weapon) = ruleset.weapons.get_mut(self.selected_weapon) else { return false };
+                let name = unique_name("New Action", |candidate| weapon.options.iter().any(|option| option.action.name == candidate));
+                weapon.options.push(WeaponOption { action: new_action(name), is_base: weapon.options.is_empty() });
+            },
+
+            Msg::RemoveOption(index) => {
+                let Some(weapon) = ruleset.weapons.get_mut(self.selected_weapon) else { return false };
+                if index < weapon.options.len() {
+                    weapon.options.remove(index);
+                }
+            },
+
+            Msg::EditOption(index, field) => {
+                let Some(option) = ruleset.weapons.get_mut(self.selected_weapon)
+                    .and_then(|weapon| weapon.options.get_mut(index)) else { return false };
+                let action = &mut option.action;
+                match field {
+                    OptionField::Name(name) => action.name = name,
+                    OptionField::Text(text) => action.text = text,
+                    OptionField::Points(points) => action.points = points,
+                    OptionField::Slot(slot) => action.slot = slot,
+                    OptionField::Base(is_base) => option.is_base = is_base,
+                    OptionField::Ranges(tier, text) => match parse_ranges(&text) {
+                        Ok(ranges) => match tier {
+                            Tier::Goon => action.cost.goon = ranges,
+                            Tier::Char => action.cost.char = ranges,
+                            Tier::Hero => action.cost.hero = ranges,
+                        },
+                        Err(err) => {
+                            self.error = Some(format!("{} ({}): {}", action.name, tier, err));
+                            return true;
+                        },
+                    },
+                }
+            },
+
+            Msg::ResetToBundled => {
+                ruleset = Ruleset::bundled();
+                self.selected_class = 0;
+                self.selected_weapon = 0;
+            },
+
+            Msg::Apply => {
+                if !ruleset.validate().is_empty() {
+                    self.error = Some("Fix the problems listed before using the ruleset".to_string());
+                    return true;
+                }
+                ctx.props().on_ruleset_applied.emit(Some(ruleset));
+                return true;
+            },
+
+            Msg::UseBundled => {
+                ctx.props().on_ruleset_applied.emit(None);
+                return true;
+            },
+
+            Msg::Export => {
+                if !ruleset.validate().is_empty() {
+                    self.error = Some("Fix the problems listed before exporting".to_string());
+                    return true;
+                }
+                let result = ruleset.to_json()
+                    .map_err(|err| err.to_string())
+                    .and_then(|json| download_bytes(json.as_bytes(), "application/json", "ruleset.json"));
+                if let Err(err) = result {
+                    self.error = Some(format!("Error exporting the ruleset: {}", err));
+                }
+                return true;
+            },
+        }
+        ctx.props().on_ruleset_changed.emit(ruleset);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let ruleset = &ctx.props().ruleset;
+        let link = ctx.link();
+        let names: Vec<String> = match self.section {
+            Section::Classes => ruleset.classes.iter().map(|class| class.name.clone()).collect(),
+            Section::Weapons => ruleset.weapons.iter().map(|weapon| weapon.name.clone()).collect(),
+        };
+        let selected = match self.section {
+            Section::Classes => self.selected_class,
+            Section::Weapons => self.selected_weapon,
+        };
+        let problems = ruleset.validate();
+
+        html! {
+            <div class="units-view">
+                <div class="left-bar">
+                    <select class="editor-section" onchange={link.callback(|e: Event| {
+                        let select: HtmlSelectElement = e.target_unchecked_into();
+                        Msg::SelectSection(if select.value() == "weapons" { Section::Weapons } else { Section::Classes })
+                    })}>
+                        <option value="classes" selected={self.section == Section::Classes}>{"Classes"}</option>
+                        <option value="weapons" selected={self.section == Section::Weapons}>{"Weapons"}</option>
+                    </select>
+                    <div class="profiles-list">
+                        { for names.iter().enumerate().map(|(index, name)| html! {
+                            <button class={if index == selected { "selected" } else { "" }} onclick={link.callback(move |_| Msg::Select(index))}>
+                                { if name.is_empty() { "(no name)" } else { name } }
+                            </button>
+                        }) }
+                    </div>
+                    <div class="profile-list-buttons">
+                        <button onclick={link.callback(|_| Msg::Add)}>{"Create New"}</button>
+                        <button onclick={link.callback(|_| Msg::Duplicate)}>{"Duplicate"}</button>
+                        <button onclick={link.callback(|_| Msg::Remove)}>{"Delete Selected"}</button>
+                        <button onclick={link.callback(|_| Msg::ResetToBundled)}>{"Reset to Bundled"}</button>
+                        <button onclick={link.callback(|_| Msg::Export)}>{"Export Ruleset"}</button>
+                        <button title="Build the profiles with these classes and weapons" onclick={link.callback(|_| Msg::Apply)}>{"Use This Ruleset"}</button>
+                        <button onclick={link.callback(|_| Msg::UseBundled)}>{"Use Bundled Ruleset"}</button>
+                    </div>
+                    if ctx.props().in_use {
+                        <div class="editor-in-use">{ "In use for the profiles" }</div>
+                    }
+                </div>
+                <div class="center-bar">
+                    {
+                        match self.section {
+                            Section::Classes => match ruleset.classes.get(self.selected_class) {
+                                Some(class) => self.view_class_form(class, link),
+                                None => html! { <div class="no-selection">{ "Select or create a class" }</div> },
+                            },
+                            Section::Weapons => match ruleset.weapons.get(self.selected_weapon) {
+                                Some(weapon) => self.view_weapon_form(weapon, link),
+                                None => html! { <div class="no-selection">{ "Select or create a weapon" }</div> },
+                            },
+                        }
+                    }
+                </div>
+                <div class="right-bar">
+                    if let Some(error) = &self.error {
+                        <div class="editor-error">{ error }</div>
+                    }
+                    if !problems.is_empty() {
+                        <ul class="editor-problems">
+                            { for problems.iter().map(|problem| html! { <li>{ problem }</li> }) }
+                        </ul>
+                    }
+                    if let Some(profile) = self.sample_profile(ruleset) {
+                        <CardGenerator profile={profile} theme={ctx.props().card_theme.clone()} />
+                    }
+                </div>
+            </div>
+        }
+    }
+}
+
+impl RulesetEditor {
+
+    // A profile showing the selected class, or the selected weapon on the first class.
+    fn sample_profile(&self, ruleset: &Ruleset) -> Option<Profile> {
+        match self.section {
+            Section::Classes => {
+                let class = ruleset.classes.get(self.selected_class)?;
+                Some(Profile::new("Sample".to_string(), class.clone()).get_final_profile())
+            },
+            Section::Weapons => {
+                let weapon = ruleset.weapons.get(self.selected_weapon)?;
+                let class = ruleset.classes.first()?;
+                let mut profile = Profile::new(format!("Sample {}", weapon.name), class.clone());
+                let mut options: Vec<&WeaponOption> = weapon.options.iter().filter(|option| option.is_base).collect();
+                options.extend(weapon.options.iter().filter(|option| !option.is_base));
+                profile.actions = options.into_iter().take(MAX_ACTIONS).map(|option| option.action.clone()).collect();
+                Some(profile.get_final_profile())
+            },
+        }
+    }
+
+    fn view_class_form(&self, class: &Class, link: &yew::html::Scope<Self>) -> Html {
+        let stats = &class.characteristics;
+        let tier = class.tier.clone();
+        html! {
+            <div class="edit-form ruleset-editor">
+                <div class="form-group">
+                    <label class="label">{"NAME:"}</label>
+                    { text_input(class.name.clone(), link.callback(|value| Msg::EditClass(ClassField::Name(value)))) }
+                    <label class="label">{"TIER:"}</label>
+                    <select onchange={link.callback(|e: Event| {
+                        let select: HtmlSelectElement = e.target_unchecked_into();
+                        Msg::EditClass(ClassField::Tier(parse_tier(&select.value())))
+                    })}>
+                        { for [Tier::Goon, Tier::Char, Tier::Hero].into_iter().map(|option| html! {
+                            <option value={option.to_string()} selected={option == tier}>{ option.to_string() }</option>
+                        }) }
+                    </select>
+                    <label class="label">{"COST:"}</label>
+                    { number_input(class.cost, link.callback(|value| Msg::EditClass(ClassField::Cost(value)))) }
+                </div>
+
+                <div class="form-group editor-stats">
+                    <label class="label">{"CMD:"}</label>
+                    { number_input(stats.stat_cmd, link.callback(|value| Msg::EditClass(ClassField::Cmd(value)))) }
+                    <label class="label">{"DEF:"}</label>
+                    { number_input(stats.stat_def, link.callback(|value| Msg::EditClass(ClassField::Def(value)))) }
+                    <label class="label">{"MOVE:"}</label>
+                    { number_input(stats.stat_move, link.callback(|value| Msg::EditClass(ClassField::Move(value)))) }
+                    <label class="label">{"SAVE:"}</label>
+                    { shape_select(stats.stat_save.shape(), link.callback(|value| Msg::EditClass(ClassField::SaveShape(value)))) }
+                    <span>{"armor"}</span>
+                    { number_input(stats.stat_save.armor(), link.callback(|value| Msg::EditClass(ClassField::SaveArmor(value)))) }
+                    <label class="label">{"SHOOT:"}</label>
+                    { number_input(stats.stat_shoot.number(), link.callback(|value| Msg::EditClass(ClassField::ShootNumber(value)))) }
+                    { shape_select(stats.stat_shoot.shape(), link.callback(|value| Msg::EditClass(ClassField::ShootShape(value)))) }
+                    <label class="label">{"MELEE:"}</label>
+                    { number_input(stats.stat_melee.number(), link.callback(|value| Msg::EditClass(ClassField::MeleeNumber(value)))) }
+                    { shape_select(stats.stat_melee.shape(), link.callback(|value| Msg::EditClass(ClassField::MeleeShape(value)))) }
+                </div>
+
+                <div class="form-group">
+                    <label class="label">{"ABILITIES:"}</label>
+                    <input type="text" placeholder="Fast, Terrain Expert"
+                        value={class.special_abilities.join(", ")}
+                        onchange={link.callback(|e: Event| {
+                            let input: HtmlInputElement = e.target_unchecked_into();
+                            Msg::EditClass(ClassField::Abilities(input.value()))
+                        })} />
+                </div>
+
+                <div class="form-group">
+                    <label class="label">{"DAMAGE CHART:"}</label>
+                    { for class.damage_profile.intervals.iter().enumerate().map(|(index, interval)| html! {
+                        <div class="editor-row">
+                            { number_input(interval.0, link.callback(move |value| Msg::EditInterval(index, IntervalField::Faces(value)))) }
+                            <select onchange={link.callback(move |e: Event| {
+                                let select: HtmlSelectElement = e.target_unchecked_into();
+                                Msg::EditInterval(index, IntervalField::Color(parse_color(&select.value())))
+                            })}>
+                                { for [Color::Red, Color::Yellow, Color::Green].into_iter().map(|color| {
+                                    let name = color_name(&color);
+                                    html! { <option value={name} selected={color == interval.1}>{ name }</option> }
+                                }) }
+                            </select>
+                            { text_input(interval.2.clone(), link.callback(move |value| Msg::EditInterval(index, IntervalField::Text(value)))) }
+                            <button onclick={link.callback(move |_| Msg::RemoveInterval(index))}>{"Remove"}</button>
+                        </div>
+                    }) }
+                    <button onclick={link.callback(|_| Msg::AddInterval)}>{"Add Interval"}</button>
+                </div>
+
+                <div class="form-group">
+                    <label class="label">{"MODIFIERS:"}</label>
+                    { for class.modifiers.iter().enumerate().map(|(index, modifier)| html! {
+                        <div class="editor-row">
+                            { text_input(modifier.id.clone(), link.callback(move |value| Msg::EditModifier(index, ModifierField::Id(value)))) }
+                            { number_input(modifier.points, link.callback(move |value| Msg::EditModifier(index, ModifierField::Points(value)))) }
+                            <textarea class="editor-effects" title="Effects, e.g. {\"ability\": \"Terrain Expert\"}"
+                                value={modifier.effects.to_string()}
+                                onchange={link.callback(move |e: Event| {
+                                    let area: HtmlTextAreaElement = e.target_unchecked_into();
+                                    Msg::EditModifier(index, ModifierField::Effects(area.value()))
+                                })} />
+                            <button onclick={link.callback(move |_| Msg::RemoveModifier(index))}>{"Remove"}</button>
+                        </div>
+                    }) }
+                    <button onclick={link.callback(|_| Msg::AddModifier)}>{"Add Modifier"}</button>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_weapon_form(&self, weapon: &Weapon, link: &yew::html::Scope<Self>) -> Html {
+        html! {
+            <div class="edit-form ruleset-editor">
+                <div class="form-group">
+                    <label class="label">{"NAME:"}</label>
+                    { text_input(weapon.name.clone(), link.callback(Msg::RenameWeapon)) }
+                </div>
+                { for weapon.options.iter().enumerate().map(|(index, option)| {
+                    let action = &option.action;
+                    html! {
+                        <div class="form-group editor-option">
+                            <div class="editor-row">
+                                { text_input(action.name.clone(), link.callback(move |value| Msg::EditOption(index, OptionField::Name(value)))) }
+                                <label>{"points"}</label>
+                                { number_input(action.points, link.callback(move |value| Msg::EditOption(index, OptionField::Points(value)))) }
+                                <label>
+                                    <input type="checkbox" checked={option.is_base} onchange={link.callback(move |e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        Msg::EditOption(index, OptionField::Base(input.checked()))
+                                    })} />
+                                    {"base"}
+                                </label>
+                                <label>
+                                    <input type="checkbox" checked={action.slot} onchange={link.callback(move |e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        Msg::EditOption(index, OptionField::Slot(input.checked()))
+                                    })} />
+                                    {"slot"}
+                                </label>
+                                <button onclick={link.callback(move |_| Msg::RemoveOption(index))}>{"Remove"}</button>
+                            </div>
+                            { text_input(action.text.clone(), link.callback(move |value| Msg::EditOption(index, OptionField::Text(value)))) }
+                            <div class="editor-row">
+                                { for [Tier::Goon, Tier::Char, Tier::Hero].into_iter().map(|tier| {
+                                    let ranges = action.get_action_cost_str(&tier).join(", ");
+                                    let label = tier.to_string();
+                                    html! {
+                                        <>
+                                            <label>{ label }</label>
+                                            <input type="text" placeholder="1-3, 5 or FREE" value={ranges} onchange={link.callback(move |e: Event| {
+                                                let input: HtmlInputElement = e.target_unchecked_into();
+                                                Msg::EditOption(index, OptionField::Ranges(tier.clone(), input.value()))
+                                            })} />
+                                        </>
+                                    }
+                                }) }
+                            </div>
+                        </div>
+                    }
+                }) }
+                <button onclick={link.callback(|_| Msg::AddOption)}>{"Add Action"}</button>
+            </div>
+        }
+    }
+}
+
+fn text_input(value: String, callback: Callback<String>) -> Html {
+    html! {
+        <input type="text" value={value} oninput={callback.reform(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            input.value()
+        })} />
+    }
+}
+
+fn number_input(value: u32, callback: Callback<u32>) -> Html {
+    html! {
+        <input type="number" min="0" class="editor-number" value={value.to_string()} oninput={callback.reform(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            input.value().parse().unwrap_or(0)
+        })} />
+    }
+}
+
+// Only the existing dice can be picked.
+fn shape_select(value: u32, callback: Callback<u32>) -> Html {
+    html! {
+        <select onchange={callback.reform(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            select.value().parse().unwrap_or(6)
+        })}>
+            { for DIE_SHAPES.iter().map(|shape| html! {
+                <option value={shape.to_string()} selected={*shape == value}>{ format!("d{}", shape) }</option>
+            }) }
+        </select>
+    }
+}
+
+// The shapes come from the selects, always valid.
+fn dice(shape: u32, number: u32) -> DiceValue {
+    DiceValue::new(shape, number).unwrap_or(DiceValue::new(6, number).unwrap())
+}
+
+fn armor_die(shape: u32, armor: u32) -> DiceValue {
+    DiceValue::new_armor(shape, armor).unwrap_or(DiceValue::new_armor(6, armor).unwrap())
+}
+
+fn parse_tier(value: &str) -> Tier {
+    match value {
+        "Char" => Tier::Char,
+        "Hero" => Tier::Hero,
+        _ => Tier::Goon,
+    }
+}
+
+fn parse_color(value: &str) -> Color {
+    match value {
+        "Red" => Color::Red,
+        "Yellow" => Color::Yellow,
+        _ => Color::Green,
+    }
+}
+
+fn color_name(color: &Color) -> &'static str {
+    match color {
+        Color::Red => "Red",
+        Color::Yellow => "Yellow",
+        Color::Green => "Green",
+    }
+}
+
+// "1-3, 5" or "FREE", the way the cards show the activation ranges.
+fn parse_ranges(text: &str) -> Result<Vec<(u32, u32)>, String> {
+    let mut ranges = Vec::new();
+    for part in text.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        if part.eq_ignore_ascii_case("free") {
+            ranges.push((0, 0));
+            continue;
+        }
+        let (from, to) = part.split_once('-').unwrap_or((part, part));
+        let parse = |value: &str| value.trim().parse::<u32>().map_err(|_| format!("{} is not a range", part));
+        let range = (parse(from)?, parse(to)?);
+        if range.0 < 1 || range.1 > 6 || range.0 > range.1 {
+            return Err(format!("{} is not within 1-6", part));
+        }
+        ranges.push(range);
+    }
+    Ok(ranges)
+}
+
+fn new_class(ruleset: &Ruleset) -> Class {
+    Class {
+        name: unique_name("New Class", |candidate| ruleset.classes.iter().any(|class| class.name == candidate)),
+        tier: Tier::Goon,
+        characteristics: Characteristics::new_default(),
+        special_abilities: Vec::new(),
+        damage_profile: DamageChart::new_default(),
+        modifiers: Vec::new(),
+        cost: 3,
+    }
+}
+
+fn new_action(name: String) -> Action {
+    Action {
+        name,
+        cost: ActionCost { goon: vec![(5, 6)], char: vec![(4, 6)], hero: vec![(0, 0)] },
+        text: String::new(),
+        slot: false,
+        points: 1,
+    }
+}
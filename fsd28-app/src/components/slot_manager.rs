@@ -0,0 +1,141 @@
+use yew::prelude::*;
+use fsd28_lib::SaveData;
+
+use crate::local_storage::{delete_slot, list_slots, load_slot, save_slot, Restored};
+
+#[derive(Properties, PartialEq)]
+pub struct SlotManagerProps {
+    pub save_data: SaveData, // The current content, for saving
+    pub on_load: Callback<(SaveData, usize)>, // The loaded data and the number of items that couldn't be read
+    pub on_close: Callback<()>,
+}
+
+// Dialog to keep several named saves in the browser local storage.
+pub struct SlotManager {
+    slots: Vec<String>,
+    new_slot_name: String,
+    error: Option<String>,
+}
+
+pub enum Msg {
+    UpdateName(String),
+    SaveSlot(String),
+    LoadSlot(String),
+    DeleteSlot(String),
+    Close,
+}
+
+impl Component for SlotManager {
+    type Message = Msg;
+    type Properties = SlotManagerProps;
+
+    fn create(_: &Context<Self>) -> Self {
+        let mut slot_manager = SlotManager { slots: Vec::new(), new_slot_name: String::new(), error: None };
+        slot_manager.refresh();
+        slot_manager
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::UpdateName(name) => {
+                self.new_slot_name = name;
+                false
+            }
+
+            // Saving on an existing name overwrites it
+            Msg::SaveSlot(name) => {
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    self.error = Some("Choose a name for the slot".to_string());
+                    return true;
+                }
+                match save_slot(&name, &ctx.props().save_data) {
+                    Ok(()) => {
+                        self.new_slot_name.clear();
+                        self.refresh();
+                    },
+                    Err(err) => self.error = Some(err),
+                }
+                true
+            }
+
+            Msg::LoadSlot(name) => {
+                match load_slot(&name) {
+                    Ok(Restored::Loaded(save_data)) => ctx.props().on_load.emit((save_data, 0)),
+                    Ok(Restored::Recovered(save_data, skipped)) => ctx.props().on_load.emit((save_data, skipped)),
+                    Ok(Restored::Nothing) => self.error = Some(format!("The slot {} doesn't exist any more", name)),
+                    Err(err) => self.error = Some(err),
+                }
+                true
+            }
+
+            Msg::DeleteSlot(name) => {
+                if let Err(err) = delete_slot(&name) {
+                    self.error = Some(err);
+                }
+                self.refresh();
+                true
+            }
+
+            Msg::Close => {
+                ctx.props().on_close.emit(());
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="modal">
+                <div class="modal-content slot-manager">
+                    <div class="modal-header">{"Local Slots"}</div>
+                    if self.slots.is_empty() {
+                        <div class="slot-empty">{ "No slots saved in this browser yet" }</div>
+                    }
+                    { for self.slots.iter().map(|slot| self.view_slot(slot, ctx.link())) }
+                    <div class="slot-new">
+                        <input type="text" placeholder="Slot name" value={self.new_slot_name.clone()}
+                            oninput={ctx.link().callback(|e: InputEvent| {
+                                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                Msg::UpdateName(input.value())
+                            })} />
+                        <button onclick={ctx.link().callback({
+                            let name = self.new_slot_name.clone();
+                            move |_| Msg::SaveSlot(name.clone())
+                        })}>{ "Save Current" }</button>
+                    </div>
+                    if let Some(error) = &self.error {
+                        <div class="slot-error">{ error }</div>
+                    }
+                    <div class="modal-footer">
+                        <div class="button" onclick={ctx.link().callback(|_| Msg::Close)}>{"Close"}</div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl SlotManager {
+
+    fn refresh(&mut self) {
+        match list_slots() {
+            Ok(slots) => self.slots = slots,
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn view_slot(&self, slot: &str, link: &yew::html::Scope<Self>) -> Html {
+        let load_name = slot.to_string();
+        let save_name = slot.to_string();
+        let delete_name = slot.to_string();
+        html! {
+            <div class="slot">
+                <span class="slot-name">{ slot }</span>
+                <button onclick={link.callback(move |_| Msg::LoadSlot(load_name.clone()))}>{ "Load" }</button>
+                <button onclick={link.callback(move |_| Msg::SaveSlot(save_name.clone()))}>{ "Overwrite" }</button>
+                <button onclick={link.callback(move |_| Msg::DeleteSlot(delete_name.clone()))}>{ "Delete" }</button>
+            </div>
+        }
+    }
+}
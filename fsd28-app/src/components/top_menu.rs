@@ -6,8 +6,17 @@ use crate::shared_messages::SharedMessage;
 pub struct Props {
     pub on_switch_to_roster: Callback<SharedMessage>,
     pub on_switch_to_units: Callback<SharedMessage>,
+    pub on_switch_to_print: Callback<SharedMessage>,
+    pub on_switch_to_editor: Callback<SharedMessage>,
     pub on_save: Callback<SharedMessage>,
     pub on_load: Callback<SharedMessage>,
+    pub on_import: Callback<SharedMessage>,
+    pub on_share: Callback<SharedMessage>,
+    pub on_slots: Callback<SharedMessage>,
+    pub on_load_ruleset: Callback<SharedMessage>,
+    pub on_card_theme_change: Callback<SharedMessage>,
+    pub card_themes: Vec<String>,
+    pub current_card_theme: String,
 }
 
 pub struct TopMenu {
@@ -37,8 +46,22 @@ impl Component for TopMenu {
                 <div class="menu">
                     <button onclick={ctx.props().on_switch_to_roster.reform(|_| SharedMessage::ViewRoster)}>{ "View Roster" }</button>
                     <button onclick={ctx.props().on_switch_to_units.reform(|_| SharedMessage::ViewUnits)}>{ "View Units" }</button>
+                    <button onclick={ctx.props().on_switch_to_print.reform(|_| SharedMessage::ViewPrintSheet)}>{ "Print Sheet" }</button>
+                    <button title="Create and edit classes and weapons" onclick={ctx.props().on_switch_to_editor.reform(|_| SharedMessage::ViewEditor)}>{ "Homebrew" }</button>
                     <button onclick={ctx.props().on_save.reform(|_| SharedMessage::Save)}>{"Save"}</button>
                     <button onclick={ctx.props().on_load.reform(|_| SharedMessage::Load)}>{"Load"}</button>
+                    <button title="Add the profiles of a save file to the current ones" onclick={ctx.props().on_import.reform(|_| SharedMessage::Import)}>{"Import"}</button>
+                    <button onclick={ctx.props().on_share.reform(|_| SharedMessage::CopyShareLink)}>{"Copy Share Link"}</button>
+                    <button onclick={ctx.props().on_slots.reform(|_| SharedMessage::OpenSlots)}>{"Local Slots"}</button>
+                    <button title="Build the profiles with the classes and weapons of a ruleset file" onclick={ctx.props().on_load_ruleset.reform(|_| SharedMessage::LoadRuleset)}>{"Load Ruleset"}</button>
+                    <select title="Card theme" onchange={ctx.props().on_card_theme_change.reform(|e: Event| {
+                        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                        SharedMessage::SelectCardTheme(select.value())
+                    })}>
+                        { for ctx.props().card_themes.iter().map(|theme| html! {
+                            <option value={theme.clone()} selected={*theme == ctx.props().current_card_theme}>{ theme }</option>
+                        }) }
+                    </select>
                 </div>
             </div>
         }
@@ -1,24 +1,46 @@
 use fsd28_lib::models::action::Action;
+use fsd28_lib::models::profile::MAX_ACTIONS;
 use fsd28_lib::models::class::Tier;
 use fsd28_lib::models::modifier::Modifier;
 use yew::prelude::*;
+use yew::context::ContextHandle;
 use fsd28_lib::models::{
     characteristics::Characteristics, 
     profile::Profile, 
     damage_chart::DamageChart,
     damage_chart::Color,};
-use fsd28_lib::get_classes;
-use fsd28_lib::get_weapons;
-use fsd28_lib::ClassesConfig;
+use fsd28_lib::AbilityDisplay;
+use fsd28_lib::{ProfileQuery, ProfileSort};
+use fsd28_lib::models::profile_query::{folder_names, group_by_folder, tag_names};
+use fsd28_lib::CardTheme;
 use fsd28_lib::WeaponsConfig;
 use crate::components::modal::Modal;
 use crate::components::action_tree_view::ActionTreeView;
 use crate::components::modifiers_view::ModifiersView;
 use crate::components::card_generator::CardGenerator;
+use crate::download::download_bytes;
+use crate::rules::{subscribe_rules, SharedRules};
+use fsd28_lib::utils::png_card_generator::{card_file_stem, create_png_cards_zip, render_card_png, CardOptions};
+use fsd28_lib::utils::unique_name::unique_name;
+use fsd28_lib::utils::variant_generator::{generate_variants, VariantKind};
 
 // For browser debugging
 use web_sys::console;
 
+// The filters of the search bar above the profiles list.
+pub enum FilterField {
+    Name(String),
+    Tier(Option<Tier>),
+    Class(Option<String>),
+    Ability(String),
+    Weapon(Option<String>),
+    MinCost(Option<u32>),
+    MaxCost(Option<u32>),
+    Folder(Option<String>),
+    Tag(Option<String>),
+    Sort(ProfileSort),
+}
+
 #[derive(PartialEq)]
 enum ViewMode {
     Text,
@@ -30,6 +52,7 @@ pub struct UnitsViewProps {
     pub profiles: Vec<Profile>, // Assuming Profile is a struct representing your profiles
     pub on_profiles_changed: Callback<Vec<Profile>>,
     pub reset_selected: bool,
+    pub card_theme: CardTheme,
 }
 
 pub struct UnitsView {
@@ -37,12 +60,30 @@ pub struct UnitsView {
     editing_profile: Option<Profile>,
     show_modal: bool,
     view_mode: ViewMode,
+    ability_display: AbilityDisplay,
+    variant_kind: VariantKind,
+    query: ProfileQuery,
+    group_by_folder: bool,
+    show_notes: bool,
+    rules: SharedRules,
+    _rules_handle: ContextHandle<SharedRules>,
 }
 
 pub enum Msg {
+    RulesChanged(SharedRules),
+
     ProfileSelected(Profile),
     CreateNewProfile,
     DeleteSelectedProfile,
+    DuplicateSelectedProfile,
+    VariantKindChanged(VariantKind),
+    GenerateVariants,
+
+    // Search bar
+    FilterChanged(FilterField),
+    ToggleSortOrder,
+    ToggleGroupByFolder,
+    ClearFilters,
 
     // Modal popup for new profile
     ModalConfirm(String),
@@ -50,6 +91,9 @@ pub enum Msg {
 
     // Profile Manipulation
     UpdateFormName(String),
+    UpdateFolder(String),
+    UpdateTags(String),
+    UpdateNotes(String),
     ProfileEdited,
     ResetActions,
     SaveProfileChanges,
@@ -61,18 +105,32 @@ pub enum Msg {
 
     // View mode toggle
     ToggleViewMode,
+    AbilityDisplayChanged(AbilityDisplay),
+    ToggleNotes,
+
+    // Cards export
+    DownloadCard,
+    DownloadAllCards,
 }
 
 impl Component for UnitsView {
     type Message = Msg;
     type Properties = UnitsViewProps;
 
-    fn create(_: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let (rules, rules_handle) = subscribe_rules(ctx, Msg::RulesChanged);
         Self {
             selected_profile: None,
             editing_profile: None,
             show_modal: false,
             view_mode: ViewMode::Text,
+            ability_display: AbilityDisplay::Names,
+            variant_kind: VariantKind::Weapons,
+            query: ProfileQuery::default(),
+            group_by_folder: false,
+            show_notes: false,
+            rules,
+            _rules_handle: rules_handle,
         }
     }
 
@@ -93,6 +151,11 @@ impl Component for UnitsView {
 
         match msg {
 
+            Msg::RulesChanged(rules) => {
+                self.rules = rules;
+                true
+            }
+
             Msg::ProfileSelected(profile) => {
                 // When selecting a profile, we want to start with a fresh copy for editing
                 self.editing_profile = Some(profile.clone());
@@ -100,6 +163,38 @@ impl Component for UnitsView {
                 true 
             }
 
+            Msg::FilterChanged(field) => {
+                let query = &mut self.query;
+                match field {
+                    FilterField::Name(name) => query.name = name,
+                    FilterField::Tier(tier) => query.tier = tier,
+                    FilterField::Class(class_name) => query.class_name = class_name,
+                    FilterField::Ability(ability) => query.ability = Some(ability).filter(|ability| !ability.trim().is_empty()),
+                    FilterField::Weapon(weapon) => query.weapon = weapon,
+                    FilterField::MinCost(cost) => query.min_cost = cost,
+                    FilterField::MaxCost(cost) => query.max_cost = cost,
+                    FilterField::Folder(folder) => query.folder = folder,
+                    FilterField::Tag(tag) => query.tag = tag,
+                    FilterField::Sort(sort) => query.sort = sort,
+                }
+                true
+            }
+
+            Msg::ToggleSortOrder => {
+                self.query.descending = !self.query.descending;
+                true
+            }
+
+            Msg::ToggleGroupByFolder => {
+                self.group_by_folder = !self.group_by_folder;
+                true
+            },
+
+            Msg::ClearFilters => {
+                self.query = ProfileQuery::default();
+                true
+            }
+
             Msg::CreateNewProfile => {
                 self.show_modal = true;
                 true
@@ -116,6 +211,35 @@ impl Component for UnitsView {
                 true
             },
 
+            Msg::DuplicateSelectedProfile => {
+                if let Some(selected_profile) = &self.selected_profile {
+                    let mut all_profiles = ctx.props().profiles.clone();
+                    let mut copy = selected_profile.duplicate();
+                    copy.name = unique_name(&copy.name, |candidate| all_profiles.iter().any(|profile| profile.name == candidate));
+                    all_profiles.push(copy);
+                    ctx.props().on_profiles_changed.emit(all_profiles);
+                }
+                true
+            },
+
+            Msg::VariantKindChanged(variant_kind) => {
+                self.variant_kind = variant_kind;
+                false
+            },
+
+            // The variants are added after the other profiles, with the names listed by the CLI
+            Msg::GenerateVariants => {
+                if let Some(selected_profile) = &self.selected_profile {
+                    if let Some(class) = self.rules.class(&selected_profile.class_name) {
+                        let mut all_profiles = ctx.props().profiles.clone();
+                        let variants = generate_variants(selected_profile, self.variant_kind, class, self.rules.weapons_config(), &all_profiles);
+                        all_profiles.extend(variants);
+                        ctx.props().on_profiles_changed.emit(all_profiles);
+                    }
+                }
+                true
+            },
+
             Msg::UpdateFormName(new_name) => {
                 if let Some(ref mut profile) = self.editing_profile {
                     profile.name = new_name;
@@ -124,6 +248,37 @@ impl Component for UnitsView {
                 true
             },
 
+            Msg::UpdateFolder(folder) => {
+                if let Some(ref mut profile) = self.editing_profile {
+                    profile.folder = folder.trim().to_string();
+                    ctx.link().send_message(Msg::ProfileEdited);
+                }
+                true
+            },
+
+            Msg::UpdateTags(tags) => {
+                if let Some(ref mut profile) = self.editing_profile {
+                    // Tags are typed as a comma separated list, duplicates are dropped
+                    let mut parsed: Vec<String> = Vec::new();
+                    for tag in tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+                        if !parsed.iter().any(|other| other.eq_ignore_ascii_case(tag)) {
+                            parsed.push(tag.to_string());
+                        }
+                    }
+                    profile.tags = parsed;
+                    ctx.link().send_message(Msg::ProfileEdited);
+                }
+                true
+            },
+
+            Msg::UpdateNotes(notes) => {
+                if let Some(ref mut profile) = self.editing_profile {
+                    profile.notes = notes;
+                    ctx.link().send_message(Msg::ProfileEdited);
+                }
+                true
+            },
+
             Msg::ProfileEdited => {
                 if let Some(updated_profile) = self.editing_profile.as_ref() {
                     // Update the central state
@@ -183,7 +338,7 @@ impl Component for UnitsView {
 
             Msg::ActionSelected(action) => {
                 if let Some(ref mut profile) = self.editing_profile {
-                    if profile.actions.len() >= 3 {
+                    if profile.actions.len() >= MAX_ACTIONS {
                         console::log_1(&"The profile cannot have more than 3 actions.".to_string().into());
                         return true;
                     }
@@ -195,8 +350,7 @@ impl Component for UnitsView {
 
             // MODAL VIEW MESSAGES
             Msg::ModalConfirm(class_name) => {
-                let classes: ClassesConfig = get_classes("");
-                let selected_class: Option<&fsd28_lib::Class> = classes.classes.iter().find(|c| c.name == class_name);
+                let selected_class: Option<&fsd28_lib::Class> = self.rules.class(&class_name);
             
                 match selected_class {
                     Some(class) => {
@@ -221,8 +375,7 @@ impl Component for UnitsView {
             },
 
             Msg::ClassChanged(new_class_name) => {
-                let classes: ClassesConfig = get_classes("");
-                if let Some(selected_class) = classes.classes.iter().find(|c| c.name == new_class_name) {
+                if let Some(selected_class) = self.rules.class(&new_class_name) {
                     if let Some(ref mut profile) = self.editing_profile {
                         // Store the current actions
                         let current_actions = profile.actions.clone();
@@ -252,21 +405,78 @@ impl Component for UnitsView {
                 };
                 true
             },
+
+            Msg::AbilityDisplayChanged(ability_display) => {
+                self.ability_display = ability_display;
+                true
+            },
+
+            Msg::ToggleNotes => {
+                self.show_notes = !self.show_notes;
+                true
+            },
+
+            Msg::DownloadCard => {
+                if let Some(profile) = &self.editing_profile {
+                    let result = render_card_png(profile, &self.card_options(ctx))
+                        .map_err(|err| err.to_string())
+                        .and_then(|png| download_bytes(&png, "image/png", &format!("{}.png", card_file_stem(&profile.name))));
+                    if let Err(err) = result {
+                        console::log_1(&format!("Error downloading the card: {}", err).into());
+                    }
+                }
+                false
+            },
+
+            Msg::DownloadAllCards => {
+                let result = create_png_cards_zip(&ctx.props().profiles, &self.card_options(ctx))
+                    .map_err(|err| err.to_string())
+                    .and_then(|zip| download_bytes(&zip, "application/zip", "cards.zip"));
+                if let Err(err) = result {
+                    console::log_1(&format!("Error downloading the cards: {}", err).into());
+                }
+                false
+            },
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let all_classes : Vec<String> = get_classes("").classes.iter().map(|class| class.name.clone()).collect();
+        let all_classes : Vec<String> = self.rules.class_names();
+        let profiles = &ctx.props().profiles;
+        let found = self.query.apply(profiles, &self.rules);
 
         html! {
             <div class="units-view">
                 <div class="left-bar">
+                    { self.view_search_bar(ctx) }
                     <div class="profiles-list">
-                        { for ctx.props().profiles.iter().map(|profile| self.view_profile_button(profile, ctx.link())) }
+                        if self.group_by_folder {
+                            { for group_by_folder(profiles, &found).into_iter().map(|(folder, indexes)| html! {
+                                <>
+                                    <div class="folder-header">{ if folder.is_empty() { "No folder".to_string() } else { folder } }</div>
+                                    { for indexes.iter().map(|index| self.view_profile_button(&profiles[*index], ctx.link())) }
+                                </>
+                            }) }
+                        } else {
+                            { for found.iter().map(|index| self.view_profile_button(&profiles[*index], ctx.link())) }
+                        }
+                        if found.is_empty() && !profiles.is_empty() {
+                            <div class="search-empty">{ "No profile matches the filters" }</div>
+                        }
                     </div>
                     <div class="profile-list-buttons">
                         <button onclick={ctx.link().callback(|_| Msg::CreateNewProfile)}>{"Create New"}</button>
                         <button onclick={ctx.link().callback(|_| Msg::DeleteSelectedProfile)}>{"Delete Selected"}</button>
+                        <button onclick={ctx.link().callback(|_| Msg::DuplicateSelectedProfile)}>{"Duplicate"}</button>
+                        <select title="What changes between the variants" onchange={ctx.link().callback(|e: Event| {
+                            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                            Msg::VariantKindChanged(select.value().parse().unwrap_or(VariantKind::Weapons))
+                        })}>
+                            <option value="weapons" selected={self.variant_kind == VariantKind::Weapons}>{"By weapon"}</option>
+                            <option value="modifiers" selected={self.variant_kind == VariantKind::Modifiers}>{"By modifiers"}</option>
+                        </select>
+                        <button onclick={ctx.link().callback(|_| Msg::GenerateVariants)}>{"Generate Variants"}</button>
+                        <button onclick={ctx.link().callback(|_| Msg::DownloadAllCards)}>{"Download All Cards"}</button>
                     </div>
                 </div>
                 <div class="center-bar">
@@ -274,7 +484,7 @@ impl Component for UnitsView {
                         if self.view_mode == ViewMode::Text {
                             { self.view_profile(profile) }
                         } else {
-                            <CardGenerator profile={profile.clone()} />
+                            <CardGenerator profile={profile.clone()} ability_display={self.ability_display} theme={ctx.props().card_theme.clone()} />
                         }
                     } else {
                         <div class="no-selection">
@@ -314,8 +524,95 @@ impl UnitsView {
         self.selected_profile = None;
     }
 
+    fn card_options(&self, ctx: &Context<Self>) -> CardOptions {
+        CardOptions {
+            abilities: self.rules.abilities().clone(),
+            ability_display: self.ability_display,
+            theme: ctx.props().card_theme.clone(),
+            show_notes: self.show_notes,
+            ..CardOptions::default()
+        }
+    }
+
+    fn view_search_bar(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let query = &self.query;
+        let on_select = |to_field: fn(String) -> FilterField| link.callback(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            Msg::FilterChanged(to_field(select.value()))
+        });
+        let on_input = |to_field: fn(String) -> FilterField| link.callback(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            Msg::FilterChanged(to_field(input.value()))
+        });
+        let cost_text = |cost: Option<u32>| cost.map(|cost| cost.to_string()).unwrap_or_default();
+        let profiles = &ctx.props().profiles;
+
+        html! {
+            <div class="search-bar">
+                <input type="search" placeholder="Search by name" value={query.name.clone()} oninput={on_input(FilterField::Name)} />
+                <div class="search-row">
+                    <select title="Tier" onchange={on_select(|value| FilterField::Tier(value.parse().ok()))}>
+                        <option value="" selected={query.tier.is_none()}>{"Any tier"}</option>
+                        { for [Tier::Goon, Tier::Char, Tier::Hero].into_iter().map(|tier| html! {
+                            <option value={tier.to_string()} selected={query.tier.as_ref() == Some(&tier)}>{ tier.to_string() }</option>
+                        }) }
+                    </select>
+                    <select title="Class" onchange={on_select(|value| FilterField::Class(Some(value).filter(|value| !value.is_empty())))}>
+                        <option value="" selected={query.class_name.is_none()}>{"Any class"}</option>
+                        { for self.rules.class_names().into_iter().map(|name| html! {
+                            <option value={name.clone()} selected={query.class_name.as_ref() == Some(&name)}>{ name }</option>
+                        }) }
+                    </select>
+                    <select title="Weapon" onchange={on_select(|value| FilterField::Weapon(Some(value).filter(|value| !value.is_empty())))}>
+                        <option value="" selected={query.weapon.is_none()}>{"Any weapon"}</option>
+                        { for self.rules.weapons().iter().map(|weapon| html! {
+                            <option value={weapon.name.clone()} selected={query.weapon.as_ref() == Some(&weapon.name)}>{ &weapon.name }</option>
+                        }) }
+                    </select>
+                </div>
+                <div class="search-row">
+                    <input type="text" placeholder="Ability" value={query.ability.clone().unwrap_or_default()} oninput={on_input(FilterField::Ability)} />
+                    <input type="number" min="0" placeholder="Min pts" value={cost_text(query.min_cost)} oninput={on_input(|value| FilterField::MinCost(value.parse().ok()))} />
+                    <input type="number" min="0" placeholder="Max pts" value={cost_text(query.max_cost)} oninput={on_input(|value| FilterField::MaxCost(value.parse().ok()))} />
+                </div>
+                <div class="search-row">
+                    <select title="Folder" onchange={on_select(|value| FilterField::Folder(Some(value).filter(|value| !value.is_empty())))}>
+                        <option value="" selected={query.folder.is_none()}>{"Any folder"}</option>
+                        { for folder_names(profiles).into_iter().map(|folder| html! {
+                            <option value={folder.clone()} selected={query.folder.as_ref() == Some(&folder)}>{ folder }</option>
+                        }) }
+                    </select>
+                    <select title="Tag" onchange={on_select(|value| FilterField::Tag(Some(value).filter(|value| !value.is_empty())))}>
+                        <option value="" selected={query.tag.is_none()}>{"Any tag"}</option>
+                        { for tag_names(profiles).into_iter().map(|tag| html! {
+                            <option value={tag.clone()} selected={query.tag.as_ref() == Some(&tag)}>{ tag }</option>
+                        }) }
+                    </select>
+                    <button class="search-order" title="Group the profiles by folder" onclick={link.callback(|_| Msg::ToggleGroupByFolder)}>
+                        { if self.group_by_folder { "Ungroup" } else { "Group by folder" } }
+                    </button>
+                </div>
+                <div class="search-row">
+                    <select title="Sort" onchange={on_select(|value| FilterField::Sort(value.parse().unwrap_or_default()))}>
+                        <option value="saved" selected={query.sort == ProfileSort::Saved}>{"Saved order"}</option>
+                        <option value="name" selected={query.sort == ProfileSort::Name}>{"By name"}</option>
+                        <option value="cost" selected={query.sort == ProfileSort::Cost}>{"By cost"}</option>
+                        <option value="class" selected={query.sort == ProfileSort::Class}>{"By class"}</option>
+                    </select>
+                    <button class="search-order" title="Reverse the order" onclick={link.callback(|_| Msg::ToggleSortOrder)}>
+                        { if query.descending { "Descending" } else { "Ascending" } }
+                    </button>
+                    if !query.is_empty() {
+                        <button class="search-clear" onclick={link.callback(|_| Msg::ClearFilters)}>{"Clear"}</button>
+                    }
+                </div>
+            </div>
+        }
+    }
+
     fn view_profile_button(&self, profile: &Profile, link: &yew::html::Scope<Self>) -> Html {
-        let is_selected = self.selected_profile.as_ref().map_or(false, |p| p == profile);
+        let is_selected = self.selected_profile.as_ref() == Some(profile);
         let local_profile = profile.clone(); // There is a _DOUBLE_ clone here - TODO FIX this is horrible (but it works)
         html! {
             <button
@@ -337,41 +634,38 @@ impl UnitsView {
                 <div class="profile-name">{ &final_profile.name }</div>
                 <div class="profile-description">{ &final_profile.description }</div>
                 <div class="profile-cost">{ &final_profile.cost } { " points" }</div>
+                if !final_profile.folder.is_empty() || !final_profile.tags.is_empty() {
+                    <div class="profile-tags">
+                        if !final_profile.folder.is_empty() {
+                            <span class="profile-folder">{ &final_profile.folder }</span>
+                        }
+                        { for final_profile.tags.iter().map(|tag| html! { <span class="profile-tag">{ tag }</span> }) }
+                    </div>
+                }
                 <div class="profile-stats">{ self.display_characteristics(&final_profile.characteristics) }</div>
                 <div class="profile-actions">{ self.display_actions(&final_profile.actions, &final_profile.tier) }</div>
                 <div class="profile-special-abilities">
                     { "Special Abilities: " }
-                    { &final_profile.special_abilities.join(", ") }
+                    { for self.rules.abilities().format_abilities(&final_profile.special_abilities, self.ability_display).iter().map(|line| html! {
+                        <div>{ line }</div>
+                    }) }
                 </div>
                 { self.view_damage_chart(&final_profile.damage_chart) }
+                if !final_profile.notes.is_empty() {
+                    <div class="profile-notes">{ &final_profile.notes }</div>
+                }
             </div>
         }
     }
 
-    fn view_current_profile(&self) -> Html {
-        if let Some(profile) = &self.editing_profile {
-            self.view_profile(profile)
-        } else {
-            html! { <div class="label center" style="font-variant: small-caps;">{ "~ no profile selected ~" }</div> }
-        }
-    }
-
-
     fn view_edit_form(&self, ctx: &Context<Self>) -> Html {
         if let Some(profile) = &self.editing_profile {
-            let weapons_config: WeaponsConfig = get_weapons(""); // Load your weapons configuration
+            let weapons_config: &WeaponsConfig = self.rules.weapons_config();
 
             // Retrieving the modifiers to show:
-            let all_classes: ClassesConfig = get_classes("");
-            let available_modifiers: Vec<Modifier>;
-            if let Some(index) = all_classes.classes
-                .iter()
-                .position(|p| p.name == profile.class_name) {
-                available_modifiers = all_classes.classes[index].modifiers.clone();
-            }
-            else {
-                available_modifiers = Vec::<Modifier>::new();
-            }
+            let available_modifiers: Vec<Modifier> = self.rules.class(&profile.class_name)
+                .map(|class| class.modifiers.clone())
+                .unwrap_or_default();
 
             let selected_actions = if let Some(profile) = &self.editing_profile {
                 profile.actions.iter().map(|action| {action.name.clone()}).collect::<Vec<String>>()
@@ -379,7 +673,7 @@ impl UnitsView {
                 Vec::<String>::new()
             };
 
-            let all_classes: Vec<String> = get_classes("").classes.iter().map(|class| class.name.clone()).collect();
+            let all_classes: Vec<String> = self.rules.class_names();
 
             html! {
                 <div class="edit-form">
@@ -398,6 +692,56 @@ impl UnitsView {
                         >
                             { if self.view_mode == ViewMode::Text { "Switch to Image View" } else { "Switch to Text View" } }
                         </button>
+                        <button 
+                            class="view-mode-button"
+                            onclick={ctx.link().callback(|_| Msg::DownloadCard)}
+                        >
+                            { "Download PNG" }
+                        </button>
+
+                        <label class="label" for="ability-display">{"ABILITIES:"}</label>
+                        <select id="ability-display"
+                            onchange={ctx.link().callback(|e: Event| {
+                                let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                                Msg::AbilityDisplayChanged(select.value().parse().unwrap_or_default())
+                            })}>
+                            <option value="names" selected={self.ability_display == AbilityDisplay::Names}>{"Names"}</option>
+                            <option value="rules" selected={self.ability_display == AbilityDisplay::RulesText}>{"Rules Text"}</option>
+                            <option value="footnotes" selected={self.ability_display == AbilityDisplay::Footnotes}>{"Footnotes"}</option>
+                        </select>
+                        <label class="custom-checkbox" title="Print the notes and tags on the downloaded cards">
+                            { "Notes on cards" }
+                            <input type="checkbox" checked={self.show_notes} onclick={ctx.link().callback(|_| Msg::ToggleNotes)} />
+                            <span class="checkmark"></span>
+                        </label>
+
+                        <label class="label" for="folder">{"FOLDER:"}</label>
+                        <input type="text" id="folder" list="folder-names" placeholder="Faction or folder"
+                            value={profile.folder.clone()}
+                            onchange={ctx.link().callback(|e: Event| {
+                                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                Msg::UpdateFolder(input.value())
+                            })} />
+                        <datalist id="folder-names">
+                            { for folder_names(&ctx.props().profiles).into_iter().map(|folder| html! { <option value={folder} /> }) }
+                        </datalist>
+
+                        <label class="label" for="tags">{"TAGS:"}</label>
+                        <input type="text" id="tags" placeholder="Comma separated"
+                            value={profile.tags.join(", ")}
+                            onchange={ctx.link().callback(|e: Event| {
+                                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                Msg::UpdateTags(input.value())
+                            })} />
+
+                        <label class="label" for="notes">{"NOTES:"}</label>
+                        // Updated when the textarea loses focus, not on every keystroke (each update autosaves)
+                        <textarea id="notes" rows="3"
+                            value={profile.notes.clone()}
+                            onchange={ctx.link().callback(|e: Event| {
+                                let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+                                Msg::UpdateNotes(input.value())
+                            })} />
 
                         // Setting up the Modifiers view, where the class modifiers are available to toggle.
                         <ModifiersView 
@@ -411,7 +755,7 @@ impl UnitsView {
 
                         // Setting up all the available actions
                         <ActionTreeView 
-                            weapons={weapons_config.weapons} 
+                            weapons={weapons_config.weapons.clone()} 
                             selected_actions={selected_actions} 
                             on_action_select={ctx.link().callback(move |action: Action| Msg::ActionSelected(action))}
                         />
@@ -443,7 +787,7 @@ impl UnitsView {
         }
     }
 
-    fn display_actions (&self, actions: &Vec<Action>, tier: &Tier) -> Html {
+    fn display_actions (&self, actions: &[Action], tier: &Tier) -> Html {
         if actions.is_empty(){
             html! { <div>{"no actions selected"}</div> }
         }
@@ -0,0 +1,45 @@
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+// Triggers the browser download of some in-memory content, through a temporary
+// hidden anchor pointing to an object URL.
+pub fn download_bytes(content: &[u8], mime_type: &str, file_name: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("no global `window` exists")?;
+    let document = window
+        .document().ok_or("should have a document on window")?;
+    let a = document.create_element("a")
+        .map_err(|_| "Couldn't create the download link")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| "Couldn't create the download link")?;
+
+    // Convert the content to a Blob
+    let mut blob_parts: web_sys::BlobPropertyBag = web_sys::BlobPropertyBag::new();
+    blob_parts.type_(mime_type);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(
+        &js_sys::Array::of1(&js_sys::Uint8Array::from(content)), &blob_parts)
+        .map_err(|_| "Couldn't create the file content")?;
+
+    // Create an Object URL from the Blob
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(|_| "Couldn't create the file URL")?;
+
+    a.set_href(&url);
+    a.set_download(file_name);
+    a.set_attribute("style", "display: none").unwrap();
+    document.body().unwrap().append_child(&a).unwrap();
+    a.click();
+    a.remove();
+
+    // Clean up the Object URL to free resources, once the browser has started the download:
+    // revoking it right after the click can cancel the download in some browsers
+    let revoke = Closure::once_into_js(move || {
+        let _ = web_sys::Url::revoke_object_url(&url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 0)
+        .map(|_| ())
+        .map_err(|_| "Couldn't release the file URL".to_string())
+}
+
+// Encodes a PNG as a data URL, usable directly as the source of an <img>.
+pub fn png_data_url(png: &[u8]) -> String {
+    format!("data:image/png;base64,{}", base64::encode(png))
+}
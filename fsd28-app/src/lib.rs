@@ -6,9 +6,16 @@ mod components {
     pub mod action_tree_view;
     pub mod modifiers_view;
     pub mod card_generator;
+    pub mod print_sheet_view;
+    pub mod slot_manager;
+    pub mod ruleset_editor;
 }
 mod app;
+mod download;
+mod share_link;
+mod local_storage;
 mod shared_messages;
+mod rules;
 mod model;
 
 
@@ -0,0 +1,80 @@
+use fsd28_lib::SaveData;
+
+// Browser local storage keys
+const AUTOSAVE_KEY: &str = "fsd28.autosave";
+const SLOT_PREFIX: &str = "fsd28.slot.";
+const BACKUP_KEY: &str = "fsd28.backup"; // Stored data that failed to load, kept untouched
+
+// What was found in the local storage.
+pub enum Restored {
+    Nothing,
+    Loaded(SaveData),
+    Recovered(SaveData, usize), // Loaded skipping the items that can't be read any more
+}
+
+fn storage() -> Result<web_sys::Storage, String> {
+    web_sys::window().ok_or("no global `window` exists")?
+        .local_storage().map_err(|_| "The local storage is not available")?
+        .ok_or_else(|| "The local storage is not available".to_string())
+}
+
+fn write(key: &str, save_data: &SaveData) -> Result<(), String> {
+    let json = save_data.to_json().map_err(|err| err.to_string())?;
+    storage()?.set_item(key, &json).map_err(|_| "The local storage is full".to_string())
+}
+
+// Data that doesn't load is saved as a backup before trying to recover it.
+fn read(key: &str) -> Result<Restored, String> {
+    let storage = storage()?;
+    let Some(json) = storage.get_item(key).map_err(|_| "The local storage is not available")? else {
+        return Ok(Restored::Nothing);
+    };
+    if let Ok(save_data) = SaveData::from_json(&json) {
+        return Ok(Restored::Loaded(save_data));
+    }
+    storage.set_item(BACKUP_KEY, &json).map_err(|_| "The local storage is full")?;
+    let (save_data, skipped) = SaveData::recover_from_json(&json).map_err(|err| format!("The saved data is damaged: {}", err))?;
+    Ok(Restored::Recovered(save_data, skipped))
+}
+
+pub fn autosave(save_data: &SaveData) -> Result<(), String> {
+    write(AUTOSAVE_KEY, save_data)
+}
+
+pub fn restore_autosave() -> Result<Restored, String> {
+    read(AUTOSAVE_KEY)
+}
+
+// Named slots, sorted by name.
+pub fn list_slots() -> Result<Vec<String>, String> {
+    let storage = storage()?;
+    let length = storage.length().map_err(|_| "The local storage is not available")?;
+    let mut slots: Vec<String> = (0..length)
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter_map(|key| key.strip_prefix(SLOT_PREFIX).map(|name| name.to_string()))
+        .collect();
+    slots.sort();
+    Ok(slots)
+}
+
+pub fn save_slot(name: &str, save_data: &SaveData) -> Result<(), String> {
+    write(&format!("{}{}", SLOT_PREFIX, name), save_data)
+}
+
+pub fn load_slot(name: &str) -> Result<Restored, String> {
+    read(&format!("{}{}", SLOT_PREFIX, name))
+}
+
+pub fn delete_slot(name: &str) -> Result<(), String> {
+    storage()?.remove_item(&format!("{}{}", SLOT_PREFIX, name)).map_err(|_| "The local storage is not available".to_string())
+}
+
+pub fn backup() -> Option<String> {
+    storage().ok()?.get_item(BACKUP_KEY).ok().flatten()
+}
+
+pub fn clear_backup() {
+    if let Ok(storage) = storage() {
+        let _ = storage.remove_item(BACKUP_KEY);
+    }
+}
@@ -1,6 +1,6 @@
-//use fsd28_lib::models::profile::Profile;
 use serde::{Deserialize, Serialize};
 use fsd28_lib::models::profile::Profile;
+use fsd28_lib::{Roster, Ruleset, SaveData};
 
 // For browser debugging
 use web_sys::console;
@@ -8,39 +8,70 @@ use web_sys::console;
 
 // The App Model contains 
 // - A list of profiles that have been created
-// - A roster of selected profiles (in the future it will be a list of rosters)
+// - The rosters built from those profiles
+// - The classes and weapons to build them with, if not the bundled ones
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Model {
     pub profiles: Vec<Profile>,
-    pub roster: Vec<String>,
+    pub rosters: Vec<Roster>,
+    pub ruleset: Option<Ruleset>,
 }
 
 impl Model {
     pub fn new() -> Model {
         Model { 
             profiles: Vec::<Profile>::new(),
-            roster : Vec::<String>::new(),
+            rosters: Vec::<Roster>::new(),
+            ruleset: None,
         }
     }
 
-    // JSON serialization (static methods):
-    pub fn from_json(json_str: &str) -> Result<Self, serde_json::Error> {
+    pub fn from_save_data(save_data: SaveData) -> Model {
+        Model {
+            profiles: save_data.profiles,
+            rosters: save_data.rosters,
+            ruleset: save_data.ruleset,
+        }
+    }
+
+    pub fn to_save_data(&self) -> SaveData {
+        SaveData::new(self.profiles.clone(), self.rosters.clone()).with_ruleset(self.ruleset.clone())
+    }
+
+    // The custom ruleset if loaded, the bundled one otherwise.
+    pub fn active_ruleset(&self) -> Ruleset {
+        self.ruleset.clone().unwrap_or_else(Ruleset::bundled)
+    }
 
-        let model: Model = serde_json::from_str(json_str)?;
+    // Adds the profiles and rosters that are not there yet, by ID, and the ruleset if there was none.
+    pub fn add_missing(&mut self, save_data: SaveData) {
+        if self.ruleset.is_none() {
+            self.ruleset = save_data.ruleset;
+        }
+        for profile in save_data.profiles {
+            if !self.profiles.iter().any(|existing| existing.id == profile.id) {
+                self.profiles.push(profile);
+            }
+        }
+        for roster in save_data.rosters {
+            if !self.rosters.iter().any(|existing| existing.id == roster.id) {
+                self.rosters.push(roster);
+            }
+        }
+    }
+
+    // JSON serialization (static methods). The save file format is shared with the CLI,
+    // older saves with just the profiles are still accepted.
+    pub fn from_json(json_str: &str) -> Result<Self, serde_json::Error> {
 
-        // TODO version is not implemented yet.
-        // if model.version < 1 { // Assuming 1 is the current version // TODO handle versioning better
-        //     // Handle older versions differently
-        //     // For now, just return an error
-        //     return Err(de::Error::custom("Roster version is too old"));
-        // }
+        let save_data = SaveData::from_json(json_str)?;
 
-        console::log_1(&format!("Loaded model with {} profiles and {} rosters", model.profiles.len(), model.roster.len()).into());
+        console::log_1(&format!("Loaded model with {} profiles and {} rosters", save_data.profiles.len(), save_data.rosters.len()).into());
 
-        Ok(model)
+        Ok(Model::from_save_data(save_data))
     }
     
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string(self)
+        self.to_save_data().to_json()
     }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use yew::context::ContextHandle;
+use fsd28_lib::RulesetContext;
+
+// The rules in use, built by the App when the ruleset changes and provided to every view.
+pub type SharedRules = Rc<RulesetContext>;
+
+// Reads the rules provided by the App, with a message for when they change.
+// The handle must be kept by the component to keep receiving the changes.
+pub fn subscribe_rules<C: Component>(ctx: &Context<C>, on_change: impl Fn(SharedRules) -> C::Message + 'static) -> (SharedRules, ContextHandle<SharedRules>) {
+    ctx.link()
+        .context::<SharedRules>(ctx.link().callback(on_change))
+        .expect("the rules are provided by the App")
+}
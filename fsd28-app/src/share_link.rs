@@ -0,0 +1,34 @@
+use fsd28_lib::SaveData;
+use fsd28_lib::utils::share_code::{encode_share_code, share_link, SHARE_FRAGMENT};
+use wasm_bindgen::JsCast;
+
+// The share code in the URL fragment the app was opened with, if any.
+pub fn share_code_from_url() -> Option<String> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    hash.trim_start_matches('#').strip_prefix(SHARE_FRAGMENT).map(|code| code.to_string())
+}
+
+// Removes the share code from the address bar, so that reloading the page doesn't load it again.
+pub fn clear_share_code_from_url() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_hash("");
+    }
+}
+
+// Builds the link to the app with the content encoded in it and copies it to the clipboard.
+// The clipboard API is not available everywhere (e.g. without HTTPS), so the link is returned as well.
+pub fn copy_share_link(save_data: &SaveData) -> Result<String, String> {
+    let window = web_sys::window().ok_or("no global `window` exists")?;
+    let code = encode_share_code(save_data).map_err(|err| err.to_string())?;
+    let link = share_link(&window.location().href().map_err(|_| "Couldn't read the page address")?, &code);
+
+    // navigator.clipboard.writeText(link), looked up at runtime as web-sys only has it behind a flag
+    let clipboard = js_sys::Reflect::get(&window.navigator(), &"clipboard".into())
+        .map_err(|_| "The clipboard is not available")?;
+    let write_text = js_sys::Reflect::get(&clipboard, &"writeText".into())
+        .ok()
+        .and_then(|function| function.dyn_into::<js_sys::Function>().ok())
+        .ok_or("The clipboard is not available")?;
+    write_text.call1(&clipboard, &link.clone().into()).map_err(|_| "Couldn't copy the link")?;
+    Ok(link)
+}
@@ -1,18 +1,38 @@
-use fsd28_lib::Profile;
+use fsd28_lib::{ConflictResolution, Profile, Roster, Ruleset, SaveData};
 
 pub enum SharedMessage {
     NoOp, // Dummy message for no-operation
     
     ViewRoster,
     ViewUnits,
+    ViewPrintSheet,
+    ViewEditor,
     Save,
     Load,
+    Import, // Load merging into the current content
+    ResolveMerge(Option<ConflictResolution>), // None cancels the import
+    ConfirmLoad(bool), // After seeing what the loaded file changes
+    CopyShareLink,
+    LoadRuleset, // Classes and weapons from a ruleset file
+    DismissRulesetErrors,
+
+    // Local storage
+    OpenSlots,
+    CloseSlots,
+    SlotLoaded(SaveData, usize), // With the number of items that could not be read
+    DownloadBackup,
+    DismissRecovery,
 
     _ToggleTheme, // Temporarly unused.
+    SelectCardTheme(String),
 
     // Loading
-    FileContentReceived(String), // TODO should it be a &str?
+    FileContentReceived(String, String), // File name and content. TODO should it be a &str?
 
     // Dumping profile updates
     UpdateProfiles(Vec<Profile>),
+    UpdateRosters(Vec<Roster>),
+    ImportRoster(Vec<Profile>, Roster), // Profiles read from a text list, with their roster
+    UpdateHomebrew(Ruleset),
+    ApplyRuleset(Option<Ruleset>), // None for the bundled one
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use fsd28_lib::models::profile::Profile;
+use fsd28_lib::{ConflictResolution, MergeReport, Roster, Ruleset, RulesetContext, SaveData};
 
 #[derive(PartialEq)]
 pub enum MenuStates {
@@ -10,6 +11,7 @@ pub enum MenuStates {
     EditProfile, // Choices to edit specific stuff appear from here
     AddAction,
     PrintAllProfiles,
+    ExportPng,
     //AddEquip,
     EditName,
     //EditType,
@@ -20,17 +22,27 @@ pub enum MenuStates {
 
 pub struct AppState {
     profiles: Vec<Profile>,
+    rosters: Vec<Roster>, // Not editable from the menu, but kept when saving
+    ruleset: Option<Ruleset>, // The homebrew rules of a web app save, kept when saving
     selected: Option<usize>,
+    rules: RulesetContext, // The bundled rules and the packs given with --ruleset
 }
 
 impl AppState {
-    pub fn new() -> AppState {
+    pub fn new(rules: RulesetContext) -> AppState {
         AppState {
             profiles: Vec::new(),
+            rosters: Vec::new(),
+            ruleset: None,
             selected: None,
+            rules,
         }
     }
 
+    pub fn rules(&self) -> &RulesetContext {
+        &self.rules
+    }
+
     pub fn add_profile(&mut self, new_profile: Profile) {
         
         // Adding in the new profile and then setting the index to current
@@ -67,11 +79,21 @@ impl AppState {
         self.profiles.clone()
     }
 
-    pub fn clear_profiles(&mut self) {
-        self.profiles.clear();
+    pub fn get_save_data(&self) -> SaveData {
+        SaveData::new(self.profiles.clone(), self.rosters.clone()).with_ruleset(self.ruleset.clone())
     }
 
-    pub fn set_all_profiles(&mut self, i_profiles: Vec<Profile>) {
-        self.profiles = i_profiles; // No cloning, no reference. Hard move.
+    pub fn set_save_data(&mut self, i_save_data: SaveData) {
+        self.profiles = i_save_data.profiles;
+        self.rosters = i_save_data.rosters;
+        self.ruleset = i_save_data.ruleset;
+        self.selected = None;
     }
-}
\ No newline at end of file
+
+    pub fn merge_save_data(&mut self, i_save_data: SaveData, i_resolution: ConflictResolution) -> MergeReport {
+        let mut save_data = self.get_save_data();
+        let report = save_data.merge(i_save_data, i_resolution);
+        self.set_save_data(save_data);
+        report
+    }
+}
@@ -0,0 +1,728 @@
+use clap::{Parser, Subcommand};
+
+use fsd28_lib::{convert_rules_file, get_themes, load_abilities, load_classes, load_profiles, load_roster_rules, load_theme_file, load_weapons, load_ruleset, load_save_data, save_save_data, AbilitiesConfig, AbilityDisplay, CardTheme, CollectionDiff, RosterRules, ConflictResolution, ProfileQuery, ProfileSort, Roster, Ruleset, RulesetContext, SaveData};
+use fsd28_lib::utils::pdf_ascii_generator::create_pdf_ascii_with_abilities;
+use fsd28_lib::utils::pdf_card_generator::{create_pdf_cards, PdfCardOptions};
+use fsd28_lib::utils::roster_exporter::{export_roster, RosterFormat};
+use fsd28_lib::utils::roster_importer::import_roster_text;
+use fsd28_lib::utils::build_search::{search_builds, BuildScore};
+use fsd28_lib::utils::variant_generator::{generate_variants, VariantKind};
+use fsd28_lib::utils::ruleset_diff::{diff_rulesets, ruleset_impact};
+use fsd28_lib::utils::share_code::{encode_share_code, share_link};
+use fsd28_lib::models::class::Tier;
+use fsd28_lib::utils::schema::{schema_json, SchemaKind};
+use fsd28_lib::utils::file_format::FileFormat;
+use fsd28_lib::utils::rules_csv::{classes_from_csv, classes_to_csv, weapons_from_csv, weapons_to_csv};
+use fsd28_lib::utils::png_card_generator::{create_png_cards, CardBack, CardOptions, DEFAULT_DPI};
+
+// Command line arguments. Without a subcommand the interactive menu is started.
+#[derive(Parser)]
+#[command(name = "fsd28-cli", about = "The FSD28 profile creator")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// A ruleset pack (file or folder) applied over the bundled rules. Can be repeated, the packs are applied in order
+    #[arg(long = "ruleset", global = true, value_name = "PACK")]
+    pub rulesets: Vec<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Renders one PNG card per profile of a save file
+    ExportPng {
+        /// The save file to read the profiles from
+        save: String,
+        /// The folder where the cards are written
+        #[arg(long, default_value = "cards")]
+        out: String,
+        /// The resolution of the cards
+        #[arg(long, default_value_t = DEFAULT_DPI)]
+        dpi: u32,
+        /// How special abilities are printed: names, rules or footnotes
+        #[arg(long, default_value = "names")]
+        abilities: AbilityDisplay,
+        /// Card theme: the name of a bundled theme or a theme JSON file
+        #[arg(long, default_value = "")]
+        theme: String,
+        /// Prints the notes and tags of the profiles on the cards
+        #[arg(long)]
+        notes: bool,
+    },
+
+    /// Creates a printable PDF with the cards, the reference cards and optionally the card backs
+    ExportPdf {
+        /// The save file to read the profiles from
+        save: String,
+        /// The PDF file to create
+        #[arg(long, default_value = "cards.pdf")]
+        out: String,
+        /// The resolution of the cards
+        #[arg(long, default_value_t = DEFAULT_DPI)]
+        dpi: u32,
+        /// How special abilities are printed: names, rules or footnotes
+        #[arg(long, default_value = "names")]
+        abilities: AbilityDisplay,
+        /// Card theme: the name of a bundled theme or a theme JSON file
+        #[arg(long, default_value = "")]
+        theme: String,
+        /// Prints the notes and tags of the profiles on the cards
+        #[arg(long)]
+        notes: bool,
+        /// Faction name for the card backs. Backs are added only if a faction or roster name is set
+        #[arg(long, default_value = "")]
+        faction: String,
+        /// Roster name for the card backs
+        #[arg(long, default_value = "")]
+        roster: String,
+        /// PNG logo for the card backs
+        #[arg(long)]
+        logo: Option<String>,
+        /// Skips the reference cards
+        #[arg(long)]
+        no_reference: bool,
+    },
+
+    /// Creates the ASCII profiles PDF
+    ExportAscii {
+        /// The save file to read the profiles from
+        save: String,
+        /// The PDF file to create
+        #[arg(long, default_value = "out.pdf")]
+        out: String,
+        /// How special abilities are printed: names, rules or footnotes
+        #[arg(long, default_value = "names")]
+        abilities: AbilityDisplay,
+    },
+
+    /// Checks the classes and weapons, and that every special ability used by the classes is defined in the glossary
+    Validate {
+        /// The classes file, the bundled one if not set
+        #[arg(long, default_value = "")]
+        classes: String,
+        /// The weapons file, the bundled one if not set
+        #[arg(long, default_value = "")]
+        weapons: String,
+        /// The abilities glossary, the bundled one if not set
+        #[arg(long, default_value = "")]
+        glossary: String,
+    },
+
+    /// Checks the rosters of a save file against the list-building rules
+    ValidateRoster {
+        /// The save file to read the rosters from
+        save: String,
+        /// The name or ID of the roster to check, all of them if not set
+        #[arg(long)]
+        roster: Option<String>,
+        /// A list-building rules file, in place of the ones of the ruleset
+        #[arg(long, default_value = "")]
+        rules: String,
+    },
+
+    /// Exports a roster as a text summary, a Markdown table or a PDF army list
+    ExportRoster {
+        /// The save file to read the roster from
+        save: String,
+        /// The name or ID of the roster, needed only if the save file has more than one
+        #[arg(long)]
+        roster: Option<String>,
+        /// The output format: text, markdown or pdf
+        #[arg(long, default_value = "text")]
+        format: RosterFormat,
+        /// The file to create. Text and Markdown are printed if not set
+        #[arg(long)]
+        out: Option<String>,
+        /// A list-building rules file, in place of the ones of the ruleset
+        #[arg(long, default_value = "")]
+        rules: String,
+        /// Exports the roster even if it breaks the list-building rules
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Reads a text army list ("2x Enforcer - Assault Rifle: Suppression Burst") into a save file
+    ImportRoster {
+        /// The text file with the list
+        list: String,
+        /// The save file where the profiles and the roster are added. It is created if needed
+        save: String,
+        /// Fails instead of skipping the names that can't be matched
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Adds the profiles and rosters of another save file to a save file
+    Merge {
+        /// The save file to add to
+        save: String,
+        /// The save file to import
+        import: String,
+        /// What to do with profiles and rosters already in the save file: keep-both, overwrite or skip
+        #[arg(long, default_value = "keep-both")]
+        on_conflict: ConflictResolution,
+        /// The file to write, the first save file if not set
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Lists the profiles of a save file, filtered and sorted
+    List {
+        /// The save file
+        save: String,
+        /// Only the profiles whose name contains this
+        #[arg(long, default_value = "")]
+        name: String,
+        /// Only the profiles of this tier: Goon, Char or Hero
+        #[arg(long)]
+        tier: Option<Tier>,
+        /// Only the profiles of this class
+        #[arg(long)]
+        class: Option<String>,
+        /// Only the profiles with this special ability, modifiers included
+        #[arg(long)]
+        ability: Option<String>,
+        /// Only the profiles with an action of this weapon
+        #[arg(long)]
+        weapon: Option<String>,
+        /// Only the profiles in this folder, or without one if empty
+        #[arg(long)]
+        folder: Option<String>,
+        /// Only the profiles with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// The lowest cost in points
+        #[arg(long)]
+        min_cost: Option<u32>,
+        /// The highest cost in points
+        #[arg(long)]
+        max_cost: Option<u32>,
+        /// The order: saved, name, cost or class
+        #[arg(long, default_value = "saved")]
+        sort: ProfileSort,
+        /// Reverses the order
+        #[arg(long)]
+        desc: bool,
+    },
+
+    /// Lists the variants of a profile, one per weapon or per combination of modifiers
+    Variants {
+        /// The save file with the profile
+        save: String,
+        /// The name or ID of the base profile
+        #[arg(long)]
+        profile: String,
+        /// What changes between the variants: weapons or modifiers
+        #[arg(long, default_value = "weapons")]
+        by: VariantKind,
+        /// Adds the variants to the save file
+        #[arg(long)]
+        add: bool,
+    },
+
+    /// Searches the best builds of a class within a points budget
+    Builds {
+        /// The class to build
+        #[arg(long)]
+        class: String,
+        /// The maximum final cost of the builds
+        #[arg(long)]
+        budget: u32,
+        /// How the builds are ranked: analytics, cheapest or coverage
+        #[arg(long, default_value = "analytics")]
+        score: BuildScore,
+        /// The number of builds to print
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+
+    /// Prints the units added, removed and changed from a save file to another
+    Diff {
+        /// The older save file
+        old: String,
+        /// The newer save file
+        new: String,
+    },
+
+    /// Works on ruleset folders and files
+    Ruleset {
+        #[command(subcommand)]
+        command: RulesetCommand,
+    },
+
+    /// Prints a share code (or link) for a save file or one of its rosters
+    Share {
+        /// The save file to share
+        save: String,
+        /// The name or ID of the roster to share, with just its profiles. Everything is shared if not set
+        #[arg(long)]
+        roster: Option<String>,
+        /// The address of the web app, to print a link instead of the bare code
+        #[arg(long)]
+        url: Option<String>,
+    },
+
+    /// Converts a ruleset, pack, classes, weapons or abilities file between JSON, YAML and TOML, by the file extensions
+    Convert {
+        /// The file to convert
+        input: String,
+        /// The file to create, for example classes.yaml or pack.toml
+        output: String,
+    },
+
+    /// Writes the weapons and classes in use to spreadsheet (CSV) files, for balancing
+    ExportCsv {
+        /// The weapons file to create, one row per weapon action
+        #[arg(long)]
+        weapons: Option<String>,
+        /// The classes file to create, one row per class
+        #[arg(long)]
+        classes: Option<String>,
+    },
+
+    /// Builds a ruleset file from spreadsheet (CSV) files, the rules in use filling what is not given
+    ImportCsv {
+        /// The weapons file, as written by export-csv
+        #[arg(long)]
+        weapons: Option<String>,
+        /// The classes file, as written by export-csv
+        #[arg(long)]
+        classes: Option<String>,
+        /// The ruleset file to create, in JSON, YAML or TOML by its extension
+        #[arg(long)]
+        out: String,
+        /// Writes the ruleset even if it has problems
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Prints the JSON Schema of a file format: classes, weapons, abilities, ruleset, pack or save
+    Schema {
+        /// The file format
+        kind: SchemaKind,
+        /// The file to write the schema to, printed if not set
+        #[arg(long)]
+        out: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesetCommand {
+    /// Compares two ruleset folders (classes.json and weapons.json) and the saved profiles they affect
+    Diff {
+        /// The folder of the current ruleset
+        old: String,
+        /// The folder of the new ruleset
+        new: String,
+        /// A save file whose profiles are checked against the new ruleset
+        #[arg(long)]
+        save: Option<String>,
+    },
+}
+
+pub fn run_command(command: Command, rules: &RulesetContext) -> Result<(), String> {
+    match command {
+        Command::ExportPng { save, out, dpi, abilities, theme, notes } => export_png(&save, &out, &card_options(dpi, abilities, load_theme(&theme)?, rules.abilities(), notes)),
+        Command::ExportPdf { save, out, dpi, abilities, theme, notes, faction, roster, logo, no_reference } => {
+            let back = card_back(faction, roster, logo.as_deref())?;
+            export_pdf(&save, &out, PdfCardOptions { card: card_options(dpi, abilities, load_theme(&theme)?, rules.abilities(), notes), include_reference: !no_reference, back })
+        },
+        Command::ExportAscii { save, out, abilities } => export_ascii(&save, &out, abilities, rules.abilities()),
+        Command::Validate { classes, weapons, glossary } => validate(rules, &classes, &weapons, &glossary),
+        Command::ValidateRoster { save, roster, rules: rules_path } => validate_roster(&save, roster.as_deref(), &rules_path, rules),
+        Command::ExportRoster { save, roster, format, out, rules: rules_path, force } => export_roster_command(&save, roster.as_deref(), format, out, &rules_path, force, rules),
+        Command::ImportRoster { list, save, strict } => import_roster_command(&list, &save, strict, rules),
+        Command::Merge { save, import, on_conflict, out } => merge(&save, &import, on_conflict, out.as_deref()),
+        Command::List { save, name, tier, class, ability, weapon, folder, tag, min_cost, max_cost, sort, desc } => {
+            let query = ProfileQuery { name, tier, class_name: class, ability, weapon, folder, tag, min_cost, max_cost, sort, descending: desc };
+            list(&save, &query, rules)
+        },
+        Command::Variants { save, profile, by, add } => variants(&save, &profile, by, add, rules),
+        Command::Builds { class, budget, score, top } => builds(&class, budget, score, top, rules),
+        Command::Diff { old, new } => diff(&old, &new),
+        Command::Ruleset { command: RulesetCommand::Diff { old, new, save } } => ruleset_diff(&old, &new, save.as_deref()),
+        Command::Share { save, roster, url } => share(&save, roster.as_deref(), url.as_deref()),
+        Command::Schema { kind, out } => schema(kind, out.as_deref()),
+        Command::ExportCsv { weapons, classes } => export_csv(weapons.as_deref(), classes.as_deref(), rules),
+        Command::ImportCsv { weapons, classes, out, force } => import_csv(weapons.as_deref(), classes.as_deref(), &out, force, rules),
+        Command::Convert { input, output } => convert_rules_file(&input, &output).map_err(|err| format!("Error converting: {}", err)),
+    }
+}
+
+pub fn card_options(dpi: u32, ability_display: AbilityDisplay, theme: CardTheme, abilities: &AbilitiesConfig, show_notes: bool) -> CardOptions {
+    CardOptions { dpi, abilities: abilities.clone(), ability_display, theme, show_notes }
+}
+
+// An empty name is the classic theme. Anything that is not a bundled theme is read as a file.
+pub fn load_theme(name_or_path: &str) -> Result<CardTheme, String> {
+    if name_or_path.is_empty() {
+        return Ok(CardTheme::default());
+    }
+    if let Some(theme) = get_themes("").find(name_or_path) {
+        return Ok(theme.clone());
+    }
+    load_theme_file(name_or_path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::InvalidData => format!("Error reading theme {}", err),
+        _ => format!("Not a bundled theme, and the file can't be read: {}", err),
+    })
+}
+
+fn export_png(save: &str, out: &str, options: &CardOptions) -> Result<(), String> {
+    let profiles = load_profiles(save).map_err(|err| format!("Error loading profiles from file {}: {}", save, err))?;
+    let files = create_png_cards(&profiles, out, options).map_err(|err| format!("Error exporting cards: {}", err))?;
+    for file in &files {
+        println!("Written {}", file);
+    }
+    println!("{} cards exported to {}.", files.len(), out);
+    Ok(())
+}
+
+fn export_pdf(save: &str, out: &str, options: PdfCardOptions) -> Result<(), String> {
+    let profiles = load_profiles(save).map_err(|err| format!("Error loading profiles from file {}: {}", save, err))?;
+    create_pdf_cards(&profiles, &options, out).map_err(|err| format!("Error exporting cards: {}", err))?;
+    println!("{} cards exported to {}.", profiles.len(), out);
+    Ok(())
+}
+
+fn export_ascii(save: &str, out: &str, ability_display: AbilityDisplay, abilities: &AbilitiesConfig) -> Result<(), String> {
+    let profiles = load_profiles(save).map_err(|err| format!("Error loading profiles from file {}: {}", save, err))?;
+    let profiles_count = profiles.len();
+    create_pdf_ascii_with_abilities(profiles, abilities, ability_display, out);
+    println!("{} profiles exported to {}.", profiles_count, out);
+    Ok(())
+}
+
+// The files given replace the ones of the ruleset packs.
+fn validate(rules: &RulesetContext, classes_path: &str, weapons_path: &str, glossary_path: &str) -> Result<(), String> {
+    let from_files;
+    let checked = if classes_path.is_empty() && weapons_path.is_empty() && glossary_path.is_empty() {
+        rules
+    } else {
+        // A file that can't be read or parsed is reported like the other problems
+        let read_error = |err: std::io::Error| format!("Error reading {}", err); // The error names the file
+        let classes = if classes_path.is_empty() { rules.classes_config().clone() } else { load_classes(classes_path).map_err(read_error)? };
+        let weapons = if weapons_path.is_empty() { rules.weapons_config().clone() } else { load_weapons(weapons_path).map_err(read_error)? };
+        let glossary = if glossary_path.is_empty() { rules.abilities().clone() } else { load_abilities(glossary_path).map_err(read_error)? };
+        from_files = RulesetContext::new(Ruleset::new(classes, weapons), glossary);
+        &from_files
+    };
+    if checked.problems().is_empty() {
+        println!("The classes and weapons are valid and all the special abilities are defined.");
+        return Ok(());
+    }
+    for problem in checked.problems() {
+        println!("{}", problem);
+    }
+    Err(format!("{} problems found.", checked.problems().len()))
+}
+
+// The list-building rules of the ruleset in use, unless a file is given.
+fn roster_rules(rules_path: &str, rules: &RulesetContext) -> Result<RosterRules, String> {
+    if rules_path.is_empty() {
+        return Ok(rules.roster_rules().clone());
+    }
+    load_roster_rules(rules_path).map_err(|err| format!("Error reading {}", err))
+}
+
+fn validate_roster(save: &str, roster_name: Option<&str>, rules_path: &str, rules: &RulesetContext) -> Result<(), String> {
+    let save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+    let rules = roster_rules(rules_path, rules)?;
+    let rosters = select_rosters(&save_data, roster_name)?;
+
+    let mut invalid_rosters = 0;
+    for roster in rosters {
+        let violations = roster.validate(&save_data.profiles, &rules);
+        if violations.is_empty() {
+            println!("{}: valid ({} points)", roster.name, roster.total_cost(&save_data.profiles));
+        } else {
+            invalid_rosters += 1;
+            println!("{}: {} problems", roster.name, violations.len());
+            for violation in &violations {
+                println!("  - {}", violation);
+            }
+        }
+    }
+
+    if invalid_rosters > 0 {
+        return Err(format!("{} rosters don't follow the list-building rules.", invalid_rosters));
+    }
+    Ok(())
+}
+
+fn export_roster_command(save: &str, roster_name: Option<&str>, format: RosterFormat, out: Option<String>, rules_path: &str, force: bool, rules: &RulesetContext) -> Result<(), String> {
+    let save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+    let rosters = select_rosters(&save_data, roster_name)?;
+    if rosters.len() > 1 {
+        return Err(format!("The save file has {} rosters, choose one with --roster", rosters.len()));
+    }
+    let roster = rosters[0];
+
+    // The list is checked before exporting it
+    let violations = roster.validate(&save_data.profiles, &roster_rules(rules_path, rules)?);
+    for violation in &violations {
+        eprintln!("Warning: {}", violation);
+    }
+    if !violations.is_empty() && !force {
+        return Err(format!("{} doesn't follow the list-building rules, use --force to export it anyway.", roster.name));
+    }
+
+    let content = export_roster(roster, &save_data.profiles, rules.weapons_config(), format)
+        .map_err(|err| format!("Error exporting the roster: {}", err))?;
+    let out = match out {
+        Some(out) => out,
+        None if format == RosterFormat::Pdf => format!("{}.pdf", roster.name),
+        None => {
+            print!("{}", String::from_utf8_lossy(&content));
+            return Ok(());
+        },
+    };
+    std::fs::write(&out, content).map_err(|err| format!("Error writing {}: {}", out, err))?;
+    println!("{} exported to {}.", roster.name, out);
+    Ok(())
+}
+
+fn import_roster_command(list: &str, save: &str, strict: bool, rules: &RulesetContext) -> Result<(), String> {
+    let text = std::fs::read_to_string(list).map_err(|err| format!("Error reading {}: {}", list, err))?;
+    let import = import_roster_text(&text, rules.classes_config(), rules.weapons_config());
+    for issue in &import.issues {
+        eprintln!("Warning: {}", issue);
+    }
+    if !import.issues.is_empty() && strict {
+        return Err(format!("{} lines of {} could not be read.", import.issues.len(), list));
+    }
+
+    // The imported profiles are added to the existing save file
+    let mut save_data = if std::path::Path::new(save).exists() {
+        load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?
+    } else {
+        SaveData::new(Vec::new(), Vec::new())
+    };
+    println!("{}: {} profiles, {} points.", import.roster.name, import.profiles.len(), import.roster.total_cost(&import.profiles));
+    save_data.profiles.extend(import.profiles);
+    save_data.rosters.push(import.roster);
+    save_save_data(&save_data, save).map_err(|err| format!("Error writing {}: {}", save, err))?;
+    println!("Roster added to {}.", save);
+    Ok(())
+}
+
+fn merge(save: &str, import: &str, resolution: ConflictResolution, out: Option<&str>) -> Result<(), String> {
+    let mut save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+    let imported = load_save_data(import).map_err(|err| format!("Error loading file {}: {}", import, err))?;
+    let conflicts = save_data.conflicts(&imported);
+    if !conflicts.is_empty() {
+        println!("Already existing: {}", conflicts.join(", "));
+    }
+
+    let report = save_data.merge(imported, resolution);
+    let out = out.unwrap_or(save);
+    save_save_data(&save_data, out).map_err(|err| format!("Error writing {}: {}", out, err))?;
+    println!("Merged into {}: {}.", out, report);
+    Ok(())
+}
+
+fn variants(save: &str, profile_name: &str, kind: VariantKind, add: bool, rules: &RulesetContext) -> Result<(), String> {
+    let mut save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+    let base = save_data.profiles.iter()
+        .find(|profile| profile.id == profile_name || profile.name == profile_name)
+        .ok_or_else(|| format!("There is no profile named {}", profile_name))?;
+    let class = rules.class(&base.class_name)
+        .ok_or_else(|| format!("Unknown class {} of {}", base.class_name, base.name))?;
+
+    let variants = generate_variants(base, kind, class, rules.weapons_config(), &save_data.profiles);
+    for variant in &variants {
+        let actions: Vec<&str> = variant.actions.iter().map(|action| action.name.as_str()).collect();
+        println!("{} - {} points - {}", variant.name, variant.get_final_profile().cost, actions.join(", "));
+    }
+
+    if add {
+        save_data.profiles.extend(variants);
+        save_save_data(&save_data, save).map_err(|err| format!("Error writing {}: {}", save, err))?;
+        println!("Variants added to {}.", save);
+    }
+    Ok(())
+}
+
+fn builds(class_name: &str, budget: u32, score: BuildScore, top: usize, rules: &RulesetContext) -> Result<(), String> {
+    let class = rules.classes().iter()
+        .find(|class| class.name.eq_ignore_ascii_case(class_name))
+        .ok_or_else(|| format!("Unknown class {}", class_name))?;
+
+    let builds = search_builds(class, rules.weapons_config(), budget, &score, top);
+    if builds.is_empty() {
+        return Err(format!("No {} build fits in {} points", class.name, budget));
+    }
+    for build in &builds {
+        let modifiers: Vec<&str> = build.profile.selected_modifiers.iter().map(|modifier| modifier.id.as_str()).collect();
+        // The weapon is named, since several weapons offer actions with the same name
+        let actions: Vec<String> = build.profile.actions.iter().map(|action| match rules.weapon_of(action) {
+            Some(weapon) => format!("{} ({})", action.name, weapon.name),
+            None => action.name.clone(),
+        }).collect();
+        let modifiers = if modifiers.is_empty() { String::new() } else { format!(" ({})", modifiers.join(", ")) };
+        let actions = if actions.is_empty() { "no actions".to_string() } else { actions.join(", ") };
+        println!("{:>7.2}  {:>3} pts  {}{} - {}", build.score, build.cost, build.profile.class_name, modifiers, actions);
+    }
+    Ok(())
+}
+
+fn list(save: &str, query: &ProfileQuery, rules: &RulesetContext) -> Result<(), String> {
+    let save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+    let found = query.apply(&save_data.profiles, rules);
+    for index in &found {
+        let profile = save_data.profiles[*index].get_final_profile();
+        let mut line = format!("{:>4} pts  {} - {} ({})", profile.cost, profile.name, profile.class_name, profile.tier);
+        if !profile.folder.is_empty() {
+            line += &format!(" in {}", profile.folder);
+        }
+        if !profile.tags.is_empty() {
+            line += &format!(" [{}]", profile.tags.join(", "));
+        }
+        println!("{}", line);
+    }
+    println!("{} of {} profiles.", found.len(), save_data.profiles.len());
+    Ok(())
+}
+
+fn diff(old: &str, new: &str) -> Result<(), String> {
+    let old_data = load_save_data(old).map_err(|err| format!("Error loading file {}: {}", old, err))?;
+    let new_data = load_save_data(new).map_err(|err| format!("Error loading file {}: {}", new, err))?;
+    let diff = CollectionDiff::between(&old_data.profiles, &new_data.profiles);
+    if diff.is_empty() {
+        println!("No differences.");
+    } else {
+        println!("{}", diff);
+    }
+    Ok(())
+}
+
+fn ruleset_diff(old: &str, new: &str, save: Option<&str>) -> Result<(), String> {
+    let (old_classes, old_weapons) = load_ruleset(old).map_err(|err| format!("Error loading the ruleset {}: {}", old, err))?;
+    let (new_classes, new_weapons) = load_ruleset(new).map_err(|err| format!("Error loading the ruleset {}: {}", new, err))?;
+
+    let changes = diff_rulesets(&old_classes, &old_weapons, &new_classes, &new_weapons);
+    if changes.is_empty() {
+        println!("The rulesets are the same.");
+    }
+    for change in &changes {
+        println!("{}", change);
+    }
+
+    if let Some(save) = save {
+        let save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+        let impacts = ruleset_impact(&save_data.profiles, &new_classes, &new_weapons);
+        println!();
+        println!("{} of {} profiles in {} would change.", impacts.len(), save_data.profiles.len(), save);
+        for impact in &impacts {
+            println!("~ {}", impact.name);
+            for change in &impact.changes {
+                println!("    {}", change);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn share(save: &str, roster_name: Option<&str>, url: Option<&str>) -> Result<(), String> {
+    let save_data = load_save_data(save).map_err(|err| format!("Error loading file {}: {}", save, err))?;
+    let shared = match roster_name {
+        Some(name) => {
+            let roster = save_data.find_roster(name).ok_or_else(|| format!("There is no roster named {}", name))?;
+            SaveData::from_roster(roster, &save_data.profiles)
+        },
+        None => save_data,
+    };
+    let code = encode_share_code(&shared).map_err(|err| format!("Error creating the share code: {}", err))?;
+    match url {
+        Some(url) => println!("{}", share_link(url, &code)),
+        None => println!("{}", code),
+    }
+    Ok(())
+}
+
+fn export_csv(weapons_path: Option<&str>, classes_path: Option<&str>, rules: &RulesetContext) -> Result<(), String> {
+    if weapons_path.is_none() && classes_path.is_none() {
+        return Err("Nothing to export, set --weapons or --classes".to_string());
+    }
+    if let Some(path) = weapons_path {
+        let csv = weapons_to_csv(rules.weapons_config()).map_err(|err| format!("Error exporting the weapons: {}", err))?;
+        std::fs::write(path, csv).map_err(|err| format!("Error writing {}: {}", path, err))?;
+    }
+    if let Some(path) = classes_path {
+        let csv = classes_to_csv(rules.classes_config()).map_err(|err| format!("Error exporting the classes: {}", err))?;
+        std::fs::write(path, csv).map_err(|err| format!("Error writing {}: {}", path, err))?;
+    }
+    Ok(())
+}
+
+fn import_csv(weapons_path: Option<&str>, classes_path: Option<&str>, out: &str, force: bool, rules: &RulesetContext) -> Result<(), String> {
+    if weapons_path.is_none() && classes_path.is_none() {
+        return Err("Nothing to import, set --weapons or --classes".to_string());
+    }
+    let read = |path: &str| std::fs::read_to_string(path).map_err(|err| format!("Error reading {}: {}", path, err));
+
+    // Every row that can't be read is reported before giving up
+    let mut row_errors = Vec::new();
+    let weapons = match weapons_path {
+        Some(path) => weapons_from_csv(&read(path)?).unwrap_or_else(|errors| {
+            row_errors.extend(errors.into_iter().map(|error| format!("{}, {}", path, error)));
+            rules.weapons_config().clone()
+        }),
+        None => rules.weapons_config().clone(),
+    };
+    let classes = match classes_path {
+        Some(path) => classes_from_csv(&read(path)?).unwrap_or_else(|errors| {
+            row_errors.extend(errors.into_iter().map(|error| format!("{}, {}", path, error)));
+            rules.classes_config().clone()
+        }),
+        None => rules.classes_config().clone(),
+    };
+    if !row_errors.is_empty() {
+        for error in &row_errors {
+            println!("{}", error);
+        }
+        return Err(format!("{} rows could not be read.", row_errors.len()));
+    }
+
+    let imported = RulesetContext::new(Ruleset::new(classes, weapons), rules.abilities().clone());
+    for problem in imported.problems() {
+        eprintln!("Warning: {}", problem);
+    }
+    if !imported.problems().is_empty() && !force {
+        return Err(format!("{} problems found, use --force to write the ruleset anyway.", imported.problems().len()));
+    }
+    let content = FileFormat::from_path(std::path::Path::new(out)).write(&imported.ruleset()).map_err(|err| format!("Error writing {}: {}", out, err))?;
+    std::fs::write(out, content).map_err(|err| format!("Error writing {}: {}", out, err))
+}
+
+fn schema(kind: SchemaKind, out: Option<&str>) -> Result<(), String> {
+    let json = schema_json(kind).map_err(|err| format!("Error creating the {} schema: {}", kind.name(), err))?;
+    match out {
+        Some(out) => std::fs::write(out, json).map_err(|err| format!("Error writing {}: {}", out, err)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        },
+    }
+}
+
+// The roster with the given name or ID, or all of them.
+fn select_rosters<'a>(save_data: &'a SaveData, roster_name: Option<&str>) -> Result<Vec<&'a Roster>, String> {
+    match roster_name {
+        Some(name) => save_data.find_roster(name)
+            .map(|roster| vec![roster])
+            .ok_or_else(|| format!("There is no roster named {}", name)),
+        None if save_data.rosters.is_empty() => Err("The save file has no rosters".to_string()),
+        None => Ok(save_data.rosters.iter().collect()),
+    }
+}
+
+// The card back is only printed if there is something to put on it.
+pub fn card_back(faction_name: String, roster_name: String, logo_path: Option<&str>) -> Result<Option<CardBack>, String> {
+    let logo_png = match logo_path {
+        Some(path) if !path.is_empty() => Some(std::fs::read(path).map_err(|err| format!("Error reading logo {}: {}", path, err))?),
+        _ => None,
+    };
+    if faction_name.is_empty() && roster_name.is_empty() && logo_png.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(CardBack { faction_name, roster_name, logo_png }))
+}
@@ -1,22 +1,50 @@
 mod app_state;
+mod commands;
 
 use app_state::AppState;
 use app_state::MenuStates;
+use commands::{Cli, card_back, card_options, run_command};
 
-use fsd28_lib::models::class::ClassesConfig;
 use fsd28_lib::create_profile;
-use fsd28_lib::get_classes;
-use fsd28_lib::get_weapons;
+use fsd28_lib::load_ruleset_packs;
+use fsd28_lib::RulesetContext;
+use fsd28_lib::{ProfileQuery, ProfileSort};
 use fsd28_lib::utils::pdf_ascii_generator::create_pdf_ascii;
-use fsd28_lib::load_profiles;
-use fsd28_lib::save_profiles;
+use fsd28_lib::utils::pdf_card_generator::{create_pdf_cards, PdfCardOptions};
+use fsd28_lib::utils::png_card_generator::{create_png_cards, DEFAULT_DPI};
+use fsd28_lib::load_save_data;
+use fsd28_lib::AbilityDisplay;
+use fsd28_lib::ConflictResolution;
+use fsd28_lib::CardTheme;
+use fsd28_lib::save_save_data;
 
 use dialoguer::{theme::ColorfulTheme, Select, Input};
 
+use clap::Parser;
+
 
 
 fn main() {
-    let mut app_state = AppState::new();
+
+    let cli = Cli::parse();
+    let rules = match load_ruleset_packs(&cli.rulesets) {
+        Ok(resolved) => RulesetContext::from_resolved(resolved),
+        Err(err) => {
+            eprintln!("Error loading the rulesets: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // Non-interactive commands, for batch operations
+    if let Some(command) = cli.command {
+        if let Err(err) = run_command(command, &rules) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut app_state = AppState::new(rules);
     let mut menu_state = MenuStates::MainMenu;
 
     while menu_state != MenuStates::Exit {
@@ -28,6 +56,7 @@ fn main() {
             MenuStates::SelectProfile => select_profile_dialog(&mut app_state),
             MenuStates::EditProfile => edit_profile_dialog(&mut app_state),
             MenuStates::PrintAllProfiles => print_all_profiles_dialog(&mut app_state),
+            MenuStates::ExportPng => export_png_dialog(&mut app_state),
             MenuStates::AddAction => add_action_dialog(&mut app_state),
             //MenuStates::AddEquip => add_equip_action(&mut app_state,)
             MenuStates::EditName => edit_name_dialog(&mut app_state),
@@ -40,7 +69,7 @@ fn main() {
 
 
 fn main_menu_dialog(_: &mut AppState) -> MenuStates{
-    let selections = &["Create", "Save", "Load", "Select", "Print", "Exit"];
+    let selections = &["Create", "Save", "Load", "Select", "Print", "Export PNG", "Exit"];
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("This is the FSD28 profile creator. What would you like to do?")
         .default(0)
@@ -54,6 +83,7 @@ fn main_menu_dialog(_: &mut AppState) -> MenuStates{
         "Load" => MenuStates::LoadProfiles,
         "Select" => MenuStates::SelectProfile,
         "Print" => MenuStates::PrintAllProfiles,
+        "Export PNG" => MenuStates::ExportPng,
         "Exit" => MenuStates::Exit,
         _ => unreachable!(),
     }
@@ -68,11 +98,7 @@ fn create_profile_dialog(app_state: &mut AppState) -> MenuStates {
         .unwrap();
 
     // Now asking for the class
-    let all_classes: ClassesConfig = get_classes("");
-    let options: Vec<String> = all_classes
-    .classes.iter()
-    .map(|class| class.name.clone())
-    .collect::<Vec<String>>();
+    let options: Vec<String> = app_state.rules().class_names();
     
     // Selecting the class here
     let selection = Select::with_theme(&ColorfulTheme::default())
@@ -81,7 +107,7 @@ fn create_profile_dialog(app_state: &mut AppState) -> MenuStates {
     .items(&options[..])
     .interact()
     .unwrap();
-    let selected_class = all_classes.classes[selection].clone();
+    let selected_class = app_state.rules().classes()[selection].clone();
 
     app_state.add_profile(create_profile(
         selected_name, 
@@ -92,29 +118,46 @@ fn create_profile_dialog(app_state: &mut AppState) -> MenuStates {
 
 fn select_profile_dialog(app_state: &mut AppState) -> MenuStates {
 
-    let mut options: Vec<String> = app_state
-        .get_all_profiles()
-        .iter()
-        .map(|profile| profile.name.clone())
-        .collect::<Vec<String>>();
+    // Sorted by name, and narrowed down by searching until one is picked
+    let profiles = app_state.get_all_profiles();
+    let mut query = ProfileQuery { sort: ProfileSort::Name, ..Default::default() };
+    loop {
+        let found = query.apply(&profiles, app_state.rules());
+        let mut options: Vec<String> = found
+            .iter()
+            .map(|index| {
+                let profile = profiles[*index].get_final_profile();
+                format!("{} - {} ({} pts)", profile.name, profile.class_name, profile.cost)
+            })
+            .collect::<Vec<String>>();
+
+        // Add the options to search and to return to the main menu
+        options.push(if query.name.is_empty() { "Search by Name".to_string() } else { format!("Search by Name (now \"{}\")", query.name) });
+        options.push("Return to Main Menu".to_string());
 
-    // Add an option to return to the main menu
-    options.push("Return to Main Menu".to_string());
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select a profile or return to the main menu")
+            .default(0)
+            .items(&options[..])
+            .interact()
+            .unwrap();
 
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select a profile or return to the main menu")
-        .default(0)
-        .items(&options[..])
-        .interact()
-        .unwrap();
+        if selection == options.len() - 1 {
+            // The last option (Return to Main Menu) was selected
+            return MenuStates::MainMenu;
+        }
+        if selection == options.len() - 2 {
+            query.name = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Part of the name, empty for all")
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+            continue;
+        }
 
-    if selection == options.len() - 1 {
-        // The last option (Return to Main Menu) was selected
-        MenuStates::MainMenu
-    } else {
         // Update the selected index in AppState
-        app_state.set_selected(selection);
-        MenuStates::EditProfile // or any other state you want to transition to
+        app_state.set_selected(found[selection]);
+        return MenuStates::EditProfile; // or any other state you want to transition to
     }
 }
 
@@ -144,18 +187,46 @@ fn edit_profile_dialog(app_state: &mut AppState) -> MenuStates {
 }
 
 fn print_all_profiles_dialog(app_state: &mut AppState) -> MenuStates { 
-    let selections = &["Yes", "Return"];
+    let selections = &["ASCII", "Cards", "Return"];
     let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("This will print ALL profiles created. Continue?")
+        .with_prompt("This will print ALL profiles created. Which format?")
         .default(0)
         .items(&selections[..])
         .interact()
         .unwrap();
 
     match selections[selection] {
-        "Yes" => {
+        "ASCII" => {
             create_pdf_ascii(app_state.get_all_profiles(), "out.pdf");
         },
+        "Cards" => {
+            let faction_name: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Faction name for the card backs (empty for no backs)")
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+            let roster_name: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Roster name for the card backs")
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+            let logo_path: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Logo PNG for the card backs (empty for none)")
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+
+            match card_back(faction_name, roster_name, Some(&logo_path)) {
+                Ok(back) => {
+                    let options = PdfCardOptions { card: card_options(DEFAULT_DPI, AbilityDisplay::Names, CardTheme::default(), app_state.rules().abilities(), false), include_reference: true, back };
+                    match create_pdf_cards(&app_state.get_all_profiles(), &options, "cards.pdf") {
+                        Ok(_) => println!("Cards exported to cards.pdf"),
+                        Err(err) => println!("Error exporting cards: {:?}", err),
+                    }
+                },
+                Err(err) => println!("{}", err),
+            }
+        },
         "Return" => (),
         _ => unreachable!(),
     };
@@ -163,6 +234,26 @@ fn print_all_profiles_dialog(app_state: &mut AppState) -> MenuStates {
     MenuStates::MainMenu
 }
 
+fn export_png_dialog(app_state: &mut AppState) -> MenuStates {
+    let out_dir: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the folder to export the cards to")
+        .default("cards".to_string())
+        .interact_text()
+        .unwrap();
+    let dpi: u32 = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter the resolution (DPI)")
+        .default(DEFAULT_DPI)
+        .interact_text()
+        .unwrap();
+
+    match create_png_cards(&app_state.get_all_profiles(), &out_dir, &card_options(dpi, AbilityDisplay::Names, CardTheme::default(), app_state.rules().abilities(), false)) {
+        Ok(files) => println!("{} cards exported to {}.", files.len(), out_dir),
+        Err(err) => println!("Error exporting cards: {:?}", err),
+    }
+
+    MenuStates::MainMenu
+}
+
 fn add_action_dialog(app_state: &mut AppState) -> MenuStates {
 
     // Now asking for the Action to add.
@@ -170,7 +261,7 @@ fn add_action_dialog(app_state: &mut AppState) -> MenuStates {
 
     // BROKEN BY THE UPDATES - TODO TO FIX
         // Default actions are always there.
-        let all_weapons = get_weapons("");
+        let all_weapons = app_state.rules().weapons_config().clone();
 
         // Filling the options
         let mut options: Vec<String> = all_weapons
@@ -221,17 +312,45 @@ fn load_profile_dialog(app_state: &mut AppState) -> MenuStates  {
     let path = path + ".sav"; 
 
     println!("Loading profiles from: {}", path);
-    match load_profiles(&path) {
-        Ok(loaded_profiles)  => {
-            app_state.clear_profiles();
-            app_state.set_all_profiles(loaded_profiles);
-            println!("{} profiles loaded.", app_state.get_all_profiles().len());
-            MenuStates::MainMenu},
+    let save_data = match load_save_data(&path) {
+        Ok(save_data) => save_data,
         Err(_) => {
             println!("Error loading profiles from file {}", path);
-            MenuStates::MainMenu},
+            return MenuStates::MainMenu;
+        },
+    };
+
+    // With profiles already there, the loaded ones can be added to them
+    let merge = !app_state.get_all_profiles().is_empty() && Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Replace the current profiles or merge the loaded ones into them?")
+        .default(0)
+        .items(&["Replace", "Merge"])
+        .interact()
+        .unwrap() == 1;
+
+    if !merge {
+        app_state.set_save_data(save_data);
+        println!("{} profiles loaded.", app_state.get_all_profiles().len());
+        return MenuStates::MainMenu;
     }
 
+    let conflicts = app_state.get_save_data().conflicts(&save_data);
+    let resolution = if conflicts.is_empty() {
+        ConflictResolution::KeepBoth
+    } else {
+        println!("Already existing: {}", conflicts.join(", "));
+        let choices = [ConflictResolution::KeepBoth, ConflictResolution::Overwrite, ConflictResolution::Skip];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("What to do with them?")
+            .default(0)
+            .items(&["Keep both", "Overwrite", "Skip"])
+            .interact()
+            .unwrap();
+        choices[selection]
+    };
+    let report = app_state.merge_save_data(save_data, resolution);
+    println!("Merged: {}.", report);
+    MenuStates::MainMenu
 }
 
 fn save_profile_dialog(app_state: &mut AppState) -> MenuStates  {
@@ -242,7 +361,7 @@ fn save_profile_dialog(app_state: &mut AppState) -> MenuStates  {
     let path = path + ".sav"; 
 
     println!("Saving {} profiles to: {}", app_state.get_all_profiles().len(), path);
-    match save_profiles(app_state.get_all_profiles().clone(), &path) {
+    match save_save_data(&app_state.get_save_data(), &path) {
         Ok(_) => (),
         Err(err) => println!("Error saving to file: {:?}", err),
     }
@@ -8,6 +8,9 @@ use std::io::Write;
 // Hardcoding the classes for web applications or general default info
 const CLASSES_JSON: &str = include_str!("../data/classes.json");
 const WEAPONS_JSON: &str = include_str!("../data/weapons.json");
+const ABILITIES_JSON: &str = include_str!("../data/abilities.json");
+const THEMES_JSON: &str = include_str!("../data/themes.json");
+const ROSTER_RULES_JSON: &str = include_str!("../data/roster_rules.json");
 
 
 // Expose key functions or structs if needed
@@ -19,31 +22,164 @@ pub use models::weapon::Weapon; // TODO this might be better incapsulated?
 pub use models::weapon::WeaponOption; // TODO this might be better incapsulated?
 pub use models::action::Action; // TODO this might be better incapsulated?
 pub use models::modifier::Modifier; // TODO this might be better incapsulated?
+pub use models::ability::AbilitiesConfig;
+pub use models::ability::Ability;
+pub use models::ability::AbilityDisplay;
+pub use models::card_theme::CardTheme;
+pub use models::card_theme::ThemesConfig;
+pub use models::roster::Roster;
+pub use models::roster::RosterEntry;
+pub use models::roster_rules::RosterRules;
+pub use models::save_data::SaveData;
+pub use models::save_data::ConflictResolution;
+pub use models::save_data::MergeReport;
+pub use models::profile_diff::ProfileDiff;
+pub use models::profile_diff::CollectionDiff;
+pub use models::profile_query::ProfileQuery;
+pub use models::profile_query::ProfileSort;
+pub use models::ruleset::Ruleset;
+pub use models::ruleset_pack::RulesetPack;
+pub use models::ruleset_pack::ResolvedRuleset;
+pub use models::ruleset_context::RulesetContext;
+
+use utils::file_format::{find_rules_file, read_rules_file, FileFormat};
 
 // GAME DATA LOADING
+// The get_ functions panic on a file that can't be read, the load_ ones return the error.
 pub fn get_classes(i_path : &str) -> ClassesConfig {
-    let file_content: String = 
-        if i_path.is_empty() {
-            CLASSES_JSON.to_string()
-        }
-        else {
-            read_to_string(i_path).expect("Failed to read file")
-        };
-
-    serde_json::from_str(&file_content).unwrap()
+    load_classes(i_path).expect("Failed to read file")
 }
 
 pub fn get_weapons(i_path: &str) -> WeaponsConfig {
-    let file_content: String = 
-        if i_path.is_empty() {
-            WEAPONS_JSON.to_string()
+    load_weapons(i_path).expect("Failed to read file")
+}
+
+pub fn get_abilities(i_path: &str) -> AbilitiesConfig {
+    load_abilities(i_path).expect("Failed to read file")
+}
+
+pub fn load_classes(i_path: &str) -> Result<ClassesConfig, std::io::Error> {
+    if i_path.is_empty() {
+        return Ok(serde_json::from_str(CLASSES_JSON).unwrap());
+    }
+    read_rules_file(std::path::Path::new(i_path))
+}
+
+pub fn load_weapons(i_path: &str) -> Result<WeaponsConfig, std::io::Error> {
+    if i_path.is_empty() {
+        return Ok(serde_json::from_str(WEAPONS_JSON).unwrap());
+    }
+    read_rules_file(std::path::Path::new(i_path))
+}
+
+pub fn load_abilities(i_path: &str) -> Result<AbilitiesConfig, std::io::Error> {
+    if i_path.is_empty() {
+        return Ok(serde_json::from_str(ABILITIES_JSON).unwrap());
+    }
+    read_rules_file(std::path::Path::new(i_path))
+}
+
+pub fn get_themes(i_path: &str) -> ThemesConfig {
+    load_themes(i_path).expect("Failed to read file")
+}
+
+pub fn load_themes(i_path: &str) -> Result<ThemesConfig, std::io::Error> {
+    if i_path.is_empty() {
+        return Ok(serde_json::from_str(THEMES_JSON).unwrap());
+    }
+    read_rules_file(std::path::Path::new(i_path))
+}
+
+// A single theme, as given to the card exports. Only the values that differ from the classic look are needed.
+pub fn load_theme_file(i_path: &str) -> Result<CardTheme, std::io::Error> {
+    read_rules_file(std::path::Path::new(i_path))
+}
+
+pub fn get_roster_rules(i_path: &str) -> RosterRules {
+    load_roster_rules(i_path).expect("Failed to read file")
+}
+
+pub fn load_roster_rules(i_path: &str) -> Result<RosterRules, std::io::Error> {
+    if i_path.is_empty() {
+        return Ok(serde_json::from_str(ROSTER_RULES_JSON).unwrap());
+    }
+    read_rules_file(std::path::Path::new(i_path))
+}
+
+// A ruleset folder with classes and weapons files (a missing file is the bundled one),
+// or a single ruleset file with both. The files can be JSON, YAML or TOML.
+pub fn load_ruleset(i_dir: &str) -> Result<(ClassesConfig, WeaponsConfig), std::io::Error> {
+    let dir = std::path::Path::new(i_dir);
+    if dir.is_file() {
+        let ruleset: Ruleset = read_rules_file(dir)?;
+        return Ok((ruleset.classes_config(), ruleset.weapons_config()));
+    }
+    if !dir.is_dir() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} is not a folder or a ruleset file", i_dir)));
+    }
+    let classes = match find_rules_file(dir, "classes") {
+        Some(path) => read_rules_file(&path)?,
+        None => get_classes(""),
+    };
+    let weapons = match find_rules_file(dir, "weapons") {
+        Some(path) => read_rules_file(&path)?,
+        None => get_weapons(""),
+    };
+    Ok((classes, weapons))
+}
+
+
+// The content of a ruleset file, in the format of the file name extension (JSON, YAML or TOML).
+// For the web app, which gets the content of the uploaded files rather than their path.
+pub fn parse_ruleset(i_name: &str, i_content: &str) -> Result<Ruleset, String> {
+    FileFormat::from_path(std::path::Path::new(i_name)).parse(i_content)
+        .map_err(|err| format!("{}: {}", i_name, err))
+}
+
+// A ruleset pack file (a plain ruleset file is a pack without a manifest), or a folder with a pack
+// file, or with any of the classes, weapons, abilities and roster_rules files. The files can be JSON, YAML or TOML.
+pub fn load_ruleset_pack(i_path: &str) -> Result<RulesetPack, std::io::Error> {
+    let path = std::path::Path::new(i_path);
+    if path.is_file() {
+        return read_rules_file(path);
+    }
+    if !path.is_dir() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} is not a ruleset pack", i_path)));
+    }
+    if let Some(manifest) = find_rules_file(path, "pack") {
+        return read_rules_file(&manifest);
+    }
+
+    // Each file only fills its own list
+    let mut pack = RulesetPack::default();
+    for name in ["classes", "weapons", "abilities"] {
+        if let Some(file) = find_rules_file(path, name) {
+            let part: RulesetPack = read_rules_file(&file)?;
+            pack.classes.extend(part.classes);
+            pack.weapons.extend(part.weapons);
+            pack.abilities.extend(part.abilities);
         }
-        else {
-            read_to_string(i_path).expect("Failed to read file")
-        };
-    serde_json::from_str(&file_content).unwrap()
+    }
+    if let Some(file) = find_rules_file(path, "roster_rules") {
+        pack.roster_rules = Some(read_rules_file(&file)?);
+    }
+    Ok(pack)
 }
 
+// The rules from the packs applied in order over the bundled ones.
+pub fn load_ruleset_packs(i_paths: &[String]) -> Result<ResolvedRuleset, std::io::Error> {
+    let packs = i_paths.iter().map(|path| load_ruleset_pack(path)).collect::<Result<Vec<_>, _>>()?;
+    models::ruleset_pack::resolve_packs(&packs).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+// Rewrites a ruleset, pack, classes, weapons or abilities file in the format of the output
+// file extension. The content is read as a pack, so nothing the loaders know is lost.
+pub fn convert_rules_file(i_path: &str, o_path: &str) -> Result<(), std::io::Error> {
+    let pack: RulesetPack = read_rules_file(std::path::Path::new(i_path))?;
+    let content = FileFormat::from_path(std::path::Path::new(o_path)).write(&pack)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", o_path, err)))?;
+    std::fs::write(o_path, content)
+}
 
 // PROFILES HANDLING
 
@@ -55,19 +191,33 @@ pub fn save_profiles(i_profiles: Vec<Profile>, i_path : &str) -> Result<(), std:
     let out_content = serde_json::to_string(&i_profiles).unwrap();
     let mut file = match File::create(i_path) {
         Ok(content) => content,
-        Err(_) => return Err(std::io::Error::new(std::io::ErrorKind::Other, "Couldn't create file!")),
+        Err(_) => return Err(std::io::Error::other("Couldn't create file!")),
     };
     file.write_all(out_content.as_bytes())
 }
 
+// Accepts both the save files with rosters and the older ones with just the profiles.
 pub fn load_profiles(i_path : &str) -> Result<Vec<Profile>, std::io::Error> {
+    load_save_data(i_path).map(|save_data| save_data.profiles)
+}
+
+pub fn save_save_data(i_save_data: &SaveData, i_path : &str) -> Result<(), std::io::Error> {
+    let out_content = i_save_data.to_json().map_err(std::io::Error::other)?;
+    let mut file = match File::create(i_path) {
+        Ok(content) => content,
+        Err(_) => return Err(std::io::Error::other("Couldn't create file!")),
+    };
+    file.write_all(out_content.as_bytes())
+}
+
+pub fn load_save_data(i_path : &str) -> Result<SaveData, std::io::Error> {
     let file_content = match read_to_string(i_path) {
         Ok(content) => content,
-        Err(_) => return Err(std::io::Error::new(std::io::ErrorKind::Other, "Couldn't read file!")),
+        Err(_) => return Err(std::io::Error::other("Couldn't read file!")),
     };
 
-    match serde_json::from_str(&file_content) {
-        Ok(profiles) => Ok(profiles),
+    match SaveData::from_json(&file_content) {
+        Ok(save_data) => Ok(save_data),
         Err(_) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse JSON")),
     }
 }
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::str::FromStr;
+
+use super::class::ClassesConfig;
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AbilitiesConfig {
+    pub abilities: Vec<Ability>,
+}
+
+// The definition of a special ability, as referenced by name from classes and modifiers.
+// The bundled glossary only has the names: an empty text is shown as the name alone.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Ability {
+    pub name: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+// How special abilities are shown by the renderers.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum AbilityDisplay {
+    #[default]
+    Names,      // Just the names, comma separated
+    RulesText,  // One line per ability, with its rules text
+    Footnotes,  // Numbered names, followed by the numbered rules texts
+}
+
+impl FromStr for AbilityDisplay {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "names" => Ok(AbilityDisplay::Names),
+            "rules" | "rules-text" => Ok(AbilityDisplay::RulesText),
+            "footnotes" => Ok(AbilityDisplay::Footnotes),
+            _ => Err(format!("Unknown ability display: {} (expected names, rules or footnotes)", value)),
+        }
+    }
+}
+
+impl AbilitiesConfig {
+
+    pub fn find(&self, name: &str) -> Option<&Ability> {
+        self.abilities.iter().find(|ability| ability.name == name)
+    }
+
+    // Checks that every ability referenced by the classes (directly or through a modifier)
+    // is defined, returning a message for each missing one.
+    pub fn validate_references(&self, classes: &ClassesConfig) -> Vec<String> {
+        let mut errors = Vec::<String>::new();
+        for class in &classes.classes {
+            for ability in &class.special_abilities {
+                if self.find(ability).is_none() {
+                    errors.push(format!("Class {} references the undefined ability \"{}\"", class.name, ability));
+                }
+            }
+            for modifier in &class.modifiers {
+                let ability = modifier.effects.get("ability").and_then(|value| value.as_str());
+                if let Some(ability) = ability {
+                    if self.find(ability).is_none() {
+                        errors.push(format!("Modifier {} of class {} references the undefined ability \"{}\"", modifier.id, class.name, ability));
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    // Formats a list of ability names as text lines, according to the display mode.
+    // Abilities missing from the glossary, or without a rules text, are always shown by name only.
+    pub fn format_abilities(&self, names: &[String], display: AbilityDisplay) -> Vec<String> {
+        if names.is_empty() {
+            return Vec::new();
+        }
+
+        match display {
+            AbilityDisplay::Names => vec![names.join(", ")],
+            AbilityDisplay::RulesText => names.iter()
+                .map(|name| match self.find(name) {
+                    Some(ability) if !ability.text.is_empty() => format!("{}: {}", ability.name, ability.text),
+                    _ => name.clone(),
+                })
+                .collect(),
+            AbilityDisplay::Footnotes => {
+                let mut footnotes = Vec::<String>::new();
+                let numbered_names = names.iter()
+                    .map(|name| match self.find(name) {
+                        Some(ability) if !ability.text.is_empty() => {
+                            footnotes.push(format!("[{}] {}", footnotes.len() + 1, ability.text));
+                            format!("{} [{}]", name, footnotes.len())
+                        },
+                        _ => name.clone(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                std::iter::once(numbered_names).chain(footnotes).collect()
+            },
+        }
+    }
+}
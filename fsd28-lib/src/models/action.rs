@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
 use colored::Colorize;
 
 use super::class::Tier;
@@ -10,14 +11,14 @@ pub struct ActionsConfig {
 
 // TODO make Vec<(u32, u32)> into a type
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ActionCost {
     pub goon: Vec<(u32, u32)>,
     pub char: Vec<(u32, u32)>,
     pub hero: Vec<(u32, u32)>,
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Action {
     pub name: String,
     pub cost: ActionCost, // TODO this is misleading - cost should be Points, not Activation Dice!
@@ -55,7 +56,7 @@ impl Action {
     // ASCII ART
 
     // Static methods to draw the ascii content of ONE box (either with text or not)
-    fn add_ascii_box(lines : &mut Vec<String>, content : Option<(u32, u32)>) {
+    fn add_ascii_box(lines : &mut [String], content : Option<(u32, u32)>) {
 
         // Sanity Check:
         if lines.len() != 3 {
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use super::damage_chart::Color;
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemesConfig {
+    pub themes: Vec<CardTheme>,
+}
+
+// The look of a deck of cards. Every field has a default, so a theme file only needs
+// the values that differ from the classic look. Colours are CSS hex strings ("#rrggbb").
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardTheme {
+    pub name: String,
+    pub font_family: String, // Used by the browser canvas. The PNG renderer always uses the bundled font
+    pub background: String,
+    pub text_color: String,
+    pub accent: Option<String>, // Faction colour for the title, the points and the card frame
+    pub damage_palette: DamagePalette,
+    pub damage_text_color: String,
+    pub sizes: CardSizes,
+    pub border: BorderStyle,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DamagePalette {
+    pub red: String,
+    pub yellow: String,
+    pub green: String,
+}
+
+// Font sizes in pixels at 300 DPI.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardSizes {
+    pub title: f32,
+    pub subtitle: f32,
+    pub text: f32,
+    pub action_title: f32,
+    pub action_description: f32,
+    pub action_cost: f32,
+    pub damage_effect: f32,
+}
+
+// Lines in pixels at 300 DPI. A frame width of 0 means no frame around the card.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BorderStyle {
+    pub dice_box_width: f32,
+    pub dice_box_radius: f32,
+    pub frame_width: f32,
+    pub frame_radius: f32,
+}
+
+impl Default for CardTheme {
+    fn default() -> Self {
+        CardTheme {
+            name: "Classic".to_string(),
+            font_family: "'Trebuchet MS', sans-serif".to_string(),
+            background: "#ffffff".to_string(),
+            text_color: "#000000".to_string(),
+            accent: None,
+            damage_palette: DamagePalette::default(),
+            damage_text_color: "#ffffff".to_string(),
+            sizes: CardSizes::default(),
+            border: BorderStyle::default(),
+        }
+    }
+}
+
+impl Default for DamagePalette {
+    fn default() -> Self {
+        DamagePalette {
+            red: "#951c07".to_string(),
+            yellow: "#ab7a1e".to_string(),
+            green: "#5a7e26".to_string(),
+        }
+    }
+}
+
+impl Default for CardSizes {
+    fn default() -> Self {
+        CardSizes {
+            title: 60.0,
+            subtitle: 28.0,
+            text: 24.0,
+            action_title: 36.0,
+            action_description: 30.0,
+            action_cost: 50.0,
+            damage_effect: 42.0,
+        }
+    }
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        BorderStyle {
+            dice_box_width: 8.0,
+            dice_box_radius: 10.0,
+            frame_width: 0.0,
+            frame_radius: 30.0,
+        }
+    }
+}
+
+impl DamagePalette {
+    pub fn color(&self, color: &Color) -> &str {
+        match color {
+            Color::Red => &self.red,
+            Color::Yellow => &self.yellow,
+            Color::Green => &self.green,
+        }
+    }
+}
+
+impl ThemesConfig {
+    pub fn find(&self, name: &str) -> Option<&CardTheme> {
+        self.themes.iter().find(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl CardTheme {
+
+    pub fn from_json(json: &str) -> Result<CardTheme, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    // The colour of the highlighted elements: the accent if set, the text colour otherwise.
+    pub fn accent_color(&self) -> &str {
+        self.accent.as_deref().unwrap_or(&self.text_color)
+    }
+}
+
+// Parses "#rrggbb" (or "#rgb") into its RGB components.
+pub fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().strip_prefix('#')?;
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|digit| [digit, digit]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let component = |index: usize| u8::from_str_radix(&expanded[index..index + 2], 16).ok();
+    Some([component(0)?, component(2)?, component(4)?])
+}
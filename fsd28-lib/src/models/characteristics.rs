@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use colored::Colorize;
 
 use crate::models::dice_value::DiceValue;
 
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Characteristics {
     pub stat_cmd: u32,
     pub stat_def: u32,
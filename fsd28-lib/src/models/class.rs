@@ -1,21 +1,48 @@
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::fmt;
+use std::str::FromStr;
 use super::damage_chart::DamageChart;
 use super::characteristics::Characteristics;
 use crate::models::modifier::Modifier;
+use crate::models::dice_value::DIE_SHAPES;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ClassesConfig {
     pub classes: Vec<Class>,
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum Tier {
     Goon, 
     Char,
     Hero
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+impl FromStr for Tier {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "goon" => Ok(Tier::Goon),
+            "char" => Ok(Tier::Char),
+            "hero" => Ok(Tier::Hero),
+            _ => Err(format!("Unknown tier: {} (expected Goon, Char or Hero)", value)),
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tier::Goon => write!(f, "Goon"),
+            Tier::Char => write!(f, "Char"),
+            Tier::Hero => write!(f, "Hero"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Class {
     pub name: String,
     pub tier: Tier,
@@ -28,4 +55,43 @@ pub struct Class {
 
 impl Class {
 
+    // The problems that would make the class unusable, for hand-made rulesets.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push("the class has no name".to_string());
+        }
+
+        let stats = &self.characteristics;
+        for (stat, value) in [("save", &stats.stat_save), ("shoot", &stats.stat_shoot), ("melee", &stats.stat_melee)] {
+            if !DIE_SHAPES.contains(&value.shape()) {
+                errors.push(format!("{}: the {} die must be a d6, d8, d10 or d12, not a d{}", self.name, stat, value.shape()));
+            }
+        }
+
+        // The damage chart covers the six faces of the damage die
+        let faces: u32 = self.damage_profile.intervals.iter().map(|interval| interval.0).sum();
+        if faces != 6 {
+            errors.push(format!("{}: the damage intervals cover {} faces instead of 6", self.name, faces));
+        }
+        if self.damage_profile.intervals.iter().any(|interval| interval.0 == 0) {
+            errors.push(format!("{}: every damage interval needs at least one face", self.name));
+        }
+        if self.damage_profile.intervals.iter().any(|interval| interval.2.trim().is_empty()) {
+            errors.push(format!("{}: every damage interval needs a text", self.name));
+        }
+
+        for (index, modifier) in self.modifiers.iter().enumerate() {
+            if modifier.id.trim().is_empty() {
+                errors.push(format!("{}: modifier {} has no name", self.name, index + 1));
+            }
+            else if self.modifiers[..index].iter().any(|other| other.id == modifier.id) {
+                errors.push(format!("{}: modifier {} is there twice", self.name, modifier.id));
+            }
+            if !modifier.effects.is_object() {
+                errors.push(format!("{}: the effects of modifier {} must be a JSON object", self.name, modifier.id));
+            }
+        }
+        errors
+    }
 }
\ No newline at end of file
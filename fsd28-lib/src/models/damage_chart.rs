@@ -1,16 +1,17 @@
 
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum Color {
     Red,
     Yellow,
     Green
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct DamageChart {
     pub intervals: Vec<(u32, Color, String)>
 }
@@ -1,16 +1,19 @@
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 
-#[derive(Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub struct DiceValue {
     shape : u32,
     number : u32,
     armor : u32
 }
 
+pub const DIE_SHAPES: [u32; 4] = [6, 8, 10, 12];
+
 impl DiceValue {
     pub fn new(i_shape:u32, i_number:u32) -> Result<DiceValue, String>{
-        if ![6,8,10,12].contains(&i_shape) {
+        if !DIE_SHAPES.contains(&i_shape) {
             return Err("shape provided does not exist".to_string());
         }
         Ok(DiceValue{shape: i_shape, number: i_number, armor: 0})
@@ -30,7 +33,7 @@ impl DiceValue {
                 let armor_str = &i_string[start_parenthesis + 1..end_parenthesis];
                 shape = shape_str.parse::<u32>().map_err(|_| "Invalid shape".to_string())?;
                 armor = armor_str.parse::<u32>().map_err(|_| "Invalid armor".to_string())?;
-                if ![6,8,10,12].contains(&shape) {
+                if !DIE_SHAPES.contains(&shape) {
                     return Err(format!("Shape provided does not exist: {}", i_string).to_string());
                 }
                 number = 1;
@@ -51,7 +54,7 @@ impl DiceValue {
             };
             armor = 0;
 
-            if ![6,8,10,12].contains(&shape) {
+            if !DIE_SHAPES.contains(&shape) {
                 return Err(format!("Shape provided does not exist: {}", i_string).to_string());
             }
             Ok(DiceValue { shape, number, armor })
@@ -60,12 +63,20 @@ impl DiceValue {
     }
 
     pub fn new_armor(i_shape:u32, i_armor:u32) -> Result<DiceValue, String>{
-        if ![6,8,10,12].contains(&i_shape) {
+        if !DIE_SHAPES.contains(&i_shape) {
             return Err("shape provided does not exist".to_string());
         }
         Ok(DiceValue{shape: i_shape, number: 0, armor: i_armor})
     }
 
+    // All three values at once, as written by the spreadsheet export.
+    pub fn from_parts(i_shape: u32, i_number: u32, i_armor: u32) -> Result<DiceValue, String> {
+        if !DIE_SHAPES.contains(&i_shape) {
+            return Err(format!("d{} is not a die shape", i_shape));
+        }
+        Ok(DiceValue{shape: i_shape, number: i_number, armor: i_armor})
+    }
+
     pub fn change_number(self, delta:i32) -> Result<DiceValue, String>{
         let temp = self.number as i32 + delta;
         if temp < 1 {
@@ -89,6 +100,18 @@ impl DiceValue {
         Ok(DiceValue{shape: self.shape, number: self.number, armor: temp as u32})
     }
 
+    pub fn shape(&self) -> u32 {
+        self.shape
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn armor(&self) -> u32 {
+        self.armor
+    }
+
     pub fn display(&self) -> String {
         match self.armor {
             0 => {
@@ -5,4 +5,14 @@ pub mod action;
 pub mod weapon;
 pub mod modifier;
 pub mod class;
+pub mod ability;
+pub mod card_theme;
+pub mod roster;
+pub mod roster_rules;
+pub mod save_data;
+pub mod profile_diff;
+pub mod profile_query;
+pub mod ruleset;
+pub mod ruleset_pack;
+pub mod ruleset_context;
 pub mod characteristics;
\ No newline at end of file
@@ -4,8 +4,11 @@ use crate::models::characteristics::Characteristics;
 use crate::models::class::Class;
 use crate::models::class::Tier;
 use crate::models::modifier::Modifier;
+use crate::models::ability::{AbilitiesConfig, AbilityDisplay};
+use crate::models::profile_diff::ProfileDiff;
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 // For ascii display
 use colored::Colorize;
@@ -13,12 +16,21 @@ use colored::Colorize;
 use super::dice_value::DiceValue;
 
 
-#[derive(Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub struct Profile {
+    #[serde(default = "new_profile_id")]
+    #[schemars(default = "String::new", skip_serializing_if = "String::is_empty")] // Keeps the random ID out of the schema
+    pub id: String, // Stable reference used by the rosters. Older saves get a new one when loaded
     pub name: String,
     pub class_name: String,
     pub selected_modifiers: Vec<Modifier>,
     pub description: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub folder: String, // The faction or any other group, empty for none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
     pub tier: Tier,
     pub characteristics: Characteristics,
     pub special_abilities: Vec<String>,
@@ -28,6 +40,33 @@ pub struct Profile {
 }
 
 
+pub fn new_profile_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+// Construction rule: actions a profile can take.
+pub const MAX_ACTIONS: usize = 3;
+
+// Longer lines would overlap with the next column of the printable ASCII sheet.
+const ASCII_LINE_WIDTH: usize = 58;
+
+fn wrap_ascii_line(line: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+    let mut current_line = String::new();
+    for word in line.split_whitespace() {
+        if !current_line.is_empty() && current_line.len() + 1 + word.len() > width {
+            lines.push(current_line);
+            current_line = String::new();
+        }
+        if !current_line.is_empty() {
+            current_line += " ";
+        }
+        current_line += word;
+    }
+    lines.push(current_line);
+    lines
+}
+
 fn apply_modifier_effects(profile: &mut Profile, modifier: &Modifier) {
     if let Some(effects_map) = modifier.effects.as_object() {
 
@@ -88,10 +127,14 @@ impl Profile {
 
     pub fn new(i_name : String, i_class : Class) -> Profile {
         Profile {
+            id: new_profile_id(),
             name : i_name,
             description : i_class.name.clone(),
             class_name : i_class.name,
             selected_modifiers : Vec::<Modifier>::new(),
+            folder: String::new(),
+            tags: Vec::new(),
+            notes: String::new(),
             tier: i_class.tier,
             characteristics : i_class.characteristics,
             special_abilities: Vec::<String>::new(),
@@ -101,6 +144,24 @@ impl Profile {
         }
     } 
 
+    // A copy with its own ID, so that both can be used in the rosters. The callers give it a free name.
+    pub fn duplicate(&self) -> Profile {
+        Profile {
+            id: new_profile_id(),
+            ..self.clone()
+        }
+    }
+
+    // Tags are not case sensitive.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|other| other.to_lowercase() == tag.trim().to_lowercase())
+    }
+
+    // What changed from this profile to the other one.
+    pub fn diff(&self, other: &Profile) -> ProfileDiff {
+        ProfileDiff::between(self, other)
+    }
+
     pub fn get_final_profile(&self) -> Profile {
         let mut modified_profile = self.clone();
 
@@ -130,6 +191,11 @@ impl Profile {
     }
 
     pub fn display_ascii (&self) -> String {
+        self.display_ascii_with_abilities(&AbilitiesConfig::default(), AbilityDisplay::Names)
+    }
+
+    // Same as display_ascii, but expanding the special abilities with their rules text.
+    pub fn display_ascii_with_abilities (&self, abilities: &AbilitiesConfig, display: AbilityDisplay) -> String {
         let mut out_string = String::new();
         // Infos
         out_string += "Character Name:     ";
@@ -150,7 +216,16 @@ impl Profile {
 
         // Abilities and damage chart
         out_string += "\n\nSpecial Abilities:  ";
-        out_string += &*self.special_abilities.join(", ").bold().blue().to_string();
+        let ability_lines = abilities.format_abilities(&self.special_abilities, display);
+        if display == AbilityDisplay::Names {
+            out_string += &*ability_lines.join("").bold().blue().to_string();
+        }
+        else {
+            for line in ability_lines.iter().flat_map(|line| wrap_ascii_line(line, ASCII_LINE_WIDTH)) {
+                out_string += "\n";
+                out_string += &*line.bold().blue().to_string();
+            }
+        }
         out_string += "\n\n";
         out_string += &*self.damage_chart.display_ascii();
 
@@ -0,0 +1,265 @@
+use std::fmt;
+
+use super::action::Action;
+use super::characteristics::Characteristics;
+use super::class::Tier;
+use super::profile::Profile;
+
+// What changed between two versions of a profile. Characteristics, abilities and points
+// are compared with the modifiers applied, as printed on the cards.
+#[derive(Clone, PartialEq, Default)]
+pub struct ProfileDiff {
+    pub name: Option<(String, String)>,
+    pub class_name: Option<(String, String)>,
+    pub characteristics: Vec<StatChange>,
+    pub modifiers_added: Vec<String>,
+    pub modifiers_removed: Vec<String>,
+    pub actions_added: Vec<String>,
+    pub actions_removed: Vec<String>,
+    pub actions_changed: Vec<ActionChange>,
+    pub abilities_added: Vec<String>,
+    pub abilities_removed: Vec<String>,
+    pub folder: Option<(String, String)>,
+    pub tags_added: Vec<String>,
+    pub tags_removed: Vec<String>,
+    pub notes_changed: bool,
+    pub points: (u32, u32),
+}
+
+// An action replaced by another one with the same name and other stats: its name and what changed.
+pub type ActionChange = (String, Vec<String>);
+
+#[derive(Clone, PartialEq)]
+pub struct StatChange {
+    pub stat: String,
+    pub old: String,
+    pub new: String,
+}
+
+// The differences between two lists of profiles, matched by ID (or by name, for the
+// older saves whose profiles get a new ID every time they are loaded).
+#[derive(Clone, PartialEq, Default)]
+pub struct CollectionDiff {
+    pub added: Vec<(String, u32)>,   // Name and points
+    pub removed: Vec<(String, u32)>, // Name and points
+    pub changed: Vec<(String, ProfileDiff)>,
+    pub points: (u32, u32),
+}
+
+impl ProfileDiff {
+
+    pub fn between(old: &Profile, new: &Profile) -> ProfileDiff {
+        let old_final = old.get_final_profile();
+        let new_final = new.get_final_profile();
+        let (actions_added, actions_removed, actions_changed) = compare_actions(&old.actions, &new.actions, &new.tier);
+        ProfileDiff {
+            name: (old.name != new.name).then(|| (old.name.clone(), new.name.clone())),
+            class_name: (old.class_name != new.class_name).then(|| (old.class_name.clone(), new.class_name.clone())),
+            characteristics: stat_changes(&old_final.characteristics, &new_final.characteristics),
+            modifiers_added: missing_from(&modifier_ids(new), &modifier_ids(old)),
+            modifiers_removed: missing_from(&modifier_ids(old), &modifier_ids(new)),
+            actions_added,
+            actions_removed,
+            actions_changed,
+            abilities_added: missing_from(&new_final.special_abilities, &old_final.special_abilities),
+            abilities_removed: missing_from(&old_final.special_abilities, &new_final.special_abilities),
+            folder: (old.folder != new.folder).then(|| (old.folder.clone(), new.folder.clone())),
+            tags_added: missing_from(&new.tags, &old.tags),
+            tags_removed: missing_from(&old.tags, &new.tags),
+            notes_changed: old.notes != new.notes,
+            points: (old_final.cost, new_final.cost),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.class_name.is_none() && self.characteristics.is_empty()
+            && self.modifiers_added.is_empty() && self.modifiers_removed.is_empty()
+            && self.actions_added.is_empty() && self.actions_removed.is_empty() && self.actions_changed.is_empty()
+            && self.abilities_added.is_empty() && self.abilities_removed.is_empty()
+            && self.folder.is_none() && self.tags_added.is_empty() && self.tags_removed.is_empty() && !self.notes_changed
+            && self.points.0 == self.points.1
+    }
+
+    pub fn points_delta(&self) -> i64 {
+        self.points.1 as i64 - self.points.0 as i64
+    }
+
+    // One line per change, for reports.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::<String>::new();
+        if let Some((old, new)) = &self.name {
+            lines.push(format!("name: {} -> {}", old, new));
+        }
+        if let Some((old, new)) = &self.class_name {
+            lines.push(format!("class: {} -> {}", old, new));
+        }
+        for change in &self.characteristics {
+            lines.push(format!("{}: {} -> {}", change.stat, change.old, change.new));
+        }
+        lines.extend(self.modifiers_added.iter().map(|name| format!("+ modifier {}", name)));
+        lines.extend(self.modifiers_removed.iter().map(|name| format!("- modifier {}", name)));
+        lines.extend(self.actions_added.iter().map(|name| format!("+ action {}", name)));
+        lines.extend(self.actions_removed.iter().map(|name| format!("- action {}", name)));
+        lines.extend(self.actions_changed.iter().map(|(name, changes)| format!("~ action {}: {}", name, changes.join(", "))));
+        lines.extend(self.abilities_added.iter().map(|name| format!("+ ability {}", name)));
+        lines.extend(self.abilities_removed.iter().map(|name| format!("- ability {}", name)));
+        if let Some((old, new)) = &self.folder {
+            lines.push(format!("folder: {} -> {}", folder_name(old), folder_name(new)));
+        }
+        lines.extend(self.tags_added.iter().map(|tag| format!("+ tag {}", tag)));
+        lines.extend(self.tags_removed.iter().map(|tag| format!("- tag {}", tag)));
+        if self.notes_changed {
+            lines.push("notes changed".to_string());
+        }
+        if self.points.0 != self.points.1 {
+            lines.push(format!("points: {} -> {} ({:+})", self.points.0, self.points.1, self.points_delta()));
+        }
+        lines
+    }
+}
+
+impl CollectionDiff {
+
+    pub fn between(old: &[Profile], new: &[Profile]) -> CollectionDiff {
+        let mut diff = CollectionDiff {
+            points: (total_points(old), total_points(new)),
+            ..CollectionDiff::default()
+        };
+
+        let mut matched_old = Vec::<usize>::new();
+        for new_profile in new {
+            let old_index = old.iter().position(|profile| profile.id == new_profile.id)
+                .or_else(|| (0..old.len()).find(|index| old[*index].name == new_profile.name && !matched_old.contains(index)));
+            match old_index {
+                Some(index) => {
+                    matched_old.push(index);
+                    let profile_diff = ProfileDiff::between(&old[index], new_profile);
+                    if !profile_diff.is_empty() {
+                        diff.changed.push((new_profile.name.clone(), profile_diff));
+                    }
+                },
+                None => diff.added.push((new_profile.name.clone(), new_profile.get_final_profile().cost)),
+            }
+        }
+        for (index, old_profile) in old.iter().enumerate() {
+            if !matched_old.contains(&index) {
+                diff.removed.push((old_profile.name.clone(), old_profile.get_final_profile().cost));
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    pub fn points_delta(&self) -> i64 {
+        self.points.1 as i64 - self.points.0 as i64
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::<String>::new();
+        lines.extend(self.added.iter().map(|(name, points)| format!("+ {} ({} pts)", name, points)));
+        lines.extend(self.removed.iter().map(|(name, points)| format!("- {} ({} pts)", name, points)));
+        for (name, profile_diff) in &self.changed {
+            lines.push(format!("~ {}", name));
+            lines.extend(profile_diff.lines().into_iter().map(|line| format!("    {}", line)));
+        }
+        lines.push(format!("Total points: {} -> {} ({:+})", self.points.0, self.points.1, self.points_delta()));
+        lines
+    }
+}
+
+impl fmt::Display for ProfileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+impl fmt::Display for CollectionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+// PRIVATE METHODS
+
+fn total_points(profiles: &[Profile]) -> u32 {
+    profiles.iter().map(|profile| profile.get_final_profile().cost).sum()
+}
+
+fn modifier_ids(profile: &Profile) -> Vec<String> {
+    profile.selected_modifiers.iter().map(|modifier| modifier.id.clone()).collect()
+}
+
+// Actions are compared whole, since several weapons offer actions with the same name and different dice.
+// An action replaced by another one with the same name is reported with what changed.
+fn compare_actions(old: &[Action], new: &[Action], tier: &Tier) -> (Vec<String>, Vec<String>, Vec<ActionChange>) {
+    let mut removed: Vec<&Action> = old.iter().filter(|action| !new.contains(action)).collect();
+    let mut added = Vec::<String>::new();
+    let mut changed = Vec::<ActionChange>::new();
+    for action in new.iter().filter(|action| !old.contains(action)) {
+        match removed.iter().position(|other| other.name == action.name) {
+            Some(index) => {
+                let other = removed.remove(index);
+                // The other tiers are only shown when the profile's own one did not change
+                let mut changes = action_changes(other, action, Some(tier));
+                if changes.is_empty() {
+                    changes = action_changes(other, action, None);
+                }
+                changed.push((action.name.clone(), changes));
+            },
+            None => added.push(action.name.clone()),
+        }
+    }
+    (added, removed.into_iter().map(|action| action.name.clone()).collect(), changed)
+}
+
+// The items of `items` that are not in `others`.
+fn missing_from(items: &[String], others: &[String]) -> Vec<String> {
+    items.iter().filter(|item| !others.contains(item)).cloned().collect()
+}
+
+pub fn stat_changes(old: &Characteristics, new: &Characteristics) -> Vec<StatChange> {
+    let stats = [
+        ("Cmd", old.stat_cmd.to_string(), new.stat_cmd.to_string()),
+        ("Def", format!("{}+", old.stat_def), format!("{}+", new.stat_def)),
+        ("Save", old.stat_save.display(), new.stat_save.display()),
+        ("Move", format!("{} DU", old.stat_move), format!("{} DU", new.stat_move)),
+        ("Shoot", old.stat_shoot.display(), new.stat_shoot.display()),
+        ("Melee", old.stat_melee.display(), new.stat_melee.display()),
+    ];
+    stats.into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(stat, old, new)| StatChange { stat: stat.to_string(), old, new })
+        .collect()
+}
+
+// Only the activation ranges of the given tier are compared, if there is one.
+pub fn action_changes(old: &Action, new: &Action, tier: Option<&Tier>) -> Vec<String> {
+    let mut changes = Vec::<String>::new();
+    if old.points != new.points {
+        changes.push(format!("points {} -> {}", old.points, new.points));
+    }
+    let tiers = match tier {
+        Some(tier) => vec![tier.clone()],
+        None => vec![Tier::Goon, Tier::Char, Tier::Hero],
+    };
+    for tier in tiers {
+        let (old_ranges, new_ranges) = (old.get_action_cost_str(&tier), new.get_action_cost_str(&tier));
+        if old_ranges != new_ranges {
+            changes.push(format!("{} activation {} -> {}", tier, old_ranges.join(", "), new_ranges.join(", ")));
+        }
+    }
+    if old.text != new.text {
+        changes.push(format!("text \"{}\" -> \"{}\"", old.text, new.text));
+    }
+    if old.slot != new.slot {
+        changes.push(if new.slot { "now has a slot".to_string() } else { "no longer has a slot".to_string() });
+    }
+    changes
+}
+
+fn folder_name(folder: &str) -> &str {
+    if folder.is_empty() { "none" } else { folder }
+}
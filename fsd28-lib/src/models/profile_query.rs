@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use super::class::Tier;
+use super::profile::Profile;
+use super::ruleset_context::RulesetContext;
+
+// The order of the profiles in a search.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum ProfileSort {
+    #[default]
+    Saved, // As they are in the save file
+    Name,
+    Cost,
+    Class,
+}
+
+impl FromStr for ProfileSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "saved" | "none" => Ok(ProfileSort::Saved),
+            "name" => Ok(ProfileSort::Name),
+            "cost" | "points" => Ok(ProfileSort::Cost),
+            "class" => Ok(ProfileSort::Class),
+            _ => Err(format!("Unknown sort: {} (expected saved, name, cost or class)", value)),
+        }
+    }
+}
+
+// A search through the saved profiles. Every filter that is set must match; the names are not
+// case sensitive, and the cost and abilities are the ones with the modifiers and actions applied.
+#[derive(Clone, Default, PartialEq)]
+pub struct ProfileQuery {
+    pub name: String, // Part of the name, empty for any
+    pub tier: Option<Tier>,
+    pub class_name: Option<String>,
+    pub ability: Option<String>,
+    pub weapon: Option<String>, // Profiles with at least one action of the weapon
+    pub folder: Option<String>, // An empty folder is the profiles without one
+    pub tag: Option<String>,
+    pub min_cost: Option<u32>,
+    pub max_cost: Option<u32>,
+    pub sort: ProfileSort,
+    pub descending: bool,
+}
+
+impl ProfileQuery {
+    pub fn is_empty(&self) -> bool {
+        ProfileQuery { sort: self.sort, descending: self.descending, ..Default::default() } == *self
+    }
+
+    pub fn matches(&self, profile: &Profile, rules: &RulesetContext) -> bool {
+        let final_profile = profile.get_final_profile();
+        let same = |a: &str, b: &str| a.to_lowercase() == b.to_lowercase();
+
+        profile.name.to_lowercase().contains(&self.name.trim().to_lowercase())
+            && self.tier.as_ref().is_none_or(|tier| profile.tier == *tier)
+            && self.class_name.as_ref().is_none_or(|class_name| same(&profile.class_name, class_name))
+            && self.ability.as_ref().is_none_or(|ability| final_profile.special_abilities.iter().any(|other| same(other, ability)))
+            && self.weapon.as_ref().is_none_or(|weapon| profile.actions.iter().any(|action| {
+                rules.weapon_of(action).is_some_and(|other| same(&other.name, weapon))
+            }))
+            && self.folder.as_ref().is_none_or(|folder| same(&profile.folder, folder))
+            && self.tag.as_ref().is_none_or(|tag| profile.has_tag(tag))
+            && self.min_cost.is_none_or(|min_cost| final_profile.cost >= min_cost)
+            && self.max_cost.is_none_or(|max_cost| final_profile.cost <= max_cost)
+    }
+
+    // The positions of the matching profiles, in the order asked. Ties keep the saved order.
+    pub fn apply(&self, profiles: &[Profile], rules: &RulesetContext) -> Vec<usize> {
+        let mut found: Vec<(usize, u32)> = profiles.iter().enumerate()
+            .filter(|(_, profile)| self.matches(profile, rules))
+            .map(|(index, profile)| (index, profile.get_final_profile().cost))
+            .collect();
+
+        let compare = |a: &(usize, u32), b: &(usize, u32)| -> Ordering {
+            let (first, second) = (&profiles[a.0], &profiles[b.0]);
+            match self.sort {
+                ProfileSort::Saved => Ordering::Equal,
+                ProfileSort::Name => first.name.to_lowercase().cmp(&second.name.to_lowercase()),
+                ProfileSort::Cost => a.1.cmp(&b.1),
+                ProfileSort::Class => first.class_name.cmp(&second.class_name),
+            }
+        };
+        found.sort_by(|a, b| if self.descending { compare(b, a) } else { compare(a, b) });
+        found.into_iter().map(|(index, _)| index).collect()
+    }
+}
+
+// The positions split by folder, keeping their order within each folder.
+// The folders are sorted by name, with the profiles without one at the end.
+pub fn group_by_folder(profiles: &[Profile], positions: &[usize]) -> Vec<(String, Vec<usize>)> {
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for position in positions {
+        let folder = &profiles[*position].folder;
+        match groups.iter_mut().find(|(name, _)| name == folder) {
+            Some((_, group)) => group.push(*position),
+            None => groups.push((folder.clone(), vec![*position])),
+        }
+    }
+    groups.sort_by_key(|(folder, _)| (folder.is_empty(), folder.to_lowercase()));
+    groups
+}
+
+// The folders in use, sorted by name, for the pickers.
+pub fn folder_names(profiles: &[Profile]) -> Vec<String> {
+    let mut folders: Vec<String> = profiles.iter().map(|profile| profile.folder.clone()).filter(|folder| !folder.is_empty()).collect();
+    folders.sort();
+    folders.dedup();
+    folders.sort_by_key(|folder| folder.to_lowercase());
+    folders
+}
+
+// The tags in use, ignoring case, sorted by name for the pickers.
+pub fn tag_names(profiles: &[Profile]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in profiles.iter().flat_map(|profile| profile.tags.iter()) {
+        if !tags.iter().any(|other| other.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags
+}
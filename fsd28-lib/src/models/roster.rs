@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use super::class::Tier;
+use super::profile::Profile;
+use super::roster_rules::RosterRules;
+
+// A list of units for a game: profiles are referenced by ID, each with a number of models.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Roster {
+    #[serde(default = "new_roster_id")]
+    #[schemars(default = "String::new", skip_serializing_if = "String::is_empty")] // Keeps the random ID out of the schema
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub faction: String,
+    #[serde(default)]
+    pub points_limit: Option<u32>,
+    #[serde(default)]
+    pub entries: Vec<RosterEntry>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RosterEntry {
+    pub profile_id: String,
+    pub count: u32,
+    #[serde(default)]
+    pub notes: String,
+}
+
+pub fn new_roster_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl Roster {
+
+    pub fn new(name: String) -> Roster {
+        Roster {
+            id: new_roster_id(),
+            name,
+            faction: String::new(),
+            points_limit: None,
+            entries: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    // Adds one model of the profile, in its existing entry if there is one.
+    pub fn add_profile(&mut self, profile_id: &str) {
+        match self.entries.iter_mut().find(|entry| entry.profile_id == profile_id) {
+            Some(entry) => entry.count += 1,
+            None => self.entries.push(RosterEntry {
+                profile_id: profile_id.to_string(),
+                count: 1,
+                notes: String::new(),
+            }),
+        }
+    }
+
+    pub fn remove_profile(&mut self, profile_id: &str) {
+        self.entries.retain(|entry| entry.profile_id != profile_id);
+    }
+
+    // Setting the count to 0 removes the entry.
+    pub fn set_count(&mut self, profile_id: &str, count: u32) {
+        if count == 0 {
+            self.remove_profile(profile_id);
+        }
+        else if let Some(entry) = self.entries.iter_mut().find(|entry| entry.profile_id == profile_id) {
+            entry.count = count;
+        }
+    }
+
+    pub fn count_of(&self, profile_id: &str) -> u32 {
+        self.entries.iter()
+            .find(|entry| entry.profile_id == profile_id)
+            .map_or(0, |entry| entry.count)
+    }
+
+    // The entries paired with their profile. Entries whose profile has been deleted get None.
+    pub fn resolve<'a>(&'a self, profiles: &'a [Profile]) -> Vec<(&'a RosterEntry, Option<&'a Profile>)> {
+        self.entries.iter()
+            .map(|entry| (entry, profiles.iter().find(|profile| profile.id == entry.profile_id)))
+            .collect()
+    }
+
+    // Cost of an entry: the cost of the profile with its modifiers, times the models.
+    pub fn entry_cost(entry: &RosterEntry, profile: &Profile) -> u32 {
+        profile.get_final_profile().cost * entry.count
+    }
+
+    // Entries referencing missing profiles do not count.
+    pub fn total_cost(&self, profiles: &[Profile]) -> u32 {
+        self.resolve(profiles).iter()
+            .filter_map(|(entry, profile)| profile.map(|profile| Roster::entry_cost(entry, profile)))
+            .sum()
+    }
+
+    pub fn models_count(&self) -> u32 {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    pub fn missing_profiles(&self, profiles: &[Profile]) -> Vec<String> {
+        self.resolve(profiles).iter()
+            .filter(|(_, profile)| profile.is_none())
+            .map(|(entry, _)| entry.profile_id.clone())
+            .collect()
+    }
+
+    pub fn effective_points_limit(&self, rules: &RosterRules) -> Option<u32> {
+        self.points_limit.or(rules.points_limit)
+    }
+
+    // Checks the roster against the list-building rules, returning a message for each violation.
+    pub fn validate(&self, profiles: &[Profile], rules: &RosterRules) -> Vec<String> {
+        let mut violations = Vec::<String>::new();
+
+        for profile_id in self.missing_profiles(profiles) {
+            violations.push(format!("The roster references a deleted profile ({})", profile_id));
+        }
+
+        let total_cost = self.total_cost(profiles);
+        if let Some(limit) = self.effective_points_limit(rules) {
+            if total_cost > limit {
+                violations.push(format!("{} points over the limit of {} ({} points)", total_cost - limit, limit, total_cost));
+            }
+        }
+
+        for limit in &rules.tier_limits {
+            let count = self.tier_count(profiles, &limit.tier);
+            if let Some(min) = limit.min {
+                if count < min {
+                    violations.push(format!("At least {} {} models are required, the roster has {}", min, limit.tier, count));
+                }
+            }
+            if let Some(max) = limit.max {
+                if count > max {
+                    violations.push(format!("At most {} {} models are allowed, the roster has {}", max, limit.tier, count));
+                }
+            }
+        }
+
+        if let Some(max_leaders) = rules.max_leaders {
+            let leaders: u32 = self.resolve(profiles).iter()
+                .filter(|(_, profile)| profile.is_some_and(|profile| rules.leader_classes.contains(&profile.class_name)))
+                .map(|(entry, _)| entry.count)
+                .sum();
+            if leaders > max_leaders {
+                violations.push(format!("At most {} leaders ({}) are allowed, the roster has {}", max_leaders, rules.leader_classes.join(", "), leaders));
+            }
+        }
+
+        if let Some(max_duplicates) = rules.max_duplicates {
+            for (entry, profile) in self.resolve(profiles) {
+                if let Some(profile) = profile {
+                    if entry.count > max_duplicates {
+                        violations.push(format!("At most {} models of {} are allowed, the roster has {}", max_duplicates, profile.name, entry.count));
+                    }
+                }
+            }
+        }
+
+        for ratio in &rules.tier_ratios {
+            let count = self.tier_count(profiles, &ratio.tier);
+            let per_count = self.tier_count(profiles, &ratio.per_tier);
+            if count < ratio.min * per_count {
+                violations.push(format!("{} {} models need at least {} {} models, the roster has {}",
+                    per_count, ratio.per_tier, ratio.min * per_count, ratio.tier, count));
+            }
+        }
+
+        violations
+    }
+
+    // Models of the given tier in the roster.
+    pub fn tier_count(&self, profiles: &[Profile], tier: &Tier) -> u32 {
+        self.resolve(profiles).iter()
+            .filter(|(_, profile)| profile.is_some_and(|profile| &profile.tier == tier))
+            .map(|(entry, _)| entry.count)
+            .sum()
+    }
+}
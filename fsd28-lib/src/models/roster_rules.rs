@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use super::class::Tier;
+
+// List-building rules of the ruleset. Every rule is optional: a missing one is not checked.
+// All the counts are in models, so an entry of 3x Bandit counts as 3 Goons.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RosterRules {
+    pub points_limit: Option<u32>, // Used when the roster does not set its own limit
+    pub tier_limits: Vec<TierLimit>,
+    pub leader_classes: Vec<String>, // Classes that lead the force, like the Captain
+    pub max_leaders: Option<u32>,
+    pub max_duplicates: Option<u32>, // Models of the same profile
+    pub tier_ratios: Vec<TierRatio>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TierLimit {
+    pub tier: Tier,
+    #[serde(default)]
+    pub min: Option<u32>,
+    #[serde(default)]
+    pub max: Option<u32>,
+}
+
+// At least `min` models of `tier` for every model of `per_tier`, e.g. 2 Goons per Char.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TierRatio {
+    pub tier: Tier,
+    pub per_tier: Tier,
+    pub min: u32,
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use super::ability::{AbilitiesConfig, Ability};
+use super::class::{Class, ClassesConfig};
+use super::roster_rules::RosterRules;
+use super::weapon::{Weapon, WeaponsConfig};
+
+// Classes, weapons, list-building rules and glossary entries in a single file. The file can also
+// be read as classes.json or weapons.json, since each of those only looks at its own list.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Ruleset {
+    #[serde(default)]
+    pub classes: Vec<Class>,
+    #[serde(default)]
+    pub weapons: Vec<Weapon>,
+    #[serde(default = "bundled_roster_rules")]
+    pub roster_rules: RosterRules, // The bundled ones for the files without them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub abilities: Vec<Ability>, // Glossary entries added to the bundled ones, or replacing them by name
+}
+
+fn bundled_roster_rules() -> RosterRules {
+    crate::get_roster_rules("")
+}
+
+impl Ruleset {
+    pub fn new(classes: ClassesConfig, weapons: WeaponsConfig) -> Ruleset {
+        Ruleset { classes: classes.classes, weapons: weapons.weapons, roster_rules: bundled_roster_rules(), abilities: Vec::new() }
+    }
+
+    // Keeps the entries of the glossary that are not the bundled ones.
+    pub fn with_glossary(mut self, glossary: &AbilitiesConfig) -> Ruleset {
+        let bundled = crate::get_abilities("");
+        self.abilities = glossary.abilities.iter()
+            .filter(|ability| bundled.find(&ability.name) != Some(*ability))
+            .cloned()
+            .collect();
+        self
+    }
+
+    // The bundled glossary with the entries of the ruleset.
+    pub fn glossary(&self) -> AbilitiesConfig {
+        let mut glossary = crate::get_abilities("");
+        for ability in &self.abilities {
+            match glossary.abilities.iter().position(|other| other.name == ability.name) {
+                Some(index) => glossary.abilities[index] = ability.clone(),
+                None => glossary.abilities.push(ability.clone()),
+            }
+        }
+        glossary
+    }
+
+    pub fn with_roster_rules(mut self, roster_rules: RosterRules) -> Ruleset {
+        self.roster_rules = roster_rules;
+        self
+    }
+
+    pub fn bundled() -> Ruleset {
+        Ruleset::new(crate::get_classes(""), crate::get_weapons(""))
+    }
+
+    pub fn classes_config(&self) -> ClassesConfig {
+        ClassesConfig { classes: self.classes.clone() }
+    }
+
+    pub fn weapons_config(&self) -> WeaponsConfig {
+        WeaponsConfig { weapons: self.weapons.clone() }
+    }
+
+    // Everything wrong in the classes and weapons, plus names used twice.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (index, class) in self.classes.iter().enumerate() {
+            if self.classes[..index].iter().any(|other| other.name == class.name) {
+                errors.push(format!("class {} is there twice", class.name));
+            }
+            errors.extend(class.validate());
+        }
+        for (index, weapon) in self.weapons.iter().enumerate() {
+            if self.weapons[..index].iter().any(|other| other.name == weapon.name) {
+                errors.push(format!("weapon {} is there twice", weapon.name));
+            }
+            errors.extend(weapon.validate());
+        }
+        for leader in &self.roster_rules.leader_classes {
+            if !self.classes.iter().any(|class| class.name == *leader) {
+                errors.push(format!("the leader class {} of the list-building rules is not a class", leader));
+            }
+        }
+        errors
+    }
+
+    pub fn from_json(json_str: &str) -> Result<Ruleset, serde_json::Error> {
+        serde_json::from_str(json_str)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
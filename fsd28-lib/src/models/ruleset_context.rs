@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use super::ability::AbilitiesConfig;
+use super::action::Action;
+use super::class::{Class, ClassesConfig};
+use super::modifier::Modifier;
+use super::roster_rules::RosterRules;
+use super::ruleset::Ruleset;
+use super::ruleset_pack::ResolvedRuleset;
+use super::weapon::{Weapon, WeaponsConfig};
+
+// The rules in use, parsed and validated once, with the lookups by name indexed.
+// Meant to be built at startup (or when the ruleset changes) and shared.
+#[derive(Clone, PartialEq)]
+pub struct RulesetContext {
+    classes: ClassesConfig,
+    weapons: WeaponsConfig,
+    abilities: AbilitiesConfig,
+    roster_rules: RosterRules,
+    problems: Vec<String>,
+    class_index: HashMap<String, usize>,
+    modifier_index: HashMap<(String, String), (usize, usize)>, // (class, modifier ID) -> (class, modifier)
+    weapon_index: HashMap<String, usize>,
+    action_index: HashMap<(String, String), (usize, usize)>, // (weapon, action) -> (weapon, option)
+}
+
+impl RulesetContext {
+    pub fn new(ruleset: Ruleset, abilities: AbilitiesConfig) -> RulesetContext {
+        let mut problems = ruleset.validate();
+        let classes = ruleset.classes_config();
+        let weapons = ruleset.weapons_config();
+        let roster_rules = ruleset.roster_rules;
+        problems.extend(abilities.validate_references(&classes));
+
+        // The first entry wins, as in the lookups by iteration
+        let mut class_index = HashMap::new();
+        let mut modifier_index = HashMap::new();
+        for (class_position, class) in classes.classes.iter().enumerate() {
+            class_index.entry(class.name.clone()).or_insert(class_position);
+            for (modifier_position, modifier) in class.modifiers.iter().enumerate() {
+                modifier_index.entry((class.name.clone(), modifier.id.clone())).or_insert((class_position, modifier_position));
+            }
+        }
+        let mut weapon_index = HashMap::new();
+        let mut action_index = HashMap::new();
+        for (weapon_position, weapon) in weapons.weapons.iter().enumerate() {
+            weapon_index.entry(weapon.name.clone()).or_insert(weapon_position);
+            for (option_position, option) in weapon.options.iter().enumerate() {
+                action_index.entry((weapon.name.clone(), option.action.name.clone())).or_insert((weapon_position, option_position));
+            }
+        }
+
+        RulesetContext { classes, weapons, abilities, roster_rules, problems, class_index, modifier_index, weapon_index, action_index }
+    }
+
+    pub fn bundled() -> RulesetContext {
+        RulesetContext::from_ruleset(Ruleset::bundled())
+    }
+
+    // With the glossary of the ruleset: the bundled one and the ruleset's own entries.
+    pub fn from_ruleset(ruleset: Ruleset) -> RulesetContext {
+        let abilities = ruleset.glossary();
+        RulesetContext::new(ruleset, abilities)
+    }
+
+    pub fn from_resolved(resolved: ResolvedRuleset) -> RulesetContext {
+        RulesetContext::new(resolved.ruleset, resolved.abilities)
+    }
+
+    pub fn classes(&self) -> &[Class] {
+        &self.classes.classes
+    }
+
+    pub fn weapons(&self) -> &[Weapon] {
+        &self.weapons.weapons
+    }
+
+    pub fn classes_config(&self) -> &ClassesConfig {
+        &self.classes
+    }
+
+    pub fn weapons_config(&self) -> &WeaponsConfig {
+        &self.weapons
+    }
+
+    pub fn abilities(&self) -> &AbilitiesConfig {
+        &self.abilities
+    }
+
+    pub fn roster_rules(&self) -> &RosterRules {
+        &self.roster_rules
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        Ruleset::new(self.classes.clone(), self.weapons.clone())
+            .with_roster_rules(self.roster_rules.clone())
+            .with_glossary(&self.abilities)
+    }
+
+    // What validation found when the context was built: bad classes and weapons, undefined abilities.
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    pub fn class_names(&self) -> Vec<String> {
+        self.classes().iter().map(|class| class.name.clone()).collect()
+    }
+
+    pub fn class(&self, name: &str) -> Option<&Class> {
+        self.class_index.get(name).map(|index| &self.classes.classes[*index])
+    }
+
+    pub fn modifier(&self, class_name: &str, id: &str) -> Option<&Modifier> {
+        self.modifier_index.get(&(class_name.to_string(), id.to_string()))
+            .map(|(class, modifier)| &self.classes.classes[*class].modifiers[*modifier])
+    }
+
+    pub fn weapon(&self, name: &str) -> Option<&Weapon> {
+        self.weapon_index.get(name).map(|index| &self.weapons.weapons[*index])
+    }
+
+    // Several weapons can offer an action with the same name and different dice, so the weapon is needed.
+    pub fn weapon_action(&self, weapon_name: &str, action_name: &str) -> Option<&Action> {
+        self.action_index.get(&(weapon_name.to_string(), action_name.to_string()))
+            .map(|(weapon, option)| &self.weapons.weapons[*weapon].options[*option].action)
+    }
+
+    // The weapon a profile action was taken from: the one offering exactly that action.
+    pub fn weapon_of(&self, action: &Action) -> Option<&Weapon> {
+        self.weapons.weapon_of(action)
+    }
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use super::ability::{AbilitiesConfig, Ability};
+use super::class::Class;
+use super::modifier::Modifier;
+use super::roster_rules::RosterRules;
+use super::ruleset::Ruleset;
+use super::weapon::Weapon;
+
+// The name of the bundled rules, which every pack is layered on.
+pub const CORE_PACK: &str = "core";
+
+// Additions and changes to the rules, applied over the bundled ones and the packs before it.
+// Entries with the same name (or modifier ID) as existing ones replace them.
+// A plain ruleset file is a pack without a manifest, and is written back without the empty parts.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RulesetPack {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>, // Names of the packs that must be applied before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<Class>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weapons: Vec<Weapon>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<ClassModifier>, // For classes defined elsewhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub abilities: Vec<Ability>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roster_rules: Option<RosterRules>, // Replaces the list-building rules as a whole
+    #[serde(default, skip_serializing_if = "PackRemovals::is_empty")]
+    pub remove: PackRemovals,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ClassModifier {
+    pub class: String,
+    #[serde(flatten)]
+    pub modifier: Modifier,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModifierRef {
+    pub class: String,
+    pub id: String,
+}
+
+// Entries of the previous packs to drop, by name (or class and ID for the modifiers).
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PackRemovals {
+    #[serde(default)]
+    pub classes: Vec<String>,
+    #[serde(default)]
+    pub weapons: Vec<String>,
+    #[serde(default)]
+    pub modifiers: Vec<ModifierRef>,
+    #[serde(default)]
+    pub abilities: Vec<String>,
+}
+
+impl PackRemovals {
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty() && self.weapons.is_empty() && self.modifiers.is_empty() && self.abilities.is_empty()
+    }
+}
+
+// The rules after applying all the packs.
+#[derive(Clone)]
+pub struct ResolvedRuleset {
+    pub ruleset: Ruleset,
+    pub abilities: AbilitiesConfig,
+    pub packs: Vec<String>, // "name version" of the applied packs, core included
+}
+
+impl ResolvedRuleset {
+    pub fn bundled() -> ResolvedRuleset {
+        ResolvedRuleset {
+            ruleset: Ruleset::bundled(),
+            abilities: crate::get_abilities(""),
+            packs: vec![CORE_PACK.to_string()],
+        }
+    }
+}
+
+impl RulesetPack {
+
+    // The name used in messages, for packs without a manifest.
+    pub fn display_name(&self) -> String {
+        match (self.name.is_empty(), self.version.is_empty()) {
+            (true, _) => "unnamed pack".to_string(),
+            (false, true) => self.name.clone(),
+            (false, false) => format!("{} {}", self.name, self.version),
+        }
+    }
+}
+
+// Applies the packs in order over the bundled rules. Fails if a pack needs one that is not
+// applied before it, or removes something that is not there.
+pub fn resolve_packs(packs: &[RulesetPack]) -> Result<ResolvedRuleset, String> {
+    let mut resolved = ResolvedRuleset::bundled();
+    let mut applied: Vec<&str> = vec![CORE_PACK];
+
+    for pack in packs {
+        let pack_name = pack.display_name();
+        if !pack.name.is_empty() && applied.contains(&pack.name.as_str()) {
+            return Err(format!("The pack {} is loaded twice", pack.name));
+        }
+        for dependency in &pack.dependencies {
+            if !applied.contains(&dependency.as_str()) {
+                return Err(format!("The pack {} needs {}, which must be loaded before it", pack_name, dependency));
+            }
+        }
+        apply_pack(&mut resolved, pack).map_err(|err| format!("{}: {}", pack_name, err))?;
+        if !pack.name.is_empty() {
+            applied.push(&pack.name);
+        }
+        resolved.packs.push(pack_name);
+    }
+    Ok(resolved)
+}
+
+fn apply_pack(resolved: &mut ResolvedRuleset, pack: &RulesetPack) -> Result<(), String> {
+    let ruleset = &mut resolved.ruleset;
+    let abilities = &mut resolved.abilities.abilities;
+
+    // Removals first, so that a pack can't remove what it adds
+    for name in &pack.remove.classes {
+        remove_by(&mut ruleset.classes, |class| class.name == *name).ok_or_else(|| format!("there is no class {} to remove", name))?;
+    }
+    for name in &pack.remove.weapons {
+        remove_by(&mut ruleset.weapons, |weapon| weapon.name == *name).ok_or_else(|| format!("there is no weapon {} to remove", name))?;
+    }
+    for name in &pack.remove.abilities {
+        remove_by(abilities, |ability| ability.name == *name).ok_or_else(|| format!("there is no ability {} to remove", name))?;
+    }
+    for modifier in &pack.remove.modifiers {
+        let class = ruleset.classes.iter_mut().find(|class| class.name == modifier.class)
+            .ok_or_else(|| format!("there is no class {} to remove the modifier {} from", modifier.class, modifier.id))?;
+        remove_by(&mut class.modifiers, |other| other.id == modifier.id)
+            .ok_or_else(|| format!("class {} has no modifier {} to remove", modifier.class, modifier.id))?;
+    }
+
+    for class in &pack.classes {
+        upsert(&mut ruleset.classes, class.clone(), |other| other.name == class.name);
+    }
+    for weapon in &pack.weapons {
+        upsert(&mut ruleset.weapons, weapon.clone(), |other| other.name == weapon.name);
+    }
+    for ability in &pack.abilities {
+        upsert(abilities, ability.clone(), |other| other.name == ability.name);
+    }
+    for addition in &pack.modifiers {
+        let class = ruleset.classes.iter_mut().find(|class| class.name == addition.class)
+            .ok_or_else(|| format!("there is no class {} for the modifier {}", addition.class, addition.modifier.id))?;
+        upsert(&mut class.modifiers, addition.modifier.clone(), |other| other.id == addition.modifier.id);
+    }
+    if let Some(roster_rules) = &pack.roster_rules {
+        ruleset.roster_rules = roster_rules.clone();
+    }
+    Ok(())
+}
+
+fn remove_by<T>(items: &mut Vec<T>, matches: impl Fn(&T) -> bool) -> Option<T> {
+    let index = items.iter().position(matches)?;
+    Some(items.remove(index))
+}
+
+// Replaces the matching item in place, or adds it at the end.
+fn upsert<T>(items: &mut Vec<T>, item: T, matches: impl Fn(&T) -> bool) {
+    match items.iter().position(matches) {
+        Some(index) => items[index] = item,
+        None => items.push(item),
+    }
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::profile::{new_profile_id, Profile};
+use super::roster::{new_roster_id, Roster};
+use super::ruleset::Ruleset;
+use crate::utils::unique_name::unique_name;
+
+pub const SAVE_VERSION: u32 = 1;
+
+// The content of a save file: the profiles, the rosters using them and, for homebrew
+// rules, the classes and weapons the profiles are built with.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SaveData {
+    #[serde(default)]
+    pub version: u32,
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub rosters: Vec<Roster>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<Ruleset>, // None for the bundled rules
+}
+
+// What to do with imported profiles and rosters that have the same ID or name as existing ones.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ConflictResolution {
+    KeepBoth,  // The imported one is added with a new name (and ID, if needed)
+    Overwrite, // The imported one replaces the existing one, keeping its ID
+    Skip,      // The existing one is kept
+}
+
+impl FromStr for ConflictResolution {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "keep-both" | "keep" => Ok(ConflictResolution::KeepBoth),
+            "overwrite" => Ok(ConflictResolution::Overwrite),
+            "skip" => Ok(ConflictResolution::Skip),
+            _ => Err(format!("Unknown conflict resolution: {} (expected keep-both, overwrite or skip)", value)),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MergeReport {
+    pub added: usize,
+    pub renamed: usize, // Added with a new name, among the added ones
+    pub overwritten: usize,
+    pub skipped: usize, // Including the ones identical to the existing ones
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} added ({} renamed), {} overwritten, {} skipped", self.added, self.renamed, self.overwritten, self.skipped)
+    }
+}
+
+// Save files written before the rosters were just the list of profiles (CLI), or the
+// profiles with an unused list of names (web app), which is simply ignored.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SaveFormat {
+    Current(SaveData),
+    ProfilesOnly(Vec<Profile>),
+}
+
+impl SaveData {
+
+    pub fn new(profiles: Vec<Profile>, rosters: Vec<Roster>) -> SaveData {
+        SaveData { version: SAVE_VERSION, profiles, rosters, ruleset: None }
+    }
+
+    pub fn with_ruleset(self, ruleset: Option<Ruleset>) -> SaveData {
+        SaveData { ruleset, ..self }
+    }
+
+    // A roster with just the profiles it uses, to share it on its own.
+    pub fn from_roster(roster: &Roster, profiles: &[Profile]) -> SaveData {
+        let profiles = roster.resolve(profiles).into_iter()
+            .filter_map(|(_, profile)| profile.cloned())
+            .collect();
+        SaveData::new(profiles, vec![roster.clone()])
+    }
+
+    pub fn from_json(json: &str) -> Result<SaveData, serde_json::Error> {
+        let save_data = match serde_json::from_str::<SaveFormat>(json)? {
+            SaveFormat::Current(save_data) => save_data,
+            SaveFormat::ProfilesOnly(profiles) => SaveData::new(profiles, Vec::new()),
+        };
+        Ok(SaveData { version: SAVE_VERSION, ..save_data })
+    }
+
+    // Reads what can still be read of a save that doesn't load any more, e.g. after a ruleset
+    // update changed the profiles: the profiles and rosters that fail are skipped.
+    // Returns the recovered data and the number of skipped items.
+    pub fn recover_from_json(json: &str) -> Result<(SaveData, usize), serde_json::Error> {
+        let value: Value = serde_json::from_str(json)?;
+        let (profile_values, roster_values) = match &value {
+            Value::Array(profiles) => (profiles.clone(), Vec::new()),
+            _ => (json_array(value.get("profiles")), json_array(value.get("rosters"))),
+        };
+
+        let profiles: Vec<Profile> = profile_values.iter()
+            .filter_map(|profile| serde_json::from_value(profile.clone()).ok())
+            .collect();
+        let rosters: Vec<Roster> = roster_values.iter()
+            .filter_map(|roster| serde_json::from_value(roster.clone()).ok())
+            .collect();
+        let skipped = profile_values.len() + roster_values.len() - profiles.len() - rosters.len();
+        let ruleset = value.get("ruleset").and_then(|ruleset| serde_json::from_value(ruleset.clone()).ok());
+        Ok((SaveData::new(profiles, rosters).with_ruleset(ruleset), skipped))
+    }
+
+    // The imported profiles and rosters with the same ID or name as existing ones, but a different content.
+    pub fn conflicts(&self, other: &SaveData) -> Vec<String> {
+        let profiles = other.profiles.iter().filter(|profile| find_conflict(&self.profiles, *profile).is_some_and(|index| self.profiles[index] != **profile));
+        let rosters = other.rosters.iter().filter(|roster| find_conflict(&self.rosters, *roster).is_some_and(|index| self.rosters[index] != **roster));
+        profiles.map(|profile| profile.name.clone())
+            .chain(rosters.map(|roster| format!("{} (roster)", roster.name)))
+            .collect()
+    }
+
+    // Adds the content of another save. The imported rosters follow their profiles when these get
+    // a new ID, or are replaced by existing ones. The ruleset is kept, unless there was none.
+    pub fn merge(&mut self, other: SaveData, resolution: ConflictResolution) -> MergeReport {
+        let mut report = MergeReport::default();
+        if self.ruleset.is_none() {
+            self.ruleset = other.ruleset;
+        }
+        let profile_ids = merge_items(&mut self.profiles, other.profiles, resolution, &mut report);
+
+        let mut rosters = other.rosters;
+        for entry in rosters.iter_mut().flat_map(|roster| roster.entries.iter_mut()) {
+            if let Some(id) = profile_ids.get(&entry.profile_id) {
+                entry.profile_id = id.clone();
+            }
+        }
+        merge_items(&mut self.rosters, rosters, resolution, &mut report);
+        report
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    pub fn find_profile(&self, profile_id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id == profile_id)
+    }
+
+    pub fn find_roster(&self, name_or_id: &str) -> Option<&Roster> {
+        self.rosters.iter().find(|roster| roster.id == name_or_id || roster.name == name_or_id)
+    }
+}
+
+fn json_array(value: Option<&Value>) -> Vec<Value> {
+    value.and_then(Value::as_array).cloned().unwrap_or_default()
+}
+
+// Profiles and rosters are merged the same way, matching them by ID or name.
+trait MergeItem: Clone + PartialEq {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    fn set_id(&mut self, id: String);
+    fn set_name(&mut self, name: String);
+    fn new_id() -> String;
+}
+
+impl MergeItem for Profile {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn set_id(&mut self, id: String) { self.id = id; }
+    fn set_name(&mut self, name: String) { self.name = name; }
+    fn new_id() -> String { new_profile_id() }
+}
+
+impl MergeItem for Roster {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn set_id(&mut self, id: String) { self.id = id; }
+    fn set_name(&mut self, name: String) { self.name = name; }
+    fn new_id() -> String { new_roster_id() }
+}
+
+fn find_conflict<T: MergeItem>(items: &[T], item: &T) -> Option<usize> {
+    items.iter().position(|existing| existing.id() == item.id())
+        .or_else(|| items.iter().position(|existing| existing.name() == item.name()))
+}
+
+// Returns the IDs that changed, from the imported ID to the one in the merged list.
+fn merge_items<T: MergeItem>(items: &mut Vec<T>, imported: Vec<T>, resolution: ConflictResolution, report: &mut MergeReport) -> HashMap<String, String> {
+    let mut changed_ids = HashMap::<String, String>::new();
+    for mut item in imported {
+        let Some(index) = find_conflict(items, &item) else {
+            items.push(item);
+            report.added += 1;
+            continue;
+        };
+        if items[index] == item {
+            report.skipped += 1;
+            continue;
+        }
+
+        match resolution {
+            ConflictResolution::Skip => {
+                changed_ids.insert(item.id().to_string(), items[index].id().to_string());
+                report.skipped += 1;
+            },
+            ConflictResolution::Overwrite => {
+                let id = items[index].id().to_string();
+                changed_ids.insert(item.id().to_string(), id.clone());
+                item.set_id(id);
+                items[index] = item;
+                report.overwritten += 1;
+            },
+            ConflictResolution::KeepBoth => {
+                if items.iter().any(|existing| existing.id() == item.id()) {
+                    let id = T::new_id();
+                    changed_ids.insert(item.id().to_string(), id.clone());
+                    item.set_id(id);
+                }
+                if items.iter().any(|existing| existing.name() == item.name()) {
+                    let name = unique_name(item.name(), |candidate| items.iter().any(|existing| existing.name() == candidate));
+                    item.set_name(name);
+                    report.renamed += 1;
+                }
+                items.push(item);
+                report.added += 1;
+            },
+        }
+    }
+    changed_ids
+}
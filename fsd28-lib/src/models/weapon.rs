@@ -1,26 +1,71 @@
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
 use super::action::Action; // Assuming Action is defined in action.rs
+use super::class::Tier;
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct WeaponsConfig {
     pub weapons: Vec<Weapon>,
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Weapon {
     pub name: String,
     pub options: Vec<WeaponOption>,
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct WeaponOption {
     pub action: Action,
     pub is_base: bool,
 }
 
+impl WeaponsConfig {
+
+    // The weapon a profile action was taken from: the one offering exactly that action.
+    // An action that is no longer in the rules is matched by name, if a single weapon has it.
+    pub fn weapon_of(&self, action: &Action) -> Option<&Weapon> {
+        let offers = |weapon: &&Weapon| weapon.options.iter().any(|option| option.action == *action);
+        if let Some(weapon) = self.weapons.iter().find(offers) {
+            return Some(weapon);
+        }
+        let mut named = self.weapons.iter().filter(|weapon| weapon.options.iter().any(|option| option.action.name == action.name));
+        match (named.next(), named.next()) {
+            (Some(weapon), None) => Some(weapon),
+            _ => None,
+        }
+    }
+}
+
 impl Weapon {
 
-    // Method to display the weapon and its actions, if needed
+    // The problems that would make the weapon unusable, for hand-made rulesets.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push("the weapon has no name".to_string());
+        }
+        for (index, option) in self.options.iter().enumerate() {
+            let action = &option.action;
+            if action.name.trim().is_empty() {
+                errors.push(format!("{}: action {} has no name", self.name, index + 1));
+            }
+            else if self.options[..index].iter().any(|other| other.action.name == action.name) {
+                errors.push(format!("{}: action {} is there twice", self.name, action.name));
+            }
+
+            // Activation ranges are faces of a d6, with (0, 0) for the free actions
+            for tier in [Tier::Goon, Tier::Char, Tier::Hero] {
+                for range in action.get_action_cost(&tier) {
+                    let free = range == (0, 0);
+                    if !free && (range.0 < 1 || range.1 > 6 || range.0 > range.1) {
+                        errors.push(format!("{}: the {} range {}-{} of {} is not within 1-6", self.name, tier, range.0, range.1, action.name));
+                    }
+                }
+            }
+        }
+        errors
+    }
 }
 
 impl WeaponOption {
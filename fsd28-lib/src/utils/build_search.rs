@@ -0,0 +1,179 @@
+use std::str::FromStr;
+
+use crate::models::action::Action;
+use crate::models::class::{Class, Tier};
+use crate::models::modifier::Modifier;
+use crate::models::profile::{Profile, MAX_ACTIONS};
+use crate::models::weapon::WeaponsConfig;
+use crate::utils::variant_generator::construction_errors;
+
+// Sides of the activation die.
+const DIE_FACES: u32 = 6;
+
+// Ranks the builds, higher is better. Closures work too: `|profile: &Profile| ...`.
+pub trait BuildScorer {
+    fn score(&self, profile: &Profile) -> f64;
+}
+
+impl<F: Fn(&Profile) -> f64> BuildScorer for F {
+    fn score(&self, profile: &Profile) -> f64 {
+        self(profile)
+    }
+}
+
+// The bundled scoring functions.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BuildScore {
+    Analytics, // Points of the actions, weighted by their chance to activate, per point spent
+    Cheapest,  // Lowest final cost
+    Coverage,  // Most activation die faces that trigger at least one action
+}
+
+impl FromStr for BuildScore {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "analytics" => Ok(BuildScore::Analytics),
+            "cheapest" => Ok(BuildScore::Cheapest),
+            "coverage" => Ok(BuildScore::Coverage),
+            _ => Err(format!("Unknown score: {} (expected analytics, cheapest or coverage)", value)),
+        }
+    }
+}
+
+impl BuildScorer for BuildScore {
+    fn score(&self, profile: &Profile) -> f64 {
+        let final_profile = profile.get_final_profile();
+        match self {
+            BuildScore::Analytics => {
+                let action_value: f64 = profile.actions.iter()
+                    .map(|action| action.points.max(1) as f64 * activation_chance(action, &profile.tier))
+                    .sum();
+                action_value / final_profile.cost.max(1) as f64
+            },
+            BuildScore::Cheapest => -(final_profile.cost as f64),
+            BuildScore::Coverage => covered_faces(&profile.actions, &profile.tier) as f64,
+        }
+    }
+}
+
+pub struct Build {
+    pub profile: Profile,
+    pub cost: u32, // Final cost, with the modifiers and actions
+    pub score: f64,
+}
+
+// Every legal combination of the class modifiers and up to MAX_ACTIONS weapon actions within
+// the budget, best first. A build holds at least one weapon, through one of its base actions. Equal scores are sorted by cost. At most `limit` builds are returned.
+pub fn search_builds(class: &Class, weapons: &WeaponsConfig, budget: u32, scorer: &dyn BuildScorer, limit: usize) -> Vec<Build> {
+    let actions = unique_actions(weapons);
+    let mut builds = Vec::<Build>::new();
+
+    for modifiers in modifier_combinations(&class.modifiers) {
+        let modifiers_cost: u32 = modifiers.iter().map(|modifier| modifier.points).sum();
+        for action_indices in action_combinations(actions.len()) {
+            let selected: Vec<&Action> = action_indices.iter().map(|index| actions[*index]).collect();
+            if !selected.iter().any(|action| is_base_action(action, weapons)) {
+                continue;
+            }
+
+            // Quick estimate of the cost, before building the profile
+            let mut estimate = class.cost + modifiers_cost + selected.iter().map(|action| action.points).sum::<u32>();
+            if selected.len() >= MAX_ACTIONS && class.tier == Tier::Goon {
+                estimate += 2;
+            }
+            if estimate > budget {
+                continue;
+            }
+
+            let mut profile = Profile::new(class.name.clone(), class.clone());
+            profile.selected_modifiers = modifiers.clone();
+            profile.actions = selected.into_iter().cloned().collect();
+            if !construction_errors(&profile, weapons).is_empty() {
+                continue;
+            }
+            let cost = profile.get_final_profile().cost;
+            if cost > budget {
+                continue;
+            }
+            let score = scorer.score(&profile);
+            builds.push(Build { profile, cost, score });
+        }
+    }
+
+    builds.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.cost.cmp(&b.cost)));
+    builds.truncate(limit);
+    builds
+}
+
+
+// PRIVATE METHODS
+
+// One candidate per weapon option: weapons offer actions with the same name and different dice.
+// Only the options that are exactly the same (like Shoot Grenade) are taken once.
+fn unique_actions(weapons: &WeaponsConfig) -> Vec<&Action> {
+    let mut actions = Vec::<&Action>::new();
+    for option in weapons.weapons.iter().flat_map(|weapon| weapon.options.iter()) {
+        if !actions.contains(&&option.action) {
+            actions.push(&option.action);
+        }
+    }
+    actions
+}
+
+fn is_base_action(action: &Action, weapons: &WeaponsConfig) -> bool {
+    weapons.weapons.iter()
+        .flat_map(|weapon| weapon.options.iter())
+        .any(|option| option.is_base && option.action == *action)
+}
+
+fn modifier_combinations(modifiers: &[Modifier]) -> Vec<Vec<Modifier>> {
+    (0..1usize << modifiers.len())
+        .map(|mask| modifiers.iter().enumerate()
+            .filter(|(index, _)| mask & (1 << index) != 0)
+            .map(|(_, modifier)| modifier.clone())
+            .collect())
+        .collect()
+}
+
+// The sets of 0 to MAX_ACTIONS indices, in increasing order.
+fn action_combinations(count: usize) -> Vec<Vec<usize>> {
+    let mut combinations = vec![Vec::new()];
+    let mut last = vec![Vec::new()];
+    for _ in 0..MAX_ACTIONS {
+        let next: Vec<Vec<usize>> = last.iter()
+            .flat_map(|combination: &Vec<usize>| {
+                let start = combination.last().map_or(0, |index| index + 1);
+                (start..count).map(move |index| {
+                    let mut extended = combination.clone();
+                    extended.push(index);
+                    extended
+                })
+            })
+            .collect();
+        combinations.extend(next.iter().cloned());
+        last = next;
+    }
+    combinations
+}
+
+// A range starting at 0 is a free action, always available.
+fn faces_of(action: &Action, tier: &Tier) -> Vec<u32> {
+    let mut faces = Vec::<u32>::new();
+    for (from, to) in action.get_action_cost(tier) {
+        let range = if from == 0 { 1..=DIE_FACES } else { from..=to.min(DIE_FACES) };
+        faces.extend(range.filter(|face| !faces.contains(face)).collect::<Vec<u32>>());
+    }
+    faces
+}
+
+fn activation_chance(action: &Action, tier: &Tier) -> f64 {
+    faces_of(action, tier).len() as f64 / DIE_FACES as f64
+}
+
+fn covered_faces(actions: &[Action], tier: &Tier) -> usize {
+    (1..=DIE_FACES)
+        .filter(|face| actions.iter().any(|action| faces_of(action, tier).contains(face)))
+        .count()
+}
@@ -0,0 +1,65 @@
+use std::fs::read_to_string;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// The formats the rules files can be written in, told apart by the file extension.
+// Anything that is not YAML or TOML is read as JSON, as before.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FileFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FileFormat {
+    pub const ALL: [FileFormat; 3] = [FileFormat::Json, FileFormat::Yaml, FileFormat::Toml];
+
+    pub fn from_path(path: &Path) -> FileFormat {
+        match path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase()).as_deref() {
+            Some("yaml") | Some("yml") => FileFormat::Yaml,
+            Some("toml") => FileFormat::Toml,
+            _ => FileFormat::Json,
+        }
+    }
+
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileFormat::Json => &["json"],
+            FileFormat::Yaml => &["yaml", "yml"],
+            FileFormat::Toml => &["toml"],
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, String> {
+        match self {
+            FileFormat::Json => serde_json::from_str(content).map_err(|err| err.to_string()),
+            FileFormat::Yaml => serde_yaml::from_str(content).map_err(|err| err.to_string()),
+            FileFormat::Toml => toml::from_str(content).map_err(|err| err.to_string()),
+        }
+    }
+
+    pub fn write<T: Serialize>(&self, value: &T) -> Result<String, String> {
+        match self {
+            FileFormat::Json => serde_json::to_string_pretty(value).map_err(|err| err.to_string()),
+            FileFormat::Yaml => serde_yaml::to_string(value).map_err(|err| err.to_string()),
+            FileFormat::Toml => toml::to_string_pretty(value).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+// Reads a rules file in the format of its extension. The errors name the file.
+pub fn read_rules_file<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+    let content = read_to_string(path).map_err(|err| Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+    FileFormat::from_path(path).parse(&content).map_err(|err| Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), err)))
+}
+
+// The file with the given name in the folder, in any of the formats (classes.json, classes.yaml, ...).
+pub fn find_rules_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    FileFormat::ALL.iter()
+        .flat_map(|format| format.extensions())
+        .map(|extension| dir.join(format!("{}.{}", name, extension)))
+        .find(|path| path.is_file())
+}
@@ -1 +1,14 @@
-pub mod pdf_ascii_generator;
\ No newline at end of file
+pub mod pdf_ascii_generator;
+pub mod pdf_card_generator;
+pub mod raster_canvas;
+pub mod png_card_generator;
+pub mod roster_exporter;
+pub mod roster_importer;
+pub mod share_code;
+pub mod variant_generator;
+pub mod build_search;
+pub mod ruleset_diff;
+pub mod schema;
+pub mod file_format;
+pub mod rules_csv;
+pub mod unique_name;
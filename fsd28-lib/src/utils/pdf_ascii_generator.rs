@@ -2,6 +2,7 @@ use regex::Regex;
 use std::fs::File;
 use std::io::BufWriter;
 use crate::Profile;
+use crate::models::ability::{AbilitiesConfig, AbilityDisplay};
 
 // Removes the cool looking colour and font options that are used for the console display.
 fn strip_ansi_codes(s: &str) -> String {
@@ -12,14 +13,19 @@ fn strip_ansi_codes(s: &str) -> String {
 
 // Outputs the PDF file.
 pub fn create_pdf_ascii(profiles: Vec<Profile>, file_name: &str){
+    create_pdf_ascii_with_abilities(profiles, &AbilitiesConfig::default(), AbilityDisplay::Names, file_name);
+}
+
+// Outputs the PDF file, expanding the special abilities with their rules text.
+pub fn create_pdf_ascii_with_abilities(profiles: Vec<Profile>, abilities: &AbilitiesConfig, display: AbilityDisplay, file_name: &str){
     let mut combined_profiles = String::new();
 
     println!("called create_pdf_ascii");
 
     for profile_pair in profiles.chunks(2) {
-        let profile1_ascii = profile_pair[0].display_ascii();
+        let profile1_ascii = profile_pair[0].display_ascii_with_abilities(abilities, display);
         let profile2_ascii = if profile_pair.len() > 1 {
-            profile_pair[1].display_ascii()
+            profile_pair[1].display_ascii_with_abilities(abilities, display)
         } else {
             "".to_string() // Handle case where there's an odd number of profiles
         };
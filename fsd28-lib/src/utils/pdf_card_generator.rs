@@ -0,0 +1,139 @@
+use image::RgbaImage;
+use lopdf::{Document, Object, Dictionary, Stream, content::{Content, Operation}};
+
+use crate::Profile;
+use crate::utils::png_card_generator::{
+    CardBack,
+    CardOptions,
+    collect_reference_entries,
+    render_card,
+    render_card_back,
+    render_reference_cards,
+};
+
+// A4 page and poker-sized cards, in PDF points (1/72 inch).
+const PAGE_WIDTH: f32 = 595.28;
+const PAGE_HEIGHT: f32 = 841.89;
+const CARD_WIDTH: f32 = 2.5 * 72.0;
+const CARD_HEIGHT: f32 = 3.5 * 72.0;
+const CARDS_PER_ROW: usize = 3;
+const CARDS_PER_COLUMN: usize = 3;
+const CARDS_PER_PAGE: usize = CARDS_PER_ROW * CARDS_PER_COLUMN;
+
+pub struct PdfCardOptions {
+    pub card: CardOptions,
+    pub include_reference: bool,
+    pub back: Option<CardBack>, // If set, every page of fronts is followed by a page of backs
+}
+
+// Outputs a printable PDF with the unit cards at their physical size, followed by
+// the reference cards. Backs are mirrored horizontally, for duplex printing.
+pub fn create_pdf_cards(profiles: &[Profile], options: &PdfCardOptions, file_name: &str) -> Result<(), std::io::Error> {
+    let mut buffer = std::io::BufWriter::new(std::fs::File::create(file_name)?);
+    create_pdf_cards_document(profiles, options).save_to(&mut buffer)
+}
+
+// Same as create_pdf_cards, but returning the file content instead of writing it.
+pub fn create_pdf_cards_bytes(profiles: &[Profile], options: &PdfCardOptions) -> Result<Vec<u8>, std::io::Error> {
+    let mut buffer = Vec::<u8>::new();
+    create_pdf_cards_document(profiles, options).save_to(&mut buffer)?;
+    Ok(buffer)
+}
+
+
+// PRIVATE METHODS
+
+fn create_pdf_cards_document(profiles: &[Profile], options: &PdfCardOptions) -> Document {
+    let mut fronts: Vec<RgbaImage> = profiles.iter().map(|profile| render_card(profile, &options.card)).collect();
+    if options.include_reference {
+        fronts.extend(render_reference_cards(&collect_reference_entries(profiles, &options.card.abilities), &options.card));
+    }
+    let back = options.back.as_ref().map(|back| render_card_back(back, &options.card));
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let mut page_ids = Vec::<Object>::new();
+
+    for page_cards in fronts.chunks(CARDS_PER_PAGE) {
+        let slots: Vec<(usize, &RgbaImage)> = page_cards.iter().enumerate().collect();
+        page_ids.push(Object::Reference(add_cards_page(&mut doc, pages_id, &slots)));
+
+        if let Some(back) = &back {
+            let mirrored_slots: Vec<(usize, &RgbaImage)> = (0..page_cards.len())
+                .map(|index| (mirrored_slot(index), back))
+                .collect();
+            page_ids.push(Object::Reference(add_cards_page(&mut doc, pages_id, &mirrored_slots)));
+        }
+    }
+
+    let pages_count = page_ids.len() as i64;
+    doc.objects.insert(pages_id, Object::Dictionary(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Pages".to_vec())),
+        ("Kids", Object::Array(page_ids)),
+        ("Count", Object::Integer(pages_count)),
+    ])));
+
+    let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Catalog".to_vec())),
+        ("Pages", Object::Reference(pages_id)),
+    ]));
+    doc.trailer.set("Root", catalog_id);
+    doc
+}
+
+// Slot of the back matching the front in the given slot, once the sheet is flipped on its long edge.
+fn mirrored_slot(index: usize) -> usize {
+    let row = index / CARDS_PER_ROW;
+    let column = index % CARDS_PER_ROW;
+    row * CARDS_PER_ROW + (CARDS_PER_ROW - 1 - column)
+}
+
+fn add_cards_page(doc: &mut Document, pages_id: lopdf::ObjectId, slots: &[(usize, &RgbaImage)]) -> lopdf::ObjectId {
+    let margin_x = (PAGE_WIDTH - CARDS_PER_ROW as f32 * CARD_WIDTH) / 2.0;
+    let margin_y = (PAGE_HEIGHT - CARDS_PER_COLUMN as f32 * CARD_HEIGHT) / 2.0;
+
+    let mut content = Content { operations: vec![] };
+    let mut images = Dictionary::new();
+    for (index, (slot, card)) in slots.iter().enumerate() {
+        let image_name = format!("Im{}", index);
+        images.set(image_name.clone(), Object::Reference(doc.add_object(image_stream(card))));
+
+        // PDF coordinates start from the bottom-left corner
+        let x = margin_x + (slot % CARDS_PER_ROW) as f32 * CARD_WIDTH;
+        let y = PAGE_HEIGHT - margin_y - (slot / CARDS_PER_ROW + 1) as f32 * CARD_HEIGHT;
+        content.operations.push(Operation::new("q", vec![]));
+        content.operations.push(Operation::new("cm", vec![
+            CARD_WIDTH.into(), 0.into(), 0.into(), CARD_HEIGHT.into(), x.into(), y.into()]));
+        content.operations.push(Operation::new("Do", vec![Object::Name(image_name.into_bytes())]));
+        content.operations.push(Operation::new("Q", vec![]));
+    }
+
+    let content_id = doc.add_object(Stream::new(Dictionary::new(), content.encode().unwrap()));
+    let resources = Dictionary::from_iter(vec![
+        ("XObject", Object::Dictionary(images)),
+    ]);
+    let page = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Page".to_vec())),
+        ("Parent", Object::Reference(pages_id)),
+        ("Resources", Object::Dictionary(resources)),
+        ("MediaBox", Object::Array(vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()])),
+        ("Contents", Object::Reference(content_id)),
+    ]);
+    doc.add_object(page)
+}
+
+// The card as an RGB image XObject. Cards are opaque, so the alpha channel is dropped.
+fn image_stream(card: &RgbaImage) -> Stream {
+    let rgb: Vec<u8> = card.pixels().flat_map(|pixel| [pixel.0[0], pixel.0[1], pixel.0[2]]).collect();
+    let dict = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"XObject".to_vec())),
+        ("Subtype", Object::Name(b"Image".to_vec())),
+        ("Width", Object::Integer(card.width() as i64)),
+        ("Height", Object::Integer(card.height() as i64)),
+        ("ColorSpace", Object::Name(b"DeviceRGB".to_vec())),
+        ("BitsPerComponent", Object::Integer(8)),
+    ]);
+    let mut stream = Stream::new(dict, rgb);
+    let _ = stream.compress(); // If compression fails the stream is simply stored uncompressed
+    stream
+}
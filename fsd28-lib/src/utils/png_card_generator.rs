@@ -0,0 +1,530 @@
+use image::{imageops, ImageFormat, Rgba, RgbaImage};
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::write::{FileOptions, ZipWriter};
+
+use crate::Profile;
+use crate::models::ability::{AbilitiesConfig, AbilityDisplay};
+use crate::models::action::Action;
+use crate::models::characteristics::Characteristics;
+use crate::models::card_theme::{parse_hex_color, CardTheme};
+use crate::models::class::Tier;
+use crate::models::damage_chart::DamageChart;
+use crate::utils::raster_canvas::{RasterCanvas, TextAlign, TextBaseline, BLACK, WHITE};
+
+// The layout mirrors the canvas card of the web app. All the sizes are expressed
+// in pixels at 300 DPI, and scaled when rendering at a different resolution.
+// Font sizes, colours and borders come from the CardTheme.
+pub const DEFAULT_DPI: u32 = 300;
+const BASE_DPI: f32 = 300.0;
+const CARD_WIDTH: f32 = 750.0; // 2.5 inches at 300 DPI
+const CARD_HEIGHT: f32 = 1050.0; // 3.5 inches at 300 DPI
+const MARGIN: f32 = 30.0;
+const STAT_GRID_ROWS: usize = 2;
+const STAT_GRID_COLS: usize = 3;
+const STAT_CELL_HEIGHT: f32 = 80.0;
+const DICE_BOX_SIZE: f32 = 10.0 * 11.81; // 10mm at 300 DPI
+const DAMAGE_CHART_HEIGHT: f32 = 60.0;
+const DAMAGE_SLOT_PADDING: f32 = 5.0;
+const LINE_HEIGHT: f32 = 1.2; // Line height multiplier
+const REFERENCE_HEADER_SIZE: f32 = 48.0;
+const REFERENCE_TITLE_SIZE: f32 = 32.0;
+const REFERENCE_TEXT_SIZE: f32 = 26.0;
+const REFERENCE_ENTRY_SPACING: f32 = 16.0;
+const BACK_FRAME_WIDTH: f32 = 12.0;
+const BACK_FRAME_RADIUS: f32 = 30.0;
+
+// Rendering settings shared by all the cards of a deck.
+#[derive(Clone, PartialEq)]
+pub struct CardOptions {
+    pub dpi: u32,
+    pub abilities: AbilitiesConfig, // Glossary used to expand the special abilities
+    pub ability_display: AbilityDisplay,
+    pub theme: CardTheme,
+    pub show_notes: bool, // Prints the notes and tags of the profiles above the special abilities
+}
+
+impl Default for CardOptions {
+    fn default() -> Self {
+        CardOptions {
+            dpi: DEFAULT_DPI,
+            abilities: AbilitiesConfig::default(),
+            ability_display: AbilityDisplay::Names,
+            theme: CardTheme::default(),
+            show_notes: false,
+        }
+    }
+}
+
+// What is printed on the back of every card of the deck.
+#[derive(Clone, Default, PartialEq)]
+pub struct CardBack {
+    pub faction_name: String,
+    pub roster_name: String,
+    pub logo_png: Option<Vec<u8>>, // Encoded PNG, as read from file
+}
+
+// A single rule summarised on the reference cards: an ability or an action text.
+#[derive(Clone, PartialEq)]
+pub struct ReferenceEntry {
+    pub title: String,
+    pub text: String,
+}
+
+// Card drawing helper, keeping track of the scale between the layout and the output DPI.
+struct CardPainter {
+    canvas: RasterCanvas,
+    scale: f32,
+    theme: CardTheme,
+    text_color: Rgba<u8>,
+    accent_color: Rgba<u8>,
+}
+
+impl CardPainter {
+    fn new(options: &CardOptions) -> CardPainter {
+        let scale = options.dpi as f32 / BASE_DPI;
+        let theme = options.theme.clone();
+        let text_color = theme_color(&theme.text_color, BLACK);
+        CardPainter {
+            canvas: RasterCanvas::new((CARD_WIDTH * scale).round() as u32, (CARD_HEIGHT * scale).round() as u32, theme_color(&theme.background, WHITE)),
+            scale,
+            text_color,
+            accent_color: theme_color(theme.accent_color(), text_color),
+            theme,
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, x: f32, y: f32, size: f32, align: TextAlign, baseline: TextBaseline) {
+        let scale = self.scale;
+        self.canvas.draw_text(text, x * scale, y * scale, size * scale, self.text_color, align, baseline);
+    }
+
+    fn draw_wrapped_text(&mut self, text: &str, x: f32, y: f32, max_width: f32, size: f32, align: TextAlign) -> f32 {
+        self.draw_wrapped_text_colored(text, x, y, max_width, size, align, self.text_color)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_wrapped_text_colored(&mut self, text: &str, x: f32, y: f32, max_width: f32, size: f32, align: TextAlign, color: Rgba<u8>) -> f32 {
+        let scale = self.scale;
+        self.canvas.draw_wrapped_text(text, x * scale, y * scale, max_width * scale, size * scale, LINE_HEIGHT, color, align) / scale
+    }
+
+    // Frame around the card, only drawn if the theme has one.
+    fn draw_frame(&mut self) {
+        let border = &self.theme.border;
+        if border.frame_width <= 0.0 {
+            return;
+        }
+        let scale = self.scale;
+        let inset = border.frame_width / 2.0;
+        self.canvas.stroke_rounded_rect(inset * scale, inset * scale, (CARD_WIDTH - 2.0 * inset) * scale, (CARD_HEIGHT - 2.0 * inset) * scale,
+            border.frame_radius * scale, border.frame_width * scale, self.accent_color);
+    }
+
+    fn wrapped_text_height(&self, text: &str, max_width: f32, size: f32) -> f32 {
+        let lines = self.canvas.wrap_text(text, max_width * self.scale, size * self.scale).len().max(1);
+        lines as f32 * size * LINE_HEIGHT
+    }
+
+    fn draw_box(&mut self, x: f32, y: f32) {
+        let scale = self.scale;
+        self.canvas.stroke_rounded_rect(x * scale, y * scale, DICE_BOX_SIZE * scale, DICE_BOX_SIZE * scale,
+            self.theme.border.dice_box_radius * scale, self.theme.border.dice_box_width * scale, self.text_color);
+    }
+
+    fn draw_title(&mut self, title: &str, subtitle: &str) {
+        let title_y = MARGIN + 20.0;
+        self.draw_wrapped_text_colored(&title.to_uppercase(), CARD_WIDTH / 2.0, title_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.title, TextAlign::Center, self.accent_color);
+
+        let subtitle_y = title_y + self.theme.sizes.title * LINE_HEIGHT - 10.0;
+        self.draw_wrapped_text(&subtitle.to_uppercase(), CARD_WIDTH / 2.0, subtitle_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.subtitle, TextAlign::Center);
+    }
+
+    fn draw_points_label(&mut self, points: u32) {
+        let scale = self.scale;
+        self.canvas.draw_text(&format!("{} PTS", points), (CARD_WIDTH - MARGIN) * scale, MARGIN * scale, self.theme.sizes.subtitle * scale,
+            self.accent_color, TextAlign::Right, TextBaseline::Top);
+    }
+
+    fn draw_stats_grid(&mut self, stats: &Characteristics) {
+        let grid_start_y = MARGIN + self.theme.sizes.title * LINE_HEIGHT + self.theme.sizes.subtitle * LINE_HEIGHT + 20.0;
+        let cell_width = (CARD_WIDTH - 2.0 * MARGIN) / STAT_GRID_COLS as f32;
+
+        let stats_text = [
+            ("Cmd", stats.stat_cmd.to_string()),
+            ("Def", stats.stat_def.to_string()),
+            ("Save", stats.stat_save.display()),
+            ("Move", stats.stat_move.to_string()),
+            ("Shoot", stats.stat_shoot.display()),
+            ("Melee", stats.stat_melee.display()),
+        ];
+
+        for (i, row) in stats_text.chunks(STAT_GRID_COLS).enumerate() {
+            for (j, (name, value)) in row.iter().enumerate() {
+                let x = MARGIN + j as f32 * cell_width + cell_width / 2.0;
+                let y = grid_start_y + i as f32 * STAT_CELL_HEIGHT;
+                self.draw_text(name, x, y, self.theme.sizes.text, TextAlign::Center, TextBaseline::Top);
+                self.draw_text(value, x, y + 25.0, self.theme.sizes.text * 2.0, TextAlign::Center, TextBaseline::Top);
+            }
+        }
+    }
+
+    fn action_text_width(&self, action: &Action, tier: &Tier) -> f32 {
+        let boxes = action.get_action_cost(tier).len().max(1) as f32;
+        let text_start_x = MARGIN + 30.0 + boxes * (DICE_BOX_SIZE + 10.0);
+        if action.slot {
+            CARD_WIDTH - MARGIN - DICE_BOX_SIZE - 10.0 - text_start_x
+        } else {
+            CARD_WIDTH - MARGIN - text_start_x
+        }
+    }
+
+    fn action_height(&self, action: &Action, tier: &Tier) -> f32 {
+        let title_height = self.theme.sizes.action_title * LINE_HEIGHT;
+        let description_height = self.wrapped_text_height(&action.text, self.action_text_width(action, tier), self.theme.sizes.action_description);
+        f32::max(DICE_BOX_SIZE, title_height + description_height) + 20.0
+    }
+
+    fn draw_actions(&mut self, actions: &[Action], tier: &Tier) {
+        let mut current_y = MARGIN + self.theme.sizes.title * LINE_HEIGHT + self.theme.sizes.subtitle * LINE_HEIGHT + 20.0
+            + STAT_GRID_ROWS as f32 * STAT_CELL_HEIGHT + MARGIN;
+
+        for (i, action) in actions.iter().enumerate() {
+            let action_height = self.action_height(action, tier);
+            let scale = self.scale;
+            self.canvas.draw_text_rotated(&format!("S{}", i + 1), (MARGIN + 15.0) * scale, (current_y + action_height / 2.0) * scale, self.theme.sizes.text * scale, self.text_color);
+            self.draw_action(action, tier, current_y, action_height);
+            current_y += action_height + MARGIN;
+        }
+    }
+
+    fn draw_action(&mut self, action: &Action, tier: &Tier, y: f32, action_height: f32) {
+        let vertical_center = y + action_height / 2.0;
+        let box_y = vertical_center - DICE_BOX_SIZE / 2.0;
+
+        // Cost boxes, or a FREE box if the action has no activation range for this tier
+        let mut costs = action.get_action_cost(tier);
+        if costs.is_empty() {
+            costs.push((0, 0));
+        }
+        let mut x_cursor = MARGIN + 30.0;
+        for range in &costs {
+            self.draw_box(x_cursor, box_y);
+            let (text, size) = if range.0 == 0 {
+                ("FREE".to_string(), self.theme.sizes.action_cost * 0.5)
+            } else if range.0 == range.1 {
+                (range.0.to_string(), self.theme.sizes.action_cost)
+            } else {
+                (format!("{}-{}", range.0, range.1), self.theme.sizes.action_cost * 0.7)
+            };
+            self.draw_text(&text, x_cursor + DICE_BOX_SIZE / 2.0, vertical_center, size, TextAlign::Center, TextBaseline::Middle);
+            x_cursor += DICE_BOX_SIZE + 10.0;
+        }
+
+        // Shrinking the description font up to two times if it does not fit
+        let text_width = self.action_text_width(action, tier);
+        let title_height = self.theme.sizes.action_title * LINE_HEIGHT;
+        let mut font_size = self.theme.sizes.action_description;
+        for _ in 0..2 {
+            if self.wrapped_text_height(&action.text, text_width, font_size) <= action_height - title_height {
+                break;
+            }
+            font_size -= 2.0;
+        }
+        let description_height = self.wrapped_text_height(&action.text, text_width, font_size);
+        let text_start_y = vertical_center - (title_height + description_height) / 2.0;
+
+        self.draw_text(&action.name, x_cursor, text_start_y, self.theme.sizes.action_title, TextAlign::Left, TextBaseline::Top);
+        self.draw_wrapped_text(&action.text, x_cursor, text_start_y + title_height, text_width, font_size, TextAlign::Left);
+
+        // Prepared box, if the action can be kept ready
+        if action.slot {
+            self.draw_box(CARD_WIDTH - MARGIN - DICE_BOX_SIZE, box_y);
+        }
+    }
+
+    // Returns where the abilities start, for what is drawn above them.
+    fn draw_special_abilities(&mut self, abilities: &[String], options: &CardOptions) -> f32 {
+        let lines = options.abilities.format_abilities(abilities, options.ability_display);
+        let abilities_end_y = CARD_HEIGHT - DAMAGE_CHART_HEIGHT - 20.0 - self.theme.sizes.text;
+        if lines.is_empty() {
+            return abilities_end_y;
+        }
+
+        // Just the names are centered, as a single block. Expanded abilities are stacked on the left.
+        if options.ability_display == AbilityDisplay::Names {
+            let text_height = self.wrapped_text_height(&lines[0], CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.action_title);
+            self.draw_wrapped_text(&lines[0], CARD_WIDTH / 2.0, abilities_end_y - text_height, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.action_title, TextAlign::Center);
+            abilities_end_y - text_height
+        }
+        else {
+            let text_height: f32 = lines.iter()
+                .map(|line| self.wrapped_text_height(line, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text))
+                .sum();
+            let mut current_y = abilities_end_y - text_height;
+            for line in &lines {
+                current_y += self.draw_wrapped_text(line, MARGIN, current_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text, TextAlign::Left);
+            }
+            abilities_end_y - text_height
+        }
+    }
+
+    // The notes and tags of the profile, centered and ending just above the given height.
+    fn draw_notes(&mut self, notes: &str, tags: &[String], end_y: f32) {
+        let lines: Vec<String> = [notes.trim().to_string(), tags.join(", ")].into_iter().filter(|line| !line.is_empty()).collect();
+        let text_height: f32 = lines.iter()
+            .map(|line| self.wrapped_text_height(line, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text))
+            .sum();
+        let mut current_y = end_y - self.theme.sizes.text - text_height;
+        for line in &lines {
+            current_y += self.draw_wrapped_text(line, CARD_WIDTH / 2.0, current_y, CARD_WIDTH - 2.0 * MARGIN, self.theme.sizes.text, TextAlign::Center);
+        }
+    }
+
+    fn draw_damage_chart(&mut self, chart: &DamageChart) {
+        let chart_start_y = CARD_HEIGHT - DAMAGE_CHART_HEIGHT;
+        let column_width = (CARD_WIDTH - 2.0 * MARGIN) / 6.0;
+
+        for i in 1..=6 {
+            let x = MARGIN + (i - 1) as f32 * column_width + column_width / 2.0;
+            self.draw_text(&i.to_string(), x, chart_start_y - 5.0 - self.theme.sizes.text, self.theme.sizes.text, TextAlign::Center, TextBaseline::Top);
+        }
+
+        let scale = self.scale;
+        let mut current_x = MARGIN;
+        for (span, color, text) in &chart.intervals {
+            let width = *span as f32 * column_width;
+            self.canvas.fill_rect(
+                (current_x + DAMAGE_SLOT_PADDING) * scale,
+                (chart_start_y + DAMAGE_SLOT_PADDING) * scale,
+                (width - 2.0 * DAMAGE_SLOT_PADDING) * scale,
+                DAMAGE_CHART_HEIGHT * scale,
+                theme_color(self.theme.damage_palette.color(color), BLACK));
+            self.canvas.draw_text(&text.to_uppercase(), (current_x + width / 2.0) * scale,
+                (chart_start_y + DAMAGE_SLOT_PADDING + (DAMAGE_CHART_HEIGHT - DAMAGE_SLOT_PADDING) / 2.0) * scale,
+                self.theme.sizes.damage_effect * scale, theme_color(&self.theme.damage_text_color, WHITE), TextAlign::Center, TextBaseline::Middle);
+            current_x += width;
+        }
+    }
+}
+
+impl CardPainter {
+
+    fn reference_entry_height(&self, entry: &ReferenceEntry) -> f32 {
+        let text_height = if entry.text.is_empty() {
+            0.0
+        } else {
+            self.wrapped_text_height(&entry.text, CARD_WIDTH - 2.0 * MARGIN, REFERENCE_TEXT_SIZE)
+        };
+        REFERENCE_TITLE_SIZE * LINE_HEIGHT + text_height + REFERENCE_ENTRY_SPACING
+    }
+
+    fn reference_content_start(&self) -> f32 {
+        MARGIN + REFERENCE_HEADER_SIZE * LINE_HEIGHT + 20.0
+    }
+
+    fn draw_reference_card(&mut self, entries: &[&ReferenceEntry], page: usize, pages: usize) {
+        self.draw_text("REFERENCE", CARD_WIDTH / 2.0, MARGIN, REFERENCE_HEADER_SIZE, TextAlign::Center, TextBaseline::Top);
+        if pages > 1 {
+            self.draw_text(&format!("{}/{}", page, pages), CARD_WIDTH - MARGIN, MARGIN, self.theme.sizes.text, TextAlign::Right, TextBaseline::Top);
+        }
+
+        let mut current_y = self.reference_content_start();
+        for entry in entries {
+            self.draw_text(&entry.title.to_uppercase(), MARGIN, current_y, REFERENCE_TITLE_SIZE, TextAlign::Left, TextBaseline::Top);
+            current_y += REFERENCE_TITLE_SIZE * LINE_HEIGHT;
+            if !entry.text.is_empty() {
+                current_y += self.draw_wrapped_text(&entry.text, MARGIN, current_y, CARD_WIDTH - 2.0 * MARGIN, REFERENCE_TEXT_SIZE, TextAlign::Left);
+            }
+            current_y += REFERENCE_ENTRY_SPACING;
+        }
+    }
+
+    fn draw_card_back(&mut self, back: &CardBack) {
+        let scale = self.scale;
+        self.canvas.stroke_rounded_rect(MARGIN * scale, MARGIN * scale, (CARD_WIDTH - 2.0 * MARGIN) * scale, (CARD_HEIGHT - 2.0 * MARGIN) * scale,
+            BACK_FRAME_RADIUS * scale, BACK_FRAME_WIDTH * scale, self.accent_color);
+
+        let inner_width = CARD_WIDTH - 4.0 * MARGIN;
+        let faction_height = self.wrapped_text_height(&back.faction_name.to_uppercase(), inner_width, self.theme.sizes.title);
+        self.draw_wrapped_text_colored(&back.faction_name.to_uppercase(), CARD_WIDTH / 2.0, 3.0 * MARGIN, inner_width, self.theme.sizes.title, TextAlign::Center, self.accent_color);
+
+        let roster_height = self.wrapped_text_height(&back.roster_name, inner_width, self.theme.sizes.action_title);
+        let roster_y = CARD_HEIGHT - 3.0 * MARGIN - roster_height;
+        self.draw_wrapped_text(&back.roster_name, CARD_WIDTH / 2.0, roster_y, inner_width, self.theme.sizes.action_title, TextAlign::Center);
+
+        // The logo is scaled to fit the space left between the two texts
+        let logo = back.logo_png.as_ref()
+            .and_then(|png| image::load_from_memory_with_format(png, ImageFormat::Png).ok());
+        if let Some(logo) = logo {
+            let area_top = 3.0 * MARGIN + faction_height + MARGIN;
+            let area_height = roster_y - MARGIN - area_top;
+            if area_height > 0.0 && logo.width() > 0 && logo.height() > 0 {
+                let fit = f32::min(inner_width / logo.width() as f32, area_height / logo.height() as f32);
+                let logo_width = (logo.width() as f32 * fit * scale).max(1.0) as u32;
+                let logo_height = (logo.height() as f32 * fit * scale).max(1.0) as u32;
+                let resized = imageops::resize(&logo.to_rgba8(), logo_width, logo_height, imageops::FilterType::Triangle);
+                let logo_x = CARD_WIDTH * scale / 2.0 - logo_width as f32 / 2.0;
+                let logo_y = (area_top + area_height / 2.0) * scale - logo_height as f32 / 2.0;
+                self.canvas.draw_image(&resized, logo_x, logo_y);
+            }
+        }
+    }
+}
+
+// Theme colours that cannot be parsed fall back to the classic ones.
+fn theme_color(color: &str, fallback: Rgba<u8>) -> Rgba<u8> {
+    parse_hex_color(color).map_or(fallback, |[red, green, blue]| Rgba([red, green, blue, 255]))
+}
+
+// Renders the card of a profile (with its modifiers applied) at the given resolution.
+pub fn render_card(profile: &Profile, options: &CardOptions) -> RgbaImage {
+    let final_profile = profile.get_final_profile();
+    let mut painter = CardPainter::new(options);
+
+    painter.draw_frame();
+    painter.draw_title(&final_profile.name, &final_profile.description);
+    painter.draw_points_label(final_profile.cost);
+    painter.draw_stats_grid(&final_profile.characteristics);
+    painter.draw_actions(&final_profile.actions, &final_profile.tier);
+    let abilities_y = painter.draw_special_abilities(&final_profile.special_abilities, options);
+    if options.show_notes {
+        painter.draw_notes(&final_profile.notes, &final_profile.tags, abilities_y);
+    }
+    painter.draw_damage_chart(&final_profile.damage_chart);
+
+    painter.canvas.into_image()
+}
+
+// Gathers the special abilities and the action texts used by the profiles, without repetitions.
+// Abilities come first (with their rules text, if in the glossary), then actions, both sorted by name.
+pub fn collect_reference_entries(profiles: &[Profile], abilities_glossary: &AbilitiesConfig) -> Vec<ReferenceEntry> {
+    let final_profiles: Vec<Profile> = profiles.iter().map(|profile| profile.get_final_profile()).collect();
+
+    let mut abilities: Vec<String> = final_profiles.iter()
+        .flat_map(|profile| profile.special_abilities.iter().cloned())
+        .collect();
+    abilities.sort();
+    abilities.dedup();
+
+    let mut actions: Vec<&Action> = final_profiles.iter()
+        .flat_map(|profile| profile.actions.iter())
+        .collect();
+    // Actions of different weapons can share a name with another text (e.g. Overwatch)
+    actions.sort_by(|first, second| (&first.name, &first.text).cmp(&(&second.name, &second.text)));
+    actions.dedup_by(|first, second| first.name == second.name && first.text == second.text);
+
+    abilities.into_iter()
+        .map(|ability| ReferenceEntry {
+            text: abilities_glossary.find(&ability).map(|definition| definition.text.clone()).unwrap_or_default(),
+            title: ability,
+        })
+        .chain(actions.into_iter().map(|action| ReferenceEntry { title: action.name.clone(), text: action.text.clone() }))
+        .collect()
+}
+
+// Renders as many reference cards as needed to fit all the entries.
+pub fn render_reference_cards(entries: &[ReferenceEntry], options: &CardOptions) -> Vec<RgbaImage> {
+    let measuring_painter = CardPainter::new(options);
+    let available_height = CARD_HEIGHT - MARGIN - measuring_painter.reference_content_start();
+
+    // Splitting the entries in pages
+    let mut pages = Vec::<Vec<&ReferenceEntry>>::new();
+    let mut current_page = Vec::<&ReferenceEntry>::new();
+    let mut current_height = 0.0;
+    for entry in entries {
+        let entry_height = measuring_painter.reference_entry_height(entry);
+        if !current_page.is_empty() && current_height + entry_height > available_height {
+            pages.push(current_page);
+            current_page = Vec::new();
+            current_height = 0.0;
+        }
+        current_page.push(entry);
+        current_height += entry_height;
+    }
+    if !current_page.is_empty() {
+        pages.push(current_page);
+    }
+
+    let pages_count = pages.len();
+    pages.iter().enumerate().map(|(index, page_entries)| {
+        let mut painter = CardPainter::new(options);
+        painter.draw_frame();
+        painter.draw_reference_card(page_entries, index + 1, pages_count);
+        painter.canvas.into_image()
+    }).collect()
+}
+
+// Renders the back of the cards.
+pub fn render_card_back(back: &CardBack, options: &CardOptions) -> RgbaImage {
+    let mut painter = CardPainter::new(options);
+    painter.draw_card_back(back);
+    painter.canvas.into_image()
+}
+
+// Encodes any rendered card as PNG.
+pub fn encode_png(card: &RgbaImage) -> Result<Vec<u8>, image::ImageError> {
+    let mut buffer = Cursor::new(Vec::<u8>::new());
+    card.write_to(&mut buffer, ImageFormat::Png)?;
+    Ok(buffer.into_inner())
+}
+
+// Renders the card and encodes it as PNG, without touching the filesystem.
+pub fn render_card_png(profile: &Profile, options: &CardOptions) -> Result<Vec<u8>, image::ImageError> {
+    encode_png(&render_card(profile, options))
+}
+
+// Writes one PNG per profile in the output folder, returning the paths of the created files.
+pub fn create_png_cards(profiles: &[Profile], out_dir: &str, options: &CardOptions) -> Result<Vec<String>, std::io::Error> {
+    std::fs::create_dir_all(out_dir)?;
+
+    let mut file_names = Vec::<String>::new();
+    for profile in profiles {
+        let file_name = unique_file_name(&card_file_stem(&profile.name), &file_names);
+        let path = Path::new(out_dir).join(&file_name);
+        render_card(profile, options)
+            .save_with_format(&path, ImageFormat::Png)
+            .map_err(|err| std::io::Error::other(format!("Couldn't write {}: {}", path.display(), err)))?;
+        file_names.push(file_name);
+    }
+
+    Ok(file_names.iter().map(|name| Path::new(out_dir).join(name).display().to_string()).collect())
+}
+
+// Packs one PNG per profile in a zip archive, kept in memory so that it can be
+// downloaded from the browser as well.
+pub fn create_png_cards_zip(profiles: &[Profile], options: &CardOptions) -> Result<Vec<u8>, std::io::Error> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::<u8>::new()));
+    let file_options = FileOptions::default();
+
+    let mut file_names = Vec::<String>::new();
+    for profile in profiles {
+        let file_name = unique_file_name(&card_file_stem(&profile.name), &file_names);
+        let png = render_card_png(profile, options).map_err(std::io::Error::other)?;
+        zip.start_file(file_name.clone(), file_options)?;
+        zip.write_all(&png)?;
+        file_names.push(file_name);
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+// A filesystem-friendly name for the card of a profile.
+pub fn card_file_stem(profile_name: &str) -> String {
+    let stem: String = profile_name
+        .chars()
+        .map(|character| if character.is_alphanumeric() || character == '-' { character } else { '_' })
+        .collect();
+    if stem.is_empty() { "card".to_string() } else { stem }
+}
+
+// Avoids overwriting cards of profiles sharing the same name.
+pub fn unique_file_name(stem: &str, taken: &[String]) -> String {
+    let mut file_name = format!("{}.png", stem);
+    let mut counter = 2;
+    while taken.contains(&file_name) {
+        file_name = format!("{}_{}.png", stem, counter);
+        counter += 1;
+    }
+    file_name
+}
@@ -0,0 +1,226 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use image::{Rgba, RgbaImage, imageops};
+
+// The bundled font, so that the rasterizer does not depend on the fonts installed on the system.
+const FONT_DATA: &[u8] = include_bytes!("../../data/font/COUR.TTF");
+
+pub const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+pub const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextBaseline {
+    Top,
+    Middle,
+}
+
+// A minimal drawing surface, offering the few primitives that the canvas 2D context
+// provides in the browser: rectangles, rounded boxes and (bold) text.
+pub struct RasterCanvas {
+    image: RgbaImage,
+    font: FontRef<'static>,
+}
+
+impl RasterCanvas {
+    pub fn new(width: u32, height: u32, background: Rgba<u8>) -> RasterCanvas {
+        RasterCanvas {
+            image: RgbaImage::from_pixel(width, height, background),
+            font: FontRef::try_from_slice(FONT_DATA).expect("Bundled font is not valid"),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.image.width() as f32
+    }
+
+    pub fn height(&self) -> f32 {
+        self.image.height() as f32
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        self.image
+    }
+
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Rgba<u8>) {
+        let x_start = x.max(0.0).round() as u32;
+        let y_start = y.max(0.0).round() as u32;
+        let x_end = ((x + width).round().max(0.0) as u32).min(self.image.width());
+        let y_end = ((y + height).round().max(0.0) as u32).min(self.image.height());
+        for pixel_y in y_start..y_end {
+            for pixel_x in x_start..x_end {
+                self.image.put_pixel(pixel_x, pixel_y, color);
+            }
+        }
+    }
+
+    // Strokes the outline of a rounded rectangle. The line is centered on the border,
+    // as it happens with the canvas stroke().
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_rounded_rect(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, line_width: f32, color: Rgba<u8>) {
+        let half_line = line_width / 2.0;
+        let center_x = x + width / 2.0;
+        let center_y = y + height / 2.0;
+        let half_w = width / 2.0 - radius;
+        let half_h = height / 2.0 - radius;
+
+        let x_start = (x - half_line - 1.0).max(0.0) as u32;
+        let y_start = (y - half_line - 1.0).max(0.0) as u32;
+        let x_end = ((x + width + half_line + 1.0).max(0.0) as u32).min(self.image.width());
+        let y_end = ((y + height + half_line + 1.0).max(0.0) as u32).min(self.image.height());
+
+        for pixel_y in y_start..y_end {
+            for pixel_x in x_start..x_end {
+
+                // Signed distance from the border of the rounded box
+                let dx = ((pixel_x as f32 + 0.5 - center_x).abs() - half_w).max(0.0);
+                let dy = ((pixel_y as f32 + 0.5 - center_y).abs() - half_h).max(0.0);
+                let inner_x = ((pixel_x as f32 + 0.5 - center_x).abs() - half_w).min(0.0);
+                let inner_y = ((pixel_y as f32 + 0.5 - center_y).abs() - half_h).min(0.0);
+                let distance = (dx * dx + dy * dy).sqrt() + inner_x.max(inner_y) - radius;
+
+                let coverage = (half_line - distance.abs() + 0.5).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend_pixel(pixel_x, pixel_y, color, coverage);
+                }
+            }
+        }
+    }
+
+    pub fn text_width(&self, text: &str, size: f32) -> f32 {
+        let scaled_font = self.font.as_scaled(PxScale::from(size));
+        text.chars()
+            .map(|character| scaled_font.h_advance(self.font.glyph_id(character)))
+            .sum()
+    }
+
+    // Draws a single line of text. Bold is faked by overprinting with a small offset,
+    // since only the regular weight is bundled.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text(&mut self, text: &str, x: f32, y: f32, size: f32, color: Rgba<u8>, align: TextAlign, baseline: TextBaseline) {
+        let width = self.text_width(text, size);
+        let font = self.font.clone();
+        let scaled_font = font.as_scaled(PxScale::from(size));
+        let start_x = match align {
+            TextAlign::Left => x,
+            TextAlign::Center => x - width / 2.0,
+            TextAlign::Right => x - width,
+        };
+        let baseline_y = match baseline {
+            TextBaseline::Top => y + scaled_font.ascent(),
+            TextBaseline::Middle => y + (scaled_font.ascent() + scaled_font.descent()) / 2.0,
+        };
+
+        let bold_offset = (size / 24.0).ceil() as u32;
+        for offset in 0..bold_offset {
+            let mut cursor_x = start_x + offset as f32;
+            for character in text.chars() {
+                let glyph_id = font.glyph_id(character);
+                let glyph = glyph_id.with_scale_and_position(PxScale::from(size), point(cursor_x, baseline_y));
+                cursor_x += scaled_font.h_advance(glyph_id);
+
+                if let Some(outlined) = font.outline_glyph(glyph) {
+                    let bounds = outlined.px_bounds();
+                    let mut covered_pixels = Vec::new();
+                    outlined.draw(|glyph_x, glyph_y, coverage| {
+                        covered_pixels.push((bounds.min.x as i32 + glyph_x as i32, bounds.min.y as i32 + glyph_y as i32, coverage));
+                    });
+                    for (pixel_x, pixel_y, coverage) in covered_pixels {
+                        if pixel_x >= 0 && pixel_y >= 0 {
+                            self.blend_pixel(pixel_x as u32, pixel_y as u32, color, coverage);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Draws a line of text rotated by 90 degrees counter-clockwise, centered in (x, y).
+    pub fn draw_text_rotated(&mut self, text: &str, x: f32, y: f32, size: f32, color: Rgba<u8>) {
+        let width = self.text_width(text, size).ceil() as u32 + 2;
+        let height = (size * 1.2).ceil() as u32;
+        let mut label = RasterCanvas::new(width, height, Rgba([0, 0, 0, 0]));
+        label.draw_text(text, width as f32 / 2.0, height as f32 / 2.0, size, color, TextAlign::Center, TextBaseline::Middle);
+        let rotated = imageops::rotate270(&label.image);
+        self.draw_image(&rotated, x - rotated.width() as f32 / 2.0, y - rotated.height() as f32 / 2.0);
+    }
+
+    // Alpha-blends another image on the canvas, with its top-left corner in (x, y).
+    pub fn draw_image(&mut self, other: &RgbaImage, x: f32, y: f32) {
+        for (other_x, other_y, pixel) in other.enumerate_pixels() {
+            let pixel_x = x.round() as i64 + other_x as i64;
+            let pixel_y = y.round() as i64 + other_y as i64;
+            if pixel_x >= 0 && pixel_y >= 0 {
+                let coverage = pixel.0[3] as f32 / 255.0;
+                self.blend_pixel(pixel_x as u32, pixel_y as u32, Rgba([pixel.0[0], pixel.0[1], pixel.0[2], 255]), coverage);
+            }
+        }
+    }
+
+    // Splits the text in lines no longer than max_width, breaking on whitespace.
+    pub fn wrap_text(&self, text: &str, max_width: f32, size: f32) -> Vec<String> {
+        let mut lines = Vec::<String>::new();
+        let mut current_line = String::new();
+
+        for word in text.split_whitespace() {
+            if current_line.is_empty() {
+                current_line = word.to_string();
+            } else {
+                let test_line = format!("{} {}", current_line, word);
+                if self.text_width(&test_line, size) <= max_width {
+                    current_line = test_line;
+                } else {
+                    lines.push(current_line);
+                    current_line = word.to_string();
+                }
+            }
+        }
+
+        if !current_line.is_empty() {
+            lines.push(current_line);
+        }
+        lines
+    }
+
+    // Draws the wrapped text line by line, returning the total height used.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_wrapped_text(&mut self, text: &str, x: f32, y: f32, max_width: f32, size: f32, line_height: f32, color: Rgba<u8>, align: TextAlign) -> f32 {
+        let lines = self.wrap_text(text, max_width, size);
+        let mut current_y = y;
+        for line in &lines {
+            self.draw_text(line, x, current_y, size, color, align, TextBaseline::Top);
+            current_y += size * line_height;
+        }
+        lines.len() as f32 * size * line_height
+    }
+
+    fn blend_pixel(&mut self, x: u32, y: u32, color: Rgba<u8>, coverage: f32) {
+        if x >= self.image.width() || y >= self.image.height() {
+            return;
+        }
+        let coverage = coverage.clamp(0.0, 1.0);
+        let background = self.image.get_pixel(x, y).0;
+
+        // On transparent areas only the alpha channel carries the coverage.
+        if background[3] == 0 {
+            self.image.put_pixel(x, y, Rgba([color.0[0], color.0[1], color.0[2], (255.0 * coverage).round() as u8]));
+            return;
+        }
+
+        let blend = |source: u8, destination: u8| -> u8 {
+            (source as f32 * coverage + destination as f32 * (1.0 - coverage)).round() as u8
+        };
+        let alpha = (255.0 * coverage + background[3] as f32 * (1.0 - coverage)).round() as u8;
+        self.image.put_pixel(x, y, Rgba([
+            blend(color.0[0], background[0]),
+            blend(color.0[1], background[1]),
+            blend(color.0[2], background[2]),
+            alpha,
+        ]));
+    }
+}
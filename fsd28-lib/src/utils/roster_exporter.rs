@@ -0,0 +1,298 @@
+use lopdf::{Document, Object, Dictionary, Stream, StringFormat, content::{Content, Operation}};
+use std::str::FromStr;
+
+use crate::models::profile::Profile;
+use crate::models::roster::{Roster, RosterEntry};
+use crate::models::weapon::WeaponsConfig;
+
+// A4 page, in PDF points (1/72 inch).
+const PAGE_WIDTH: f32 = 595.28;
+const PAGE_HEIGHT: f32 = 841.89;
+const PAGE_MARGIN: f32 = 40.0;
+const TITLE_SIZE: f32 = 18.0;
+const MAX_TEXT_SIZE: f32 = 10.0;
+const MIN_TEXT_SIZE: f32 = 6.0;
+const LINE_HEIGHT: f32 = 1.3;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RosterFormat {
+    Text,     // Plain-text summary, for chat
+    Markdown, // A table, for the forum
+    Pdf,      // Printable army list, on one page when it fits
+}
+
+impl FromStr for RosterFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "text" | "txt" => Ok(RosterFormat::Text),
+            "markdown" | "md" => Ok(RosterFormat::Markdown),
+            "pdf" => Ok(RosterFormat::Pdf),
+            _ => Err(format!("Unknown roster format: {} (expected text, markdown or pdf)", value)),
+        }
+    }
+}
+
+impl RosterFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RosterFormat::Text => "txt",
+            RosterFormat::Markdown => "md",
+            RosterFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            RosterFormat::Text => "text/plain",
+            RosterFormat::Markdown => "text/markdown",
+            RosterFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+// A roster entry with everything the exporters print, computed once.
+struct EntrySummary {
+    count: u32,
+    name: String,
+    class_name: String,
+    modifiers: Vec<String>,
+    action_groups: Vec<String>, // "Weapon: Action, Action", or just the actions no longer in the rules
+    points: u32,
+}
+
+pub fn export_roster(roster: &Roster, profiles: &[Profile], weapons: &WeaponsConfig, format: RosterFormat) -> Result<Vec<u8>, std::io::Error> {
+    match format {
+        RosterFormat::Text => Ok(roster_to_text(roster, profiles, weapons).into_bytes()),
+        RosterFormat::Markdown => Ok(roster_to_markdown(roster, profiles, weapons).into_bytes()),
+        RosterFormat::Pdf => roster_to_pdf_bytes(roster, profiles, weapons),
+    }
+}
+
+// One line per entry, in the same notation accepted by the roster import:
+// "2x Name [Class] (Modifier) - Weapon: Action, Action - Action - 16 pts"
+pub fn roster_to_text(roster: &Roster, profiles: &[Profile], weapons: &WeaponsConfig) -> String {
+    let mut text = roster_title(roster) + "\n";
+    text += &format!("{}\n", roster_points(roster, profiles));
+    text += "\n";
+    for entry in summarize_entries(roster, profiles, weapons) {
+        text += &entry_line(&entry);
+        text += "\n";
+    }
+    if !roster.notes.is_empty() {
+        text += &format!("\n{}\n", roster.notes);
+    }
+    text
+}
+
+pub fn roster_to_markdown(roster: &Roster, profiles: &[Profile], weapons: &WeaponsConfig) -> String {
+    let mut text = format!("## {}\n\n", escape_markdown(&roster_title(roster)));
+    text += "| Models | Unit | Class | Modifiers | Actions | Points |\n";
+    text += "|---:|---|---|---|---|---:|\n";
+    for entry in summarize_entries(roster, profiles, weapons) {
+        text += &format!("| {} | {} | {} | {} | {} | {} |\n",
+            entry.count,
+            escape_markdown(&entry.name),
+            escape_markdown(&entry.class_name),
+            escape_markdown(&entry.modifiers.join(", ")),
+            escape_markdown(&entry.action_groups.join("; ")),
+            entry.points);
+    }
+    text += &format!("| **{}** | **Total** | | | | **{}** |\n", roster.models_count(), roster.total_cost(profiles));
+    if let Some(limit) = roster.points_limit {
+        text += &format!("\nPoints limit: {}\n", limit);
+    }
+    if !roster.notes.is_empty() {
+        text += &format!("\n{}\n", roster.notes);
+    }
+    text
+}
+
+// An army list, shrunk down to a minimum text size to fit one page. Longer rosters continue on more pages.
+pub fn roster_to_pdf_bytes(roster: &Roster, profiles: &[Profile], weapons: &WeaponsConfig) -> Result<Vec<u8>, std::io::Error> {
+    let entries = summarize_entries(roster, profiles, weapons);
+
+    // Lines of the body: the entry, then its modifiers and actions, indented
+    let mut body = Vec::<(String, bool)>::new(); // Text and bold flag
+    for entry in &entries {
+        body.push((format!("{}x {} [{}] - {} pts", entry.count, entry.name, entry.class_name, entry.points), true));
+        if !entry.modifiers.is_empty() {
+            body.push((format!("    Modifiers: {}", entry.modifiers.join(", ")), false));
+        }
+        for group in &entry.action_groups {
+            body.push((format!("    {}", group), false));
+        }
+    }
+    body.push((String::new(), false));
+    body.push((roster_points(roster, profiles), true));
+    if !roster.notes.is_empty() {
+        body.push((String::new(), false));
+        body.extend(roster.notes.lines().map(|line| (line.to_string(), false)));
+    }
+
+    let available_height = PAGE_HEIGHT - 2.0 * PAGE_MARGIN - 2.0 * TITLE_SIZE * LINE_HEIGHT;
+    let text_size = (available_height / (body.len().max(1) as f32 * LINE_HEIGHT)).clamp(MIN_TEXT_SIZE, MAX_TEXT_SIZE);
+
+    let mut pages = Vec::<Content>::new();
+    let mut content = Content { operations: vec![] };
+    let mut y = PAGE_HEIGHT - PAGE_MARGIN - TITLE_SIZE;
+    add_pdf_line(&mut content, &roster.name, PAGE_MARGIN, y, TITLE_SIZE, true);
+    y -= TITLE_SIZE * LINE_HEIGHT;
+    if !roster.faction.is_empty() {
+        add_pdf_line(&mut content, &roster.faction, PAGE_MARGIN, y, MAX_TEXT_SIZE, false);
+    }
+    y -= TITLE_SIZE * LINE_HEIGHT;
+    for (line, bold) in &body {
+        if y < PAGE_MARGIN {
+            pages.push(content);
+            content = Content { operations: vec![] };
+            y = PAGE_HEIGHT - PAGE_MARGIN - text_size;
+        }
+        add_pdf_line(&mut content, line, PAGE_MARGIN, y, text_size, *bold);
+        y -= text_size * LINE_HEIGHT;
+    }
+    pages.push(content);
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font = |base_font: &[u8]| Object::Dictionary(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Font".to_vec())),
+        ("Subtype", Object::Name(b"Type1".to_vec())),
+        ("BaseFont", Object::Name(base_font.to_vec())),
+        ("Encoding", Object::Name(b"WinAnsiEncoding".to_vec())),
+    ]));
+    let resources = Dictionary::from_iter(vec![
+        ("Font", Object::Dictionary(Dictionary::from_iter(vec![
+            ("F1", font(b"Helvetica")),
+            ("F2", font(b"Helvetica-Bold")),
+        ]))),
+    ]);
+    let mut page_ids = Vec::<Object>::new();
+    for page in pages {
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), page.encode().map_err(std::io::Error::other)?));
+        let page_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Page".to_vec())),
+            ("Parent", Object::Reference(pages_id)),
+            ("Resources", Object::Dictionary(resources.clone())),
+            ("MediaBox", Object::Array(vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()])),
+            ("Contents", Object::Reference(content_id)),
+        ]));
+        page_ids.push(Object::Reference(page_id));
+    }
+    doc.objects.insert(pages_id, Object::Dictionary(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Pages".to_vec())),
+        ("Count", Object::Integer(page_ids.len() as i64)),
+        ("Kids", Object::Array(page_ids)),
+    ])));
+    let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Catalog".to_vec())),
+        ("Pages", Object::Reference(pages_id)),
+    ]));
+    doc.trailer.set("Root", catalog_id);
+
+    let mut buffer = Vec::<u8>::new();
+    doc.save_to(&mut buffer)?;
+    Ok(buffer)
+}
+
+
+// PRIVATE METHODS
+
+fn roster_title(roster: &Roster) -> String {
+    if roster.faction.is_empty() {
+        roster.name.clone()
+    } else {
+        format!("{} - {}", roster.name, roster.faction)
+    }
+}
+
+fn roster_points(roster: &Roster, profiles: &[Profile]) -> String {
+    let total = roster.total_cost(profiles);
+    match roster.points_limit {
+        Some(limit) => format!("{} / {} points, {} models", total, limit, roster.models_count()),
+        None => format!("{} points, {} models", total, roster.models_count()),
+    }
+}
+
+fn entry_line(entry: &EntrySummary) -> String {
+    let mut line = format!("{}x {} [{}]", entry.count, entry.name, entry.class_name);
+    if !entry.modifiers.is_empty() {
+        line += &format!(" ({})", entry.modifiers.join(", "));
+    }
+    for group in &entry.action_groups {
+        line += &format!(" - {}", group);
+    }
+    line + &format!(" - {} pts", entry.points)
+}
+
+// Entries of deleted profiles are skipped.
+fn summarize_entries(roster: &Roster, profiles: &[Profile], weapons: &WeaponsConfig) -> Vec<EntrySummary> {
+    roster.resolve(profiles).into_iter()
+        .filter_map(|(entry, profile)| profile.map(|profile| summarize_entry(entry, profile, weapons)))
+        .collect()
+}
+
+fn summarize_entry(entry: &RosterEntry, profile: &Profile, weapons: &WeaponsConfig) -> EntrySummary {
+    EntrySummary {
+        count: entry.count,
+        name: profile.name.clone(),
+        class_name: profile.class_name.clone(),
+        modifiers: profile.selected_modifiers.iter().map(|modifier| modifier.id.clone()).collect(),
+        action_groups: group_actions_by_weapon(profile, weapons),
+        points: Roster::entry_cost(entry, profile),
+    }
+}
+
+// Actions are grouped under the weapon they were taken from, since several weapons offer
+// actions with the same name and different dice. Actions no longer in the rules are listed on their own.
+fn group_actions_by_weapon(profile: &Profile, weapons: &WeaponsConfig) -> Vec<String> {
+    let mut grouped = Vec::<(&str, Vec<&str>)>::new();
+    let mut remaining = Vec::<&str>::new();
+
+    for action in &profile.actions {
+        match weapons.weapon_of(action) {
+            Some(weapon) => match grouped.iter_mut().find(|(name, _)| *name == weapon.name) {
+                Some((_, actions)) => actions.push(&action.name),
+                None => grouped.push((&weapon.name, vec![&action.name])),
+            },
+            None => remaining.push(&action.name),
+        }
+    }
+
+    let mut groups: Vec<String> = grouped.into_iter()
+        .map(|(weapon, actions)| format!("{}: {}", weapon, actions.join(", ")))
+        .collect();
+    if !remaining.is_empty() {
+        groups.push(remaining.join(", "));
+    }
+    groups
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn add_pdf_line(content: &mut Content, text: &str, x: f32, y: f32, size: f32, bold: bool) {
+    let font: &[u8] = if bold { b"F2" } else { b"F1" };
+    content.operations.push(Operation::new("BT", vec![]));
+    content.operations.push(Operation::new("Tf", vec![Object::Name(font.to_vec()), size.into()]));
+    content.operations.push(Operation::new("Td", vec![x.into(), y.into()]));
+    content.operations.push(Operation::new("Tj", vec![Object::String(win_ansi(text), StringFormat::Literal)]));
+    content.operations.push(Operation::new("ET", vec![]));
+}
+
+// The standard fonts read the strings as WinAnsi (Windows-1252) bytes, not UTF-8.
+// Characters outside of it are printed as a question mark.
+fn win_ansi(text: &str) -> Vec<u8> {
+    const EXTRA: [(char, u8); 27] = [
+        ('€', 0x80), ('‚', 0x82), ('ƒ', 0x83), ('„', 0x84), ('…', 0x85), ('†', 0x86), ('‡', 0x87),
+        ('ˆ', 0x88), ('‰', 0x89), ('Š', 0x8A), ('‹', 0x8B), ('Œ', 0x8C), ('Ž', 0x8E), ('‘', 0x91),
+        ('’', 0x92), ('“', 0x93), ('”', 0x94), ('•', 0x95), ('–', 0x96), ('—', 0x97), ('˜', 0x98),
+        ('™', 0x99), ('š', 0x9A), ('›', 0x9B), ('œ', 0x9C), ('ž', 0x9E), ('Ÿ', 0x9F),
+    ];
+    text.chars().map(|character| match character as u32 {
+        0x20..=0x7E | 0xA0..=0xFF => character as u8,
+        _ => EXTRA.iter().find(|(extra, _)| *extra == character).map_or(b'?', |(_, byte)| *byte),
+    }).collect()
+}
@@ -0,0 +1,293 @@
+use regex::Regex;
+use std::fmt;
+
+use crate::models::action::Action;
+use crate::models::class::{Class, ClassesConfig};
+use crate::models::profile::{Profile, MAX_ACTIONS};
+use crate::models::roster::{Roster, RosterEntry};
+use crate::models::weapon::WeaponsConfig;
+
+const MAX_SUGGESTIONS: usize = 3;
+const DEFAULT_ROSTER_NAME: &str = "Imported Roster";
+
+// The profiles and the roster read from a text list, with everything that could not be matched.
+pub struct RosterImport {
+    pub roster: Roster,
+    pub profiles: Vec<Profile>,
+    pub issues: Vec<ImportIssue>,
+}
+
+pub struct ImportIssue {
+    pub line: usize, // 1-based
+    pub message: String,
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)?;
+        if !self.suggestions.is_empty() {
+            let quoted: Vec<String> = self.suggestions.iter().map(|name| format!("\"{}\"", name)).collect();
+            write!(f, " (did you mean {}?)", quoted.join(" or "))?;
+        }
+        Ok(())
+    }
+}
+
+// Reads the list notation written by the text export, one entry per line:
+// "2x Name [Class] (Modifier, Modifier) - Weapon: Action, Action - Action - 16 pts"
+// Entries have a count or a [Class]; the name (the class name is enough), the modifiers and the points
+// are optional. The first other line is the title ("Name - Faction"), the ones after the entries are notes.
+pub fn import_roster_text(text: &str, classes: &ClassesConfig, weapons: &WeaponsConfig) -> RosterImport {
+    let count_regex = Regex::new(r"^(\d+)\s*[xX×]\s+(.+)$").unwrap();
+    let points_regex = Regex::new(r"^\d+\s*(?:/\s*(\d+)\s*)?(?:points|pts)\b").unwrap();
+
+    let mut import = RosterImport {
+        roster: Roster::new(DEFAULT_ROSTER_NAME.to_string()),
+        profiles: Vec::new(),
+        issues: Vec::new(),
+    };
+    let mut has_title = false;
+    let mut notes = Vec::<&str>::new();
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_bullet(raw_line.trim());
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(captures) = points_regex.captures(line) {
+            if let Some(limit) = captures.get(1) {
+                import.roster.points_limit = limit.as_str().parse().ok();
+            }
+            continue;
+        }
+
+        let (count, entry_text) = match count_regex.captures(line) {
+            Some(captures) => (captures[1].parse::<u32>().unwrap_or(1), captures.get(2).unwrap().as_str()),
+            None if looks_like_entry(line) => (1, line),
+            None => {
+                if !has_title && import.profiles.is_empty() {
+                    let (name, faction) = line.split_once(" - ").unwrap_or((line, ""));
+                    import.roster.name = name.trim().to_string();
+                    import.roster.faction = faction.trim().to_string();
+                    has_title = true;
+                } else {
+                    notes.push(line);
+                }
+                continue;
+            }
+        };
+
+        if count == 0 {
+            continue;
+        }
+        if let Some(profile) = parse_entry(entry_text, line_number, classes, weapons, &mut import.issues) {
+            import.roster.entries.push(RosterEntry {
+                profile_id: profile.id.clone(),
+                count,
+                notes: String::new(),
+            });
+            import.profiles.push(profile);
+        }
+    }
+
+    import.roster.notes = notes.join("\n");
+    import
+}
+
+// Names close to the given one, best first: the ones containing it (or contained in it),
+// then the ones within a few typos.
+pub fn suggest_names(name: &str, candidates: &[String]) -> Vec<String> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    let mut scored: Vec<(usize, &String)> = candidates.iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            if lower.contains(&name) || name.contains(&lower) {
+                Some((0, candidate))
+            } else {
+                let distance = levenshtein(&name, &lower);
+                (distance <= max_distance).then_some((distance, candidate))
+            }
+        })
+        .collect();
+    scored.sort_by_key(|(distance, _)| *distance);
+
+    let mut suggestions = Vec::<String>::new();
+    for (_, candidate) in scored {
+        if !suggestions.contains(candidate) {
+            suggestions.push(candidate.clone());
+        }
+    }
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+
+// PRIVATE METHODS
+
+fn strip_bullet(line: &str) -> &str {
+    for bullet in ["- ", "* ", "• "] {
+        if let Some(stripped) = line.strip_prefix(bullet) {
+            return stripped.trim_start();
+        }
+    }
+    line
+}
+
+// Lines without a count are entries only with a [Class]: a title can name a class ("Scout Team").
+fn looks_like_entry(line: &str) -> bool {
+    line.split(" - ").next().unwrap_or(line).contains('[')
+}
+
+fn parse_entry(text: &str, line: usize, classes: &ClassesConfig, weapons: &WeaponsConfig, issues: &mut Vec<ImportIssue>) -> Option<Profile> {
+    let head_regex = Regex::new(r"^(?P<name>[^\[\(]+?)\s*(?:\[(?P<class>[^\]]+)\])?\s*(?:\((?P<modifiers>[^\)]*)\))?\s*$").unwrap();
+    let pts_regex = Regex::new(r"^\d+\s*(?:pts|points?)$").unwrap();
+
+    let mut groups = text.split(" - ").map(str::trim);
+    let head = groups.next().unwrap_or_default();
+    let Some(captures) = head_regex.captures(head) else {
+        issues.push(issue(line, format!("Cannot read the entry \"{}\"", head), Vec::new()));
+        return None;
+    };
+    let name = captures["name"].trim();
+    let class_name = captures.name("class").map_or(name, |class| class.as_str().trim());
+
+    let Some(class) = find_class(class_name, classes) else {
+        let class_names: Vec<String> = classes.classes.iter().map(|class| class.name.clone()).collect();
+        issues.push(issue(line, format!("Unknown class \"{}\"", class_name), suggest_names(class_name, &class_names)));
+        return None;
+    };
+    let profile_name = if captures.name("class").is_some() { name.to_string() } else { class.name.clone() };
+    let mut profile = Profile::new(profile_name, class.clone());
+
+    if let Some(modifiers) = captures.name("modifiers") {
+        for modifier_name in split_list(modifiers.as_str()) {
+            match class.modifiers.iter().find(|modifier| modifier.id.eq_ignore_ascii_case(modifier_name)) {
+                Some(modifier) if !profile.selected_modifiers.contains(modifier) => profile.selected_modifiers.push(modifier.clone()),
+                Some(_) => {}
+                None => {
+                    let modifier_ids: Vec<String> = class.modifiers.iter().map(|modifier| modifier.id.clone()).collect();
+                    issues.push(issue(line, format!("Unknown modifier \"{}\" for {}", modifier_name, class.name), suggest_names(modifier_name, &modifier_ids)));
+                }
+            }
+        }
+    }
+
+    let mut actions = Vec::<Action>::new();
+    for group in groups {
+        if group.is_empty() || pts_regex.is_match(group) {
+            continue;
+        }
+        match group.split_once(':') {
+            Some((weapon_name, action_names)) => parse_weapon_actions(weapon_name.trim(), action_names, line, weapons, &mut actions, issues),
+            None => {
+                for action_name in split_list(group) {
+                    match find_action(action_name, line, weapons) {
+                        Ok(action) => actions.push(action),
+                        Err(action_issue) => issues.push(action_issue),
+                    }
+                }
+            }
+        }
+    }
+
+    for action in actions {
+        if profile.actions.contains(&action) {
+            continue;
+        }
+        if profile.actions.len() >= MAX_ACTIONS {
+            issues.push(issue(line, format!("A profile has at most {} actions, \"{}\" was dropped", MAX_ACTIONS, action.name), Vec::new()));
+            continue;
+        }
+        profile.actions.push(action);
+    }
+
+    Some(profile)
+}
+
+// "Weapon: Action, Action". A weapon given without actions stands for its base action.
+fn parse_weapon_actions(weapon_name: &str, action_names: &str, line: usize, weapons: &WeaponsConfig, actions: &mut Vec<Action>, issues: &mut Vec<ImportIssue>) {
+    let Some(weapon) = weapons.weapons.iter().find(|weapon| weapon.name.eq_ignore_ascii_case(weapon_name)) else {
+        let weapon_names: Vec<String> = weapons.weapons.iter().map(|weapon| weapon.name.clone()).collect();
+        issues.push(issue(line, format!("Unknown weapon \"{}\"", weapon_name), suggest_names(weapon_name, &weapon_names)));
+        return;
+    };
+
+    let action_names = split_list(action_names);
+    if action_names.is_empty() {
+        actions.extend(weapon.options.iter().filter(|option| option.is_base).map(|option| option.action.clone()).take(1));
+        return;
+    }
+    for action_name in action_names {
+        match weapon.options.iter().find(|option| option.action.name.eq_ignore_ascii_case(action_name)) {
+            Some(option) => actions.push(option.action.clone()),
+            None => {
+                let weapon_actions: Vec<String> = weapon.options.iter().map(|option| option.action.name.clone()).collect();
+                issues.push(issue(line, format!("Unknown action \"{}\" for {}", action_name, weapon.name), suggest_names(action_name, &weapon_actions)));
+            }
+        }
+    }
+}
+
+// An action on its own is looked up in every weapon, then taken as a weapon name for its base action.
+// Several weapons can offer an action with the same name: unless they all have the same stats,
+// the name is reported as ambiguous instead of picking one of them.
+fn find_action(name: &str, line: usize, weapons: &WeaponsConfig) -> Result<Action, ImportIssue> {
+    let offers: Vec<(&str, &Action)> = weapons.weapons.iter()
+        .flat_map(|weapon| weapon.options.iter().map(move |option| (weapon.name.as_str(), &option.action)))
+        .filter(|(_, action)| action.name.eq_ignore_ascii_case(name))
+        .collect();
+    if let Some((_, first)) = offers.first() {
+        if offers.iter().all(|(_, action)| action == first) {
+            return Ok((*first).clone());
+        }
+        let choices = offers.iter().map(|(weapon, action)| format!("{}: {}", weapon, action.name)).collect();
+        return Err(issue(line, format!("\"{}\" is offered by several weapons with different stats, the weapon has to be given", name), choices));
+    }
+
+    weapons.weapons.iter()
+        .find(|weapon| weapon.name.eq_ignore_ascii_case(name))
+        .and_then(|weapon| weapon.options.iter().find(|option| option.is_base))
+        .map(|option| option.action.clone())
+        .ok_or_else(|| issue(line, format!("Unknown action \"{}\"", name), suggest_names(name, &all_action_names(weapons))))
+}
+
+fn all_action_names(weapons: &WeaponsConfig) -> Vec<String> {
+    let mut names = Vec::<String>::new();
+    for option in weapons.weapons.iter().flat_map(|weapon| weapon.options.iter()) {
+        if !names.contains(&option.action.name) {
+            names.push(option.action.name.clone());
+        }
+    }
+    names
+}
+
+fn find_class<'a>(name: &str, classes: &'a ClassesConfig) -> Option<&'a Class> {
+    classes.classes.iter().find(|class| class.name.eq_ignore_ascii_case(name))
+}
+
+fn split_list(text: &str) -> Vec<&str> {
+    text.split(',').map(str::trim).filter(|item| !item.is_empty()).collect()
+}
+
+fn issue(line: usize, message: String, suggestions: Vec<String>) -> ImportIssue {
+    ImportIssue { line, message, suggestions }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
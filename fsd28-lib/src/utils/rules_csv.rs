@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::action::{Action, ActionCost};
+use crate::models::characteristics::Characteristics;
+use crate::models::class::{Class, ClassesConfig, Tier};
+use crate::models::damage_chart::{Color, DamageChart};
+use crate::models::dice_value::DiceValue;
+use crate::models::modifier::Modifier;
+use crate::models::weapon::{Weapon, WeaponOption, WeaponsConfig};
+
+// Spreadsheet versions of the weapons and classes, for balancing. Every cell is plain text:
+// activation ranges as on the cards ("1-3, 5", "FREE"), dice as "2d8" or "1d6(2)" with the armor,
+// lists separated by "; ", and one modifier per line as "ID = points {effects}".
+
+// One row per weapon option. A weapon without options is a row without an action.
+#[derive(Serialize, Deserialize)]
+struct WeaponRow {
+    weapon: String,
+    action: String,
+    goon: String,
+    char: String,
+    hero: String,
+    points: String,
+    slot: String,
+    base: String,
+    text: String,
+}
+
+// One row per class.
+#[derive(Serialize, Deserialize)]
+struct ClassRow {
+    name: String,
+    tier: String,
+    cost: String,
+    cmd: String,
+    def: String,
+    save: String,
+    r#move: String,
+    shoot: String,
+    melee: String,
+    abilities: String,
+    damage: String,
+    modifiers: String,
+}
+
+pub fn weapons_to_csv(weapons: &WeaponsConfig) -> Result<String, String> {
+    let mut rows = Vec::new();
+    for weapon in &weapons.weapons {
+        if weapon.options.is_empty() {
+            rows.push(WeaponRow {
+                weapon: weapon.name.clone(),
+                action: String::new(), goon: String::new(), char: String::new(), hero: String::new(),
+                points: String::new(), slot: String::new(), base: String::new(), text: String::new(),
+            });
+        }
+        for option in &weapon.options {
+            let action = &option.action;
+            rows.push(WeaponRow {
+                weapon: weapon.name.clone(),
+                action: action.name.clone(),
+                goon: format_ranges(&action.cost.goon),
+                char: format_ranges(&action.cost.char),
+                hero: format_ranges(&action.cost.hero),
+                points: action.points.to_string(),
+                slot: action.slot.to_string(),
+                base: option.is_base.to_string(),
+                text: action.text.clone(),
+            });
+        }
+    }
+    write_rows(&rows)
+}
+
+// The weapons in the order they first appear, with the rows of each weapon as its options.
+// Every row with a problem is reported, with its number.
+pub fn weapons_from_csv(csv_text: &str) -> Result<WeaponsConfig, Vec<String>> {
+    let mut weapons: Vec<Weapon> = Vec::new();
+    let mut errors = Vec::new();
+    for (number, row) in read_rows::<WeaponRow>(csv_text) {
+        let row = match row {
+            Ok(row) => row,
+            Err(err) => {
+                errors.push(format!("row {}: {}", number, err));
+                continue;
+            },
+        };
+        if row.weapon.trim().is_empty() {
+            errors.push(format!("row {}: the weapon has no name", number));
+            continue;
+        }
+        let position = match weapons.iter().position(|weapon| weapon.name == row.weapon) {
+            Some(position) => position,
+            None => {
+                weapons.push(Weapon { name: row.weapon.clone(), options: Vec::new() });
+                weapons.len() - 1
+            },
+        };
+        if row.action.is_empty() {
+            continue;
+        }
+        match weapon_option(&row) {
+            Ok(option) => weapons[position].options.push(option),
+            Err(err) => errors.push(format!("row {}: {}", number, err)),
+        }
+    }
+    if errors.is_empty() { Ok(WeaponsConfig { weapons }) } else { Err(errors) }
+}
+
+pub fn classes_to_csv(classes: &ClassesConfig) -> Result<String, String> {
+    let rows: Vec<ClassRow> = classes.classes.iter().map(|class| {
+        let stats = &class.characteristics;
+        ClassRow {
+            name: class.name.clone(),
+            tier: class.tier.to_string(),
+            cost: class.cost.to_string(),
+            cmd: stats.stat_cmd.to_string(),
+            def: stats.stat_def.to_string(),
+            save: format_dice(&stats.stat_save),
+            r#move: stats.stat_move.to_string(),
+            shoot: format_dice(&stats.stat_shoot),
+            melee: format_dice(&stats.stat_melee),
+            abilities: class.special_abilities.join("; "),
+            damage: class.damage_profile.intervals.iter()
+                .map(|(faces, color, label)| format!("{} {} {}", faces, color_name(color), label))
+                .collect::<Vec<_>>().join("; "),
+            modifiers: class.modifiers.iter()
+                .map(|modifier| format!("{} = {} {}", modifier.id, modifier.points, modifier.effects))
+                .collect::<Vec<_>>().join("\n"),
+        }
+    }).collect();
+    write_rows(&rows)
+}
+
+// Every row with a problem is reported, with its number.
+pub fn classes_from_csv(csv_text: &str) -> Result<ClassesConfig, Vec<String>> {
+    let mut classes = Vec::new();
+    let mut errors = Vec::new();
+    for (number, row) in read_rows::<ClassRow>(csv_text) {
+        match row.and_then(|row| class(&row)) {
+            Ok(class) => classes.push(class),
+            Err(err) => errors.push(format!("row {}: {}", number, err)),
+        }
+    }
+    if errors.is_empty() { Ok(ClassesConfig { classes }) } else { Err(errors) }
+}
+
+fn write_rows<T: Serialize>(rows: &[T]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).map_err(|err| err.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|err| err.to_string())?;
+    String::from_utf8(bytes).map_err(|err| err.to_string())
+}
+
+// Each row with its number in the spreadsheet, the header being row 1. The columns can be in any order.
+fn read_rows<T: for<'de> Deserialize<'de>>(csv_text: &str) -> Vec<(u64, Result<T, String>)> {
+    let mut reader = csv::Reader::from_reader(csv_text.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(err) => return vec![(1, Err(err.to_string()))],
+    };
+    reader.records().map(|result| {
+        let record = match result {
+            Ok(record) => record,
+            Err(err) => {
+                let number = err.position().map(|position| position.record() + 1).unwrap_or_default();
+                return (number, Err(row_error(&err)));
+            },
+        };
+        let number = record.position().map(|position| position.record() + 1).unwrap_or_default();
+        (number, record.deserialize(Some(&headers)).map_err(|err| row_error(&err)))
+    }).collect()
+}
+
+// The messages of the csv crate, without the position already given by the row.
+fn row_error(err: &csv::Error) -> String {
+    match err.kind() {
+        csv::ErrorKind::UnequalLengths { expected_len, len, .. } => format!("{} columns instead of {}", len, expected_len),
+        csv::ErrorKind::Deserialize { err, .. } => err.to_string(),
+        _ => err.to_string(),
+    }
+}
+
+fn weapon_option(row: &WeaponRow) -> Result<WeaponOption, String> {
+    let action = Action {
+        name: row.action.clone(),
+        cost: ActionCost {
+            goon: parse_ranges(&row.goon).map_err(|err| format!("goon: {}", err))?,
+            char: parse_ranges(&row.char).map_err(|err| format!("char: {}", err))?,
+            hero: parse_ranges(&row.hero).map_err(|err| format!("hero: {}", err))?,
+        },
+        text: row.text.clone(),
+        slot: parse_bool(&row.slot).map_err(|err| format!("slot: {}", err))?,
+        points: parse_number(&row.points).map_err(|err| format!("points: {}", err))?,
+    };
+    Ok(WeaponOption { action, is_base: parse_bool(&row.base).map_err(|err| format!("base: {}", err))? })
+}
+
+fn class(row: &ClassRow) -> Result<Class, String> {
+    if row.name.trim().is_empty() {
+        return Err("the class has no name".to_string());
+    }
+    let tier = match row.tier.trim() {
+        "Goon" => Tier::Goon,
+        "Char" => Tier::Char,
+        "Hero" => Tier::Hero,
+        other => return Err(format!("tier: {} is not Goon, Char or Hero", other)),
+    };
+    let characteristics = Characteristics {
+        stat_cmd: parse_number(&row.cmd).map_err(|err| format!("cmd: {}", err))?,
+        stat_def: parse_number(&row.def).map_err(|err| format!("def: {}", err))?,
+        stat_save: parse_dice(&row.save).map_err(|err| format!("save: {}", err))?,
+        stat_move: parse_number(&row.r#move).map_err(|err| format!("move: {}", err))?,
+        stat_shoot: parse_dice(&row.shoot).map_err(|err| format!("shoot: {}", err))?,
+        stat_melee: parse_dice(&row.melee).map_err(|err| format!("melee: {}", err))?,
+    };
+    Ok(Class {
+        name: row.name.clone(),
+        tier,
+        characteristics,
+        special_abilities: split_list(&row.abilities).map(str::to_string).collect(),
+        damage_profile: parse_damage(&row.damage).map_err(|err| format!("damage: {}", err))?,
+        modifiers: parse_modifiers(&row.modifiers).map_err(|err| format!("modifiers: {}", err))?,
+        cost: parse_number(&row.cost).map_err(|err| format!("cost: {}", err))?,
+    })
+}
+
+fn split_list(text: &str) -> impl Iterator<Item = &str> {
+    text.split(';').map(str::trim).filter(|part| !part.is_empty())
+}
+
+fn parse_number(text: &str) -> Result<u32, String> {
+    text.trim().parse::<u32>().map_err(|_| format!("{} is not a number", text))
+}
+
+fn parse_bool(text: &str) -> Result<bool, String> {
+    match text.trim().to_lowercase().as_str() {
+        "true" | "yes" => Ok(true),
+        "false" | "no" | "" => Ok(false),
+        _ => Err(format!("{} is not true or false", text)),
+    }
+}
+
+// As shown on the cards, but keeping the end of the free ranges if there is one.
+fn format_ranges(ranges: &[(u32, u32)]) -> String {
+    ranges.iter().map(|range| match range {
+        (0, 0) => "FREE".to_string(),
+        (from, to) if from == to => from.to_string(),
+        (from, to) => format!("{}-{}", from, to),
+    }).collect::<Vec<_>>().join(", ")
+}
+
+// The ranges are not checked against the d6 here, that is up to the ruleset validation.
+fn parse_ranges(text: &str) -> Result<Vec<(u32, u32)>, String> {
+    let mut ranges = Vec::new();
+    for part in text.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        if part.eq_ignore_ascii_case("free") {
+            ranges.push((0, 0));
+            continue;
+        }
+        let (from, to) = part.split_once('-').unwrap_or((part, part));
+        let parse = |value: &str| value.trim().parse::<u32>().map_err(|_| format!("{} is not a range", part));
+        ranges.push((parse(from)?, parse(to)?));
+    }
+    Ok(ranges)
+}
+
+fn format_dice(dice: &DiceValue) -> String {
+    match dice.armor() {
+        0 => format!("{}d{}", dice.number(), dice.shape()),
+        armor => format!("{}d{}({})", dice.number(), dice.shape(), armor),
+    }
+}
+
+fn parse_dice(text: &str) -> Result<DiceValue, String> {
+    let text = text.trim();
+    let (dice, armor) = match text.split_once('(') {
+        Some((dice, armor)) => {
+            let armor = armor.strip_suffix(')').ok_or_else(|| format!("{} is missing a )", text))?;
+            (dice, parse_number(armor)?)
+        },
+        None => (text, 0),
+    };
+    let (number, shape) = dice.split_once(['d', 'D']).ok_or_else(|| format!("{} is not like 2d8 or 1d6(2)", text))?;
+    DiceValue::from_parts(parse_number(shape)?, parse_number(number)?, armor)
+}
+
+fn color_name(color: &Color) -> &'static str {
+    match color {
+        Color::Red => "Red",
+        Color::Yellow => "Yellow",
+        Color::Green => "Green",
+    }
+}
+
+// "3 Red DEAD; 3 Yellow MOV", with the label kept as written.
+fn parse_damage(text: &str) -> Result<DamageChart, String> {
+    let mut intervals = Vec::new();
+    for part in text.split(';').map(str::trim_start).filter(|part| !part.trim().is_empty()) {
+        let mut words = part.splitn(3, ' ');
+        let faces = parse_number(words.next().unwrap_or_default())?;
+        let color = match words.next().unwrap_or_default() {
+            "Red" => Color::Red,
+            "Yellow" => Color::Yellow,
+            "Green" => Color::Green,
+            other => return Err(format!("{} is not Red, Yellow or Green", other)),
+        };
+        intervals.push((faces, color, words.next().unwrap_or_default().to_string()));
+    }
+    Ok(DamageChart { intervals })
+}
+
+// One per line: "Commander = 2 {"ability": "Commander"}". Without effects they are empty.
+fn parse_modifiers(text: &str) -> Result<Vec<Modifier>, String> {
+    let mut modifiers = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (id, rest) = line.split_once('=').ok_or_else(|| format!("{} is not like ID = points {{effects}}", line))?;
+        let rest = rest.trim();
+        let (points, effects) = match rest.find('{') {
+            Some(start) => (&rest[..start], &rest[start..]),
+            None => (rest, ""),
+        };
+        let effects = match effects {
+            "" => Value::Object(Default::default()),
+            effects => serde_json::from_str(effects).map_err(|err| format!("{}: {}", id.trim(), err))?,
+        };
+        modifiers.push(Modifier { id: id.trim().to_string(), points: parse_number(points)?, effects });
+    }
+    Ok(modifiers)
+}
+
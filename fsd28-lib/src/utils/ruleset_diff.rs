@@ -0,0 +1,156 @@
+use crate::models::class::{Class, ClassesConfig};
+use crate::models::modifier::Modifier;
+use crate::models::profile::Profile;
+use crate::models::profile_diff::{action_changes, stat_changes, ProfileDiff};
+use crate::models::weapon::{Weapon, WeaponOption, WeaponsConfig};
+
+// A saved profile that would change with the new ruleset.
+pub struct ProfileImpact {
+    pub name: String,
+    pub changes: Vec<String>,
+}
+
+// One line per difference between two versions of the classes and weapons.
+pub fn diff_rulesets(old_classes: &ClassesConfig, old_weapons: &WeaponsConfig, new_classes: &ClassesConfig, new_weapons: &WeaponsConfig) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+
+    for old_class in &old_classes.classes {
+        match new_classes.classes.iter().find(|class| class.name == old_class.name) {
+            Some(new_class) => lines.extend(class_changes(old_class, new_class).into_iter().map(|change| format!("Class {}: {}", old_class.name, change))),
+            None => lines.push(format!("Class {}: removed", old_class.name)),
+        }
+    }
+    for new_class in &new_classes.classes {
+        if !old_classes.classes.iter().any(|class| class.name == new_class.name) {
+            lines.push(format!("Class {}: added", new_class.name));
+        }
+    }
+
+    for old_weapon in &old_weapons.weapons {
+        match new_weapons.weapons.iter().find(|weapon| weapon.name == old_weapon.name) {
+            Some(new_weapon) => lines.extend(weapon_changes(old_weapon, new_weapon).into_iter().map(|change| format!("Weapon {}: {}", old_weapon.name, change))),
+            None => lines.push(format!("Weapon {}: removed", old_weapon.name)),
+        }
+    }
+    for new_weapon in &new_weapons.weapons {
+        if !old_weapons.weapons.iter().any(|weapon| weapon.name == new_weapon.name) {
+            lines.push(format!("Weapon {}: added", new_weapon.name));
+        }
+    }
+    lines
+}
+
+// The profile built again from the ruleset: its class, modifiers and actions are looked up by name.
+pub fn rebuild_profile(profile: &Profile, classes: &ClassesConfig, weapons: &WeaponsConfig) -> Result<Profile, String> {
+    let class = classes.classes.iter()
+        .find(|class| class.name == profile.class_name)
+        .ok_or_else(|| format!("the class {} was removed", profile.class_name))?;
+
+    let mut rebuilt = Profile::new(profile.name.clone(), class.clone());
+    rebuilt.id = profile.id.clone();
+    rebuilt.folder = profile.folder.clone();
+    rebuilt.tags = profile.tags.clone();
+    rebuilt.notes = profile.notes.clone();
+    for modifier in &profile.selected_modifiers {
+        let new_modifier = class.modifiers.iter()
+            .find(|candidate| candidate.id == modifier.id)
+            .ok_or_else(|| format!("the modifier {} was removed", modifier.id))?;
+        rebuilt.selected_modifiers.push(new_modifier.clone());
+    }
+    // Actions offered by several weapons (like Overwatch) come from the weapon the profile holds
+    let holds = |weapon: &Weapon| weapon.options.iter().any(|option| option.is_base && profile.actions.iter().any(|action| action.name == option.action.name));
+    for action in &profile.actions {
+        let offering = |weapon: &&Weapon| weapon.options.iter().any(|option| option.action.name == action.name);
+        let weapon = weapons.weapons.iter().filter(offering).find(|weapon| holds(weapon))
+            .or_else(|| weapons.weapons.iter().find(offering))
+            .ok_or_else(|| format!("the action {} was removed", action.name))?;
+        let new_action = weapon.options.iter().find(|option| option.action.name == action.name).unwrap();
+        rebuilt.actions.push(new_action.action.clone());
+    }
+    Ok(rebuilt)
+}
+
+// The profiles whose final cost or card text would change with the new ruleset.
+pub fn ruleset_impact(profiles: &[Profile], classes: &ClassesConfig, weapons: &WeaponsConfig) -> Vec<ProfileImpact> {
+    profiles.iter()
+        .filter_map(|profile| {
+            let changes = match rebuild_profile(profile, classes, weapons) {
+                // The actions that changed are listed by the profile diff, with what changed in them
+                Ok(rebuilt) => ProfileDiff::between(profile, &rebuilt).lines(),
+                Err(err) => vec![format!("can't be rebuilt: {}", err)],
+            };
+            (!changes.is_empty()).then(|| ProfileImpact { name: profile.name.clone(), changes })
+        })
+        .collect()
+}
+
+
+// PRIVATE METHODS
+
+fn class_changes(old: &Class, new: &Class) -> Vec<String> {
+    let mut changes = Vec::<String>::new();
+    if old.cost != new.cost {
+        changes.push(format!("cost {} -> {}", old.cost, new.cost));
+    }
+    if old.tier != new.tier {
+        changes.push(format!("tier {} -> {}", old.tier, new.tier));
+    }
+    for change in stat_changes(&old.characteristics, &new.characteristics) {
+        changes.push(format!("{} {} -> {}", change.stat, change.old, change.new));
+    }
+    for ability in new.special_abilities.iter().filter(|ability| !old.special_abilities.contains(ability)) {
+        changes.push(format!("ability {} added", ability));
+    }
+    for ability in old.special_abilities.iter().filter(|ability| !new.special_abilities.contains(ability)) {
+        changes.push(format!("ability {} removed", ability));
+    }
+    if old.damage_profile != new.damage_profile {
+        changes.push("damage chart changed".to_string());
+    }
+    changes.extend(modifier_changes(&old.modifiers, &new.modifiers));
+    changes
+}
+
+fn modifier_changes(old: &[Modifier], new: &[Modifier]) -> Vec<String> {
+    let mut changes = Vec::<String>::new();
+    for old_modifier in old {
+        match new.iter().find(|modifier| modifier.id == old_modifier.id) {
+            Some(new_modifier) => {
+                if old_modifier.points != new_modifier.points {
+                    changes.push(format!("modifier {} points {} -> {}", old_modifier.id, old_modifier.points, new_modifier.points));
+                }
+                if old_modifier.effects != new_modifier.effects {
+                    changes.push(format!("modifier {} effects {} -> {}", old_modifier.id, old_modifier.effects, new_modifier.effects));
+                }
+            },
+            None => changes.push(format!("modifier {} removed", old_modifier.id)),
+        }
+    }
+    for new_modifier in new.iter().filter(|modifier| !old.iter().any(|old_modifier| old_modifier.id == modifier.id)) {
+        changes.push(format!("modifier {} added", new_modifier.id));
+    }
+    changes
+}
+
+fn weapon_changes(old: &Weapon, new: &Weapon) -> Vec<String> {
+    let mut changes = Vec::<String>::new();
+    for old_option in &old.options {
+        match new.options.iter().find(|option| option.action.name == old_option.action.name) {
+            Some(new_option) => changes.extend(option_changes(old_option, new_option).into_iter()
+                .map(|change| format!("{} {}", old_option.action.name, change))),
+            None => changes.push(format!("option {} removed", old_option.action.name)),
+        }
+    }
+    for new_option in new.options.iter().filter(|option| !old.options.iter().any(|old_option| old_option.action.name == option.action.name)) {
+        changes.push(format!("option {} added", new_option.action.name));
+    }
+    changes
+}
+
+fn option_changes(old: &WeaponOption, new: &WeaponOption) -> Vec<String> {
+    let mut changes = action_changes(&old.action, &new.action, None);
+    if old.is_base != new.is_base {
+        changes.push(if new.is_base { "is now a base option".to_string() } else { "is no longer a base option".to_string() });
+    }
+    changes
+}
@@ -0,0 +1,63 @@
+use std::str::FromStr;
+
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+use crate::models::ability::AbilitiesConfig;
+use crate::models::class::ClassesConfig;
+use crate::models::ruleset::Ruleset;
+use crate::models::ruleset_pack::RulesetPack;
+use crate::models::save_data::SaveData;
+use crate::models::weapon::WeaponsConfig;
+
+// The file formats that have a JSON Schema, for editors and external tools.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SchemaKind {
+    Classes,
+    Weapons,
+    Abilities,
+    Ruleset,
+    Pack,
+    Save,
+}
+
+impl SchemaKind {
+    pub const ALL: [SchemaKind; 6] = [SchemaKind::Classes, SchemaKind::Weapons, SchemaKind::Abilities, SchemaKind::Ruleset, SchemaKind::Pack, SchemaKind::Save];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchemaKind::Classes => "classes",
+            SchemaKind::Weapons => "weapons",
+            SchemaKind::Abilities => "abilities",
+            SchemaKind::Ruleset => "ruleset",
+            SchemaKind::Pack => "pack",
+            SchemaKind::Save => "save",
+        }
+    }
+}
+
+impl FromStr for SchemaKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SchemaKind::ALL.into_iter()
+            .find(|kind| kind.name() == s.to_lowercase())
+            .ok_or_else(|| format!("Unknown schema {}, expected one of: {}", s, SchemaKind::ALL.map(|kind| kind.name()).join(", ")))
+    }
+}
+
+// Generated from the types the files are read into, so it can't drift from the loader.
+pub fn schema(kind: SchemaKind) -> RootSchema {
+    match kind {
+        SchemaKind::Classes => schema_for!(ClassesConfig),
+        SchemaKind::Weapons => schema_for!(WeaponsConfig),
+        SchemaKind::Abilities => schema_for!(AbilitiesConfig),
+        SchemaKind::Ruleset => schema_for!(Ruleset),
+        SchemaKind::Pack => schema_for!(RulesetPack),
+        SchemaKind::Save => schema_for!(SaveData),
+    }
+}
+
+pub fn schema_json(kind: SchemaKind) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&schema(kind))
+}
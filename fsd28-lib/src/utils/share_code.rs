@@ -0,0 +1,44 @@
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use std::io::{Error, ErrorKind, Read, Write};
+
+use crate::models::save_data::SaveData;
+
+// Share codes are "<version>.<payload>": the save data JSON, deflated and encoded
+// in URL-safe base64 so that it fits in the fragment of a link.
+pub const SHARE_CODE_VERSION: u32 = 1;
+
+// The URL fragment holding a share code, e.g. https://.../#share=1.xyz
+pub const SHARE_FRAGMENT: &str = "share=";
+
+pub fn encode_share_code(save_data: &SaveData) -> Result<String, Error> {
+    let json = save_data.to_json().map_err(Error::other)?;
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(json.as_bytes())?;
+    let compressed = encoder.finish()?;
+    Ok(format!("{}.{}", SHARE_CODE_VERSION, base64::encode_config(compressed, base64::URL_SAFE_NO_PAD)))
+}
+
+// Accepts the bare code, or a whole share link.
+pub fn decode_share_code(code: &str) -> Result<SaveData, Error> {
+    let code = code.trim();
+    let code = code.split_once(SHARE_FRAGMENT).map_or(code, |(_, code)| code);
+    let (version, payload) = code.split_once('.')
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Not a share code"))?;
+    let version: u32 = version.parse()
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "Not a share code"))?;
+    if version > SHARE_CODE_VERSION {
+        return Err(Error::new(ErrorKind::InvalidData, format!("The share code was made by a newer version of the app (version {})", version)));
+    }
+
+    let compressed = base64::decode_config(payload, base64::URL_SAFE_NO_PAD)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, format!("The share code is damaged: {}", err)))?;
+    let mut json = String::new();
+    DeflateDecoder::new(compressed.as_slice()).read_to_string(&mut json)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, format!("The share code is damaged: {}", err)))?;
+    SaveData::from_json(&json).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+pub fn share_link(base_url: &str, code: &str) -> String {
+    let base_url = base_url.split('#').next().unwrap_or(base_url);
+    format!("{}#{}{}", base_url, SHARE_FRAGMENT, code)
+}
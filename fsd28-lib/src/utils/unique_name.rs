@@ -0,0 +1,10 @@
+// The name itself if it is free, otherwise "Name (2)", "Name (3)"... whichever is free.
+// Used for every generated copy: duplicated profiles, variants, merged profiles and rosters.
+pub fn unique_name(name: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    if !is_taken(name) {
+        return name.to_string();
+    }
+    (2..).map(|index| format!("{} ({})", name, index))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap()
+}
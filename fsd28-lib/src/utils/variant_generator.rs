@@ -0,0 +1,132 @@
+use std::str::FromStr;
+
+use crate::models::class::Class;
+use crate::models::modifier::Modifier;
+use crate::models::profile::{Profile, MAX_ACTIONS};
+use crate::models::weapon::{Weapon, WeaponsConfig};
+use crate::utils::unique_name::unique_name;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VariantKind {
+    Weapons,   // One variant per weapon, in place of the current one
+    Modifiers, // One variant per combination of the class modifiers
+}
+
+impl FromStr for VariantKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "weapons" | "weapon" => Ok(VariantKind::Weapons),
+            "modifiers" | "modifier" => Ok(VariantKind::Modifiers),
+            _ => Err(format!("Unknown variant kind: {} (expected weapons or modifiers)", value)),
+        }
+    }
+}
+
+// Variants of the base profile that follow the construction rules, each with its own ID.
+// The points are those of the variant, once its modifiers and actions are applied.
+// The names are not used by the profiles nor by the other variants, so they can be added as they are.
+pub fn generate_variants(base: &Profile, kind: VariantKind, class: &Class, weapons: &WeaponsConfig, profiles: &[Profile]) -> Vec<Profile> {
+    let variants = match kind {
+        VariantKind::Weapons => weapon_variants(base, weapons),
+        VariantKind::Modifiers => modifier_variants(base, class),
+    };
+    let mut named = Vec::<Profile>::new();
+    for mut variant in variants.into_iter().filter(|variant| construction_errors(variant, weapons).is_empty()) {
+        variant.name = unique_name(&variant.name, |candidate| {
+            profiles.iter().chain(named.iter()).any(|profile| profile.name == candidate)
+        });
+        named.push(variant);
+    }
+    named
+}
+
+// Checks the actions of a profile: at most MAX_ACTIONS, and the actions that depend on
+// a weapon need one of its base actions (e.g. Double Tap needs a Hand Gun).
+pub fn construction_errors(profile: &Profile, weapons: &WeaponsConfig) -> Vec<String> {
+    let mut errors = Vec::<String>::new();
+    if profile.actions.len() > MAX_ACTIONS {
+        errors.push(format!("{} has {} actions, at most {} are allowed", profile.name, profile.actions.len(), MAX_ACTIONS));
+    }
+
+    for action in &profile.actions {
+        // The weapons offering exactly this action. An action no longer in the rules is matched by name.
+        let mut offering: Vec<&Weapon> = weapons.weapons.iter()
+            .filter(|weapon| weapon.options.iter().any(|option| option.action == *action))
+            .collect();
+        if offering.is_empty() {
+            offering = weapons.weapons.iter()
+                .filter(|weapon| weapon.options.iter().any(|option| option.action.name == action.name))
+                .collect();
+        }
+        if offering.is_empty() {
+            errors.push(format!("{} has the unknown action {}", profile.name, action.name));
+            continue;
+        }
+        let is_allowed = offering.iter().any(|weapon| !has_base_options(weapon) || holds_weapon(profile, weapon));
+        if !is_allowed {
+            errors.push(format!("{} needs a weapon for {}", profile.name, action.name));
+        }
+    }
+    errors
+}
+
+
+// PRIVATE METHODS
+
+fn has_base_options(weapon: &Weapon) -> bool {
+    weapon.options.iter().any(|option| option.is_base)
+}
+
+// Base actions are compared whole: a Revolver's Special Bullet is not the Sniper Rifle's.
+fn holds_weapon(profile: &Profile, weapon: &Weapon) -> bool {
+    weapon.options.iter().any(|option| option.is_base && profile.actions.contains(&option.action))
+}
+
+// The weapon actions of the profile are replaced with the base action of each other weapon.
+// Generic actions (like Sprint) are kept as long as there is room.
+fn weapon_variants(base: &Profile, weapons: &WeaponsConfig) -> Vec<Profile> {
+    let held: Vec<&Weapon> = weapons.weapons.iter().filter(|weapon| holds_weapon(base, weapon)).collect();
+    let generic_actions: Vec<_> = base.actions.iter()
+        .filter(|action| !held.iter().any(|weapon| weapon.options.iter().any(|option| option.action == **action)))
+        .cloned()
+        .collect();
+
+    weapons.weapons.iter()
+        .filter(|weapon| !held.contains(weapon))
+        .filter_map(|weapon| {
+            let base_action = weapon.options.iter().find(|option| option.is_base)?.action.clone();
+            let mut variant = base.duplicate();
+            variant.name = format!("{} + {}", base.name, weapon.name);
+            variant.actions = vec![base_action];
+            variant.actions.extend(generic_actions.iter().take(MAX_ACTIONS - 1).cloned());
+            Some(variant)
+        })
+        .collect()
+}
+
+// Every combination of the class modifiers but the current one.
+fn modifier_variants(base: &Profile, class: &Class) -> Vec<Profile> {
+    let modifiers = &class.modifiers;
+    (0..1usize << modifiers.len())
+        .map(|mask| modifiers.iter().enumerate()
+            .filter(|(index, _)| mask & (1 << index) != 0)
+            .map(|(_, modifier)| modifier.clone())
+            .collect::<Vec<Modifier>>())
+        .filter(|combination| !same_modifiers(combination, &base.selected_modifiers))
+        .map(|combination| {
+            let mut variant = base.duplicate();
+            variant.name = match combination.is_empty() {
+                true => format!("{} + no modifiers", base.name),
+                false => format!("{} + {}", base.name, combination.iter().map(|modifier| modifier.id.as_str()).collect::<Vec<_>>().join(" + ")),
+            };
+            variant.selected_modifiers = combination;
+            variant
+        })
+        .collect()
+}
+
+fn same_modifiers(a: &[Modifier], b: &[Modifier]) -> bool {
+    a.len() == b.len() && a.iter().all(|modifier| b.contains(modifier))
+}
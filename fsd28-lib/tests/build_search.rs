@@ -0,0 +1,41 @@
+use fsd28_lib::utils::build_search::{search_builds, BuildScore};
+use fsd28_lib::RulesetContext;
+
+#[test]
+fn builds_fit_the_budget_and_hold_a_weapon() {
+    let rules = RulesetContext::bundled();
+    let class = rules.class("Bandit").unwrap();
+    let budget = 8;
+
+    let builds = search_builds(class, rules.weapons_config(), budget, &BuildScore::Analytics, 50);
+
+    assert!(!builds.is_empty());
+    for build in &builds {
+        assert!(build.cost <= budget, "{} pts is over the budget", build.cost);
+        assert_eq!(build.cost, build.profile.get_final_profile().cost);
+        assert!(!build.profile.actions.is_empty());
+    }
+    assert!(builds.windows(2).all(|pair| pair[0].score >= pair[1].score), "the builds are not sorted by score");
+}
+
+#[test]
+fn cheapest_build_is_not_empty() {
+    let rules = RulesetContext::bundled();
+    let class = rules.class("Bandit").unwrap();
+
+    let builds = search_builds(class, rules.weapons_config(), 30, &BuildScore::Cheapest, 1);
+
+    let cheapest = &builds[0].profile;
+    assert!(cheapest.actions.iter().any(|action| rules.weapon_of(action).is_some_and(|weapon| weapon.name != "Default")),
+        "the cheapest build has no weapon: {:?}", cheapest.actions.iter().map(|action| &action.name).collect::<Vec<_>>());
+}
+
+#[test]
+fn no_build_under_the_class_cost() {
+    let rules = RulesetContext::bundled();
+    let class = rules.class("Bandit").unwrap();
+
+    let builds = search_builds(class, rules.weapons_config(), class.cost.saturating_sub(1), &BuildScore::Cheapest, 10);
+
+    assert!(builds.is_empty());
+}
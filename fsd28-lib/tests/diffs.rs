@@ -0,0 +1,123 @@
+use fsd28_lib::utils::ruleset_diff::{diff_rulesets, ruleset_impact};
+use fsd28_lib::{CollectionDiff, Profile, ProfileDiff, RulesetContext, WeaponsConfig};
+
+fn enforcer_with_sprint(rules: &RulesetContext) -> Profile {
+    let mut profile = Profile::new("Raider Bob".to_string(), rules.class("Enforcer").unwrap().clone());
+    profile.actions = vec![rules.weapon_action("Default", "Sprint").unwrap().clone()];
+    profile
+}
+
+// The bundled weapons with another cost for Sprint.
+fn weapons_with_sprint_points(rules: &RulesetContext, points: u32) -> WeaponsConfig {
+    let mut weapons = rules.weapons_config().clone();
+    for option in weapons.weapons.iter_mut().flat_map(|weapon| weapon.options.iter_mut()) {
+        if option.action.name == "Sprint" {
+            option.action.points = points;
+        }
+    }
+    weapons
+}
+
+#[test]
+fn ruleset_impact_lists_each_action_change_once() {
+    let rules = RulesetContext::bundled();
+    let profile = enforcer_with_sprint(&rules);
+    let weapons = weapons_with_sprint_points(&rules, 6);
+
+    let impact = ruleset_impact(std::slice::from_ref(&profile), rules.classes_config(), &weapons);
+
+    assert_eq!(impact.len(), 1);
+    let action_lines: Vec<&String> = impact[0].changes.iter().filter(|line| line.contains("Sprint")).collect();
+    assert_eq!(action_lines, vec!["~ action Sprint: points 1 -> 6"], "unexpected changes: {:?}", impact[0].changes);
+}
+
+#[test]
+fn ruleset_impact_skips_unchanged_profiles() {
+    let rules = RulesetContext::bundled();
+    let profile = enforcer_with_sprint(&rules);
+
+    let impact = ruleset_impact(&[profile], rules.classes_config(), rules.weapons_config());
+
+    assert!(impact.is_empty());
+}
+
+#[test]
+fn same_name_actions_of_another_weapon_are_changes() {
+    let rules = RulesetContext::bundled();
+    let mut old = enforcer_with_sprint(&rules);
+    old.actions.push(rules.weapon_action("Submachinegun", "Overwatch").unwrap().clone());
+    let mut new = old.clone();
+    new.actions[1] = rules.weapon_action("Assault Rifle", "Overwatch").unwrap().clone();
+
+    let diff = ProfileDiff::between(&old, &new);
+
+    assert!(diff.actions_added.is_empty() && diff.actions_removed.is_empty());
+    assert_eq!(diff.actions_changed.len(), 1);
+    assert_eq!(diff.actions_changed[0].0, "Overwatch");
+    let action_lines: Vec<String> = diff.lines().into_iter().filter(|line| line.contains("action")).collect();
+    assert_eq!(action_lines.len(), 1, "unexpected lines: {:?}", action_lines);
+}
+
+#[test]
+fn profile_diff_reports_added_and_removed_items() {
+    let rules = RulesetContext::bundled();
+    let old = enforcer_with_sprint(&rules);
+    let mut new = old.clone();
+    new.name = "Raider Rob".to_string();
+    new.actions = vec![rules.weapon_action("Pistol", "Hand Gun").unwrap().clone()];
+    new.tags = vec!["elite".to_string()];
+
+    let diff = ProfileDiff::between(&old, &new);
+
+    assert_eq!(diff.name, Some(("Raider Bob".to_string(), "Raider Rob".to_string())));
+    assert_eq!(diff.actions_added, vec!["Hand Gun".to_string()]);
+    assert_eq!(diff.actions_removed, vec!["Sprint".to_string()]);
+    assert_eq!(diff.tags_added, vec!["elite".to_string()]);
+    assert_eq!(diff.points.0, old.get_final_profile().cost);
+    assert_eq!(diff.points.1, new.get_final_profile().cost);
+    assert!(ProfileDiff::between(&old, &old).is_empty());
+}
+
+#[test]
+fn collection_diff_matches_profiles_by_id_then_by_name() {
+    let rules = RulesetContext::bundled();
+    let kept = enforcer_with_sprint(&rules);
+    let removed = Profile::new("Cap Jones".to_string(), rules.class("Captain").unwrap().clone());
+    let old = vec![kept.clone(), removed];
+
+    let mut renamed = kept.clone();
+    renamed.name = "Raider Rob".to_string();
+    // An older save gives the profiles a new ID on every load: the name still matches them
+    let mut reloaded = renamed.clone();
+    reloaded.id = "another-id".to_string();
+    let added = Profile::new("Scout Sam".to_string(), rules.class("Scout").unwrap().clone());
+
+    let diff = CollectionDiff::between(&old, &[renamed.clone(), added]);
+    assert_eq!(diff.added.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["Scout Sam"]);
+    assert_eq!(diff.removed.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["Cap Jones"]);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].0, "Raider Rob");
+
+    let diff = CollectionDiff::between(&[renamed.clone()], &[reloaded]);
+    assert!(diff.is_empty(), "a reloaded profile is not a change: {}", diff);
+
+    let total = |profiles: &[Profile]| profiles.iter().map(|profile| profile.get_final_profile().cost).sum::<u32>();
+    let diff = CollectionDiff::between(&old, &[renamed.clone()]);
+    assert_eq!(diff.points, (total(&old), total(&[renamed])));
+}
+
+#[test]
+fn ruleset_diff_lists_the_changed_entries() {
+    let rules = RulesetContext::bundled();
+    let mut classes = rules.classes_config().clone();
+    classes.classes.retain(|class| class.name != "Bandit");
+    let weapons = weapons_with_sprint_points(&rules, 6);
+
+    let lines = diff_rulesets(rules.classes_config(), rules.weapons_config(), &classes, &weapons);
+
+    assert!(lines.contains(&"Class Bandit: removed".to_string()), "unexpected lines: {:?}", lines);
+    let sprint_lines: Vec<&String> = lines.iter().filter(|line| line.contains("Sprint")).collect();
+    assert_eq!(sprint_lines.len(), 1, "unexpected lines: {:?}", sprint_lines);
+    assert!(sprint_lines[0].contains("points 1 -> 6"));
+    assert!(diff_rulesets(rules.classes_config(), rules.weapons_config(), rules.classes_config(), rules.weapons_config()).is_empty());
+}
@@ -0,0 +1,64 @@
+use fsd28_lib::utils::roster_exporter::roster_to_text;
+use fsd28_lib::utils::roster_importer::import_roster_text;
+use fsd28_lib::{Action, Profile, Roster, RulesetContext};
+
+// The option of a weapon, as a profile takes it.
+fn weapon_action(rules: &RulesetContext, weapon: &str, action: &str) -> Action {
+    rules.weapon_action(weapon, action)
+        .unwrap_or_else(|| panic!("{} has no {} in the bundled rules", weapon, action))
+        .clone()
+}
+
+#[test]
+fn text_export_round_trips_the_weapon_of_each_action() {
+    let rules = RulesetContext::bundled();
+    let mut profile = Profile::new("Enforcer".to_string(), rules.class("Enforcer").unwrap().clone());
+    // Suppression Burst is offered by several weapons, with different dice
+    profile.actions = vec![
+        weapon_action(&rules, "Assault Rifle", "Suppression Burst"),
+        weapon_action(&rules, "Light Machinegun", "Suppression Burst"),
+        weapon_action(&rules, "Default", "Sprint"),
+    ];
+    let mut roster = Roster::new("Patrol".to_string());
+    roster.add_profile(&profile.id);
+
+    let text = roster_to_text(&roster, std::slice::from_ref(&profile), rules.weapons_config());
+    let import = import_roster_text(&text, rules.classes_config(), rules.weapons_config());
+
+    let issues: Vec<String> = import.issues.iter().map(|issue| issue.to_string()).collect();
+    assert!(issues.is_empty(), "the export does not import cleanly:\n{}\n{}", text, issues.join("\n"));
+    assert_eq!(import.profiles.len(), 1);
+    assert!(import.profiles[0].actions == profile.actions, "the actions changed through:\n{}", text);
+}
+
+#[test]
+fn ambiguous_bare_action_is_reported() {
+    let rules = RulesetContext::bundled();
+    let import = import_roster_text("1x Enforcer - Suppression Burst", rules.classes_config(), rules.weapons_config());
+
+    assert_eq!(import.issues.len(), 1);
+    let issue = &import.issues[0];
+    assert!(issue.message.contains("several weapons"), "unexpected issue: {}", issue);
+    assert!(issue.suggestions.contains(&"Assault Rifle: Suppression Burst".to_string()));
+    assert!(import.profiles[0].actions.is_empty());
+}
+
+#[test]
+fn roster_named_after_a_class_round_trips() {
+    let rules = RulesetContext::bundled();
+    let profile = Profile::new("Enforcer".to_string(), rules.class("Enforcer").unwrap().clone());
+    for name in ["Scout Team", "Captains of Industry"] {
+        let mut roster = Roster::new(name.to_string());
+        roster.faction = "Mercs".to_string();
+        roster.add_profile(&profile.id);
+
+        let text = roster_to_text(&roster, std::slice::from_ref(&profile), rules.weapons_config());
+        let import = import_roster_text(&text, rules.classes_config(), rules.weapons_config());
+
+        let issues: Vec<String> = import.issues.iter().map(|issue| issue.to_string()).collect();
+        assert!(issues.is_empty(), "the export does not import cleanly:\n{}\n{}", text, issues.join("\n"));
+        assert_eq!(import.roster.name, name);
+        assert_eq!(import.roster.faction, "Mercs");
+        assert_eq!(import.profiles.len(), 1);
+    }
+}
@@ -0,0 +1,38 @@
+use fsd28_lib::utils::rules_csv::{classes_from_csv, classes_to_csv, weapons_from_csv, weapons_to_csv};
+use fsd28_lib::RulesetContext;
+
+#[test]
+fn bundled_weapons_round_trip_through_csv() {
+    let rules = RulesetContext::bundled();
+
+    let csv = weapons_to_csv(rules.weapons_config()).unwrap();
+    let weapons = weapons_from_csv(&csv).unwrap_or_else(|errors| panic!("{}", errors.join("\n")));
+
+    assert!(weapons == *rules.weapons_config(), "the weapons changed through:\n{}", csv);
+}
+
+#[test]
+fn bundled_classes_round_trip_through_csv() {
+    let rules = RulesetContext::bundled();
+
+    let csv = classes_to_csv(rules.classes_config()).unwrap();
+    let classes = classes_from_csv(&csv).unwrap_or_else(|errors| panic!("{}", errors.join("\n")));
+
+    assert!(classes == *rules.classes_config(), "the classes changed through:\n{}", csv);
+}
+
+#[test]
+fn every_bad_row_is_reported_with_its_number() {
+    let rules = RulesetContext::bundled();
+    let csv = weapons_to_csv(rules.weapons_config()).unwrap();
+    let mut lines: Vec<String> = csv.lines().map(str::to_string).collect();
+    // Row 2 is the first after the header
+    lines[1] = lines[1].replacen("Sprint,1,", "Sprint,one,", 1);
+    lines[3] = lines[3].replacen(",false,", ",maybe,", 1);
+
+    let errors = weapons_from_csv(&lines.join("\n")).err().unwrap();
+
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(errors[0].starts_with("row 2"), "unexpected error: {}", errors[0]);
+    assert!(errors[1].starts_with("row 4"), "unexpected error: {}", errors[1]);
+}
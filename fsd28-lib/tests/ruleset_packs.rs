@@ -0,0 +1,103 @@
+use fsd28_lib::models::ruleset_pack::{resolve_packs, ModifierRef};
+use fsd28_lib::{Ability, Class, Ruleset, RulesetPack};
+
+fn pack(name: &str) -> RulesetPack {
+    RulesetPack { name: name.to_string(), version: "1.0".to_string(), ..RulesetPack::default() }
+}
+
+fn bundled_class(name: &str) -> Class {
+    Ruleset::bundled().classes.into_iter().find(|class| class.name == name).unwrap()
+}
+
+fn bandit_costing(cost: u32) -> Class {
+    Class { cost, ..bundled_class("Bandit") }
+}
+
+fn class_cost(ruleset: &Ruleset, name: &str) -> Option<u32> {
+    ruleset.classes.iter().find(|class| class.name == name).map(|class| class.cost)
+}
+
+#[test]
+fn later_packs_override_earlier_ones() {
+    let mut first = pack("first");
+    first.classes = vec![bandit_costing(20)];
+    let mut second = pack("second");
+    second.classes = vec![bandit_costing(30)];
+
+    let resolved = resolve_packs(&[first.clone(), second.clone()]).unwrap();
+    assert_eq!(class_cost(&resolved.ruleset, "Bandit"), Some(30));
+    assert_eq!(resolved.packs.len(), 3, "core and the two packs: {:?}", resolved.packs);
+
+    let resolved = resolve_packs(&[second, first]).unwrap();
+    assert_eq!(class_cost(&resolved.ruleset, "Bandit"), Some(20));
+}
+
+#[test]
+fn entries_replace_in_place_or_are_added() {
+    let bundled = Ruleset::bundled();
+    let mut homebrew = pack("homebrew");
+    homebrew.classes = vec![bandit_costing(20), Class { name: "Brute".to_string(), ..bundled_class("Bandit") }];
+    homebrew.abilities = vec![Ability { name: "Tough".to_string(), text: "Ignores the first wound.".to_string() }];
+
+    let resolved = resolve_packs(&[homebrew]).unwrap();
+
+    let names: Vec<&str> = resolved.ruleset.classes.iter().map(|class| class.name.as_str()).collect();
+    let bundled_names: Vec<&str> = bundled.classes.iter().map(|class| class.name.as_str()).collect();
+    assert_eq!(names[..bundled_names.len()], bundled_names[..], "the replaced class moved");
+    assert_eq!(names.last(), Some(&"Brute"));
+    assert_eq!(resolved.abilities.find("Tough").map(|ability| ability.text.as_str()), Some("Ignores the first wound."));
+}
+
+#[test]
+fn removals_drop_entries_of_the_previous_packs() {
+    let mut campaign = pack("campaign");
+    campaign.remove.classes = vec!["Bandit".to_string()];
+    campaign.remove.weapons = vec!["Flame Thrower".to_string()];
+    campaign.remove.modifiers = vec![ModifierRef { class: "Captain".to_string(), id: bundled_class("Captain").modifiers[0].id.clone() }];
+
+    let resolved = resolve_packs(&[campaign]).unwrap();
+
+    assert!(class_cost(&resolved.ruleset, "Bandit").is_none());
+    assert!(!resolved.ruleset.weapons.iter().any(|weapon| weapon.name == "Flame Thrower"));
+    let captain = resolved.ruleset.classes.iter().find(|class| class.name == "Captain").unwrap();
+    assert_eq!(captain.modifiers.len(), bundled_class("Captain").modifiers.len() - 1);
+}
+
+#[test]
+fn removals_come_before_the_additions_of_a_pack() {
+    let mut rework = pack("rework");
+    rework.remove.classes = vec!["Bandit".to_string()];
+    rework.classes = vec![bandit_costing(20)];
+
+    let resolved = resolve_packs(&[rework]).unwrap();
+
+    assert_eq!(class_cost(&resolved.ruleset, "Bandit"), Some(20));
+}
+
+#[test]
+fn removing_a_missing_entry_fails() {
+    let mut campaign = pack("campaign");
+    campaign.remove.weapons = vec!["Railgun".to_string()];
+
+    let err = resolve_packs(&[campaign]).err().unwrap();
+
+    assert!(err.contains("Railgun"), "unexpected error: {}", err);
+}
+
+#[test]
+fn dependencies_must_be_loaded_before() {
+    let base = pack("base");
+    let mut addon = pack("addon");
+    addon.dependencies = vec!["base".to_string()];
+
+    assert!(resolve_packs(&[base.clone(), addon.clone()]).is_ok());
+    assert!(resolve_packs(&[addon.clone(), base]).is_err());
+    assert!(resolve_packs(&[addon]).is_err());
+}
+
+#[test]
+fn a_pack_is_loaded_once() {
+    let base = pack("base");
+
+    assert!(resolve_packs(&[base.clone(), base]).is_err());
+}
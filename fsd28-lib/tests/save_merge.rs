@@ -0,0 +1,109 @@
+use fsd28_lib::{ConflictResolution, Profile, Roster, RulesetContext, SaveData};
+
+fn profile(rules: &RulesetContext, name: &str) -> Profile {
+    Profile::new(name.to_string(), rules.class("Enforcer").unwrap().clone())
+}
+
+// A save with one profile in one roster.
+fn save_with(profile: &Profile, roster_name: &str) -> SaveData {
+    let mut roster = Roster::new(roster_name.to_string());
+    roster.add_profile(&profile.id);
+    SaveData::new(vec![profile.clone()], vec![roster])
+}
+
+#[test]
+fn merge_adds_new_profiles_and_rosters() {
+    let rules = RulesetContext::bundled();
+    let mut save = save_with(&profile(&rules, "Raider Bob"), "Patrol");
+    let other = save_with(&profile(&rules, "Cap Jones"), "Strike Team");
+
+    let report = save.merge(other, ConflictResolution::KeepBoth);
+
+    assert_eq!((report.added, report.renamed, report.overwritten, report.skipped), (2, 0, 0, 0));
+    assert_eq!(save.profiles.len(), 2);
+    assert_eq!(save.rosters.len(), 2);
+}
+
+#[test]
+fn merge_skips_identical_content() {
+    let rules = RulesetContext::bundled();
+    let mut save = save_with(&profile(&rules, "Raider Bob"), "Patrol");
+    let other = save.clone();
+
+    let report = save.merge(other, ConflictResolution::KeepBoth);
+
+    assert_eq!((report.added, report.skipped), (0, 2));
+    assert_eq!(save.profiles.len(), 1);
+}
+
+#[test]
+fn keep_both_gives_new_ids_and_names_and_remaps_the_rosters() {
+    let rules = RulesetContext::bundled();
+    let existing = profile(&rules, "Raider Bob");
+    let mut save = save_with(&existing, "Patrol");
+    // The same profile ID and name with another content, in another roster with the same name
+    let mut changed = existing.clone();
+    changed.notes = "Changed".to_string();
+    let imported_save = save_with(&changed, "Patrol");
+
+    let report = save.merge(imported_save, ConflictResolution::KeepBoth);
+
+    assert_eq!(save.profiles.len(), 2);
+    let copy = &save.profiles[1];
+    assert_ne!(copy.id, existing.id);
+    assert_eq!(copy.name, "Raider Bob (2)");
+    assert_eq!(save.rosters[1].name, "Patrol (2)");
+    assert_eq!(save.rosters[1].entries[0].profile_id, copy.id, "the imported roster lost its profile");
+    assert_eq!(save.rosters[0].entries[0].profile_id, existing.id);
+    assert_eq!(report.renamed, 2);
+}
+
+#[test]
+fn overwrite_keeps_the_existing_ids() {
+    let rules = RulesetContext::bundled();
+    let existing = profile(&rules, "Raider Bob");
+    let mut save = save_with(&existing, "Patrol");
+    // Another profile with the same name: it replaces the existing one, under its ID
+    let mut imported = profile(&rules, "Raider Bob");
+    imported.notes = "Imported".to_string();
+    let imported_id = imported.id.clone();
+
+    let report = save.merge(save_with(&imported, "Patrol"), ConflictResolution::Overwrite);
+
+    assert_eq!(report.overwritten, 2);
+    assert_eq!(save.profiles.len(), 1);
+    assert_eq!(save.profiles[0].id, existing.id);
+    assert_eq!(save.profiles[0].notes, "Imported");
+    assert_eq!(save.rosters.len(), 1);
+    assert_eq!(save.rosters[0].entries[0].profile_id, existing.id);
+    assert!(save.find_profile(&imported_id).is_none());
+}
+
+#[test]
+fn skip_keeps_the_existing_content_and_points_new_rosters_to_it() {
+    let rules = RulesetContext::bundled();
+    let existing = profile(&rules, "Raider Bob");
+    let mut save = save_with(&existing, "Patrol");
+    let mut imported = profile(&rules, "Raider Bob");
+    imported.notes = "Imported".to_string();
+
+    let report = save.merge(save_with(&imported, "Strike Team"), ConflictResolution::Skip);
+
+    assert_eq!((report.added, report.skipped), (1, 1));
+    assert_eq!(save.profiles.len(), 1);
+    assert!(save.profiles[0].notes.is_empty());
+    let strike_team = save.find_roster("Strike Team").unwrap();
+    assert_eq!(strike_team.entries[0].profile_id, existing.id, "the new roster points to the skipped profile");
+}
+
+#[test]
+fn conflicts_list_only_differing_content() {
+    let rules = RulesetContext::bundled();
+    let existing = profile(&rules, "Raider Bob");
+    let save = save_with(&existing, "Patrol");
+    let mut other = save.clone();
+    other.profiles[0].notes = "Changed".to_string();
+
+    assert!(save.conflicts(&save.clone()).is_empty());
+    assert_eq!(save.conflicts(&other), vec!["Raider Bob".to_string()]);
+}
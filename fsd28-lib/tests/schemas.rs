@@ -0,0 +1,52 @@
+use serde_json::Value;
+use jsonschema::JSONSchema;
+
+use fsd28_lib::utils::schema::{schema, SchemaKind};
+use fsd28_lib::{Profile, Roster, Ruleset, SaveData};
+
+fn assert_valid(kind: SchemaKind, json: &str) {
+    let schema = serde_json::to_value(schema(kind)).unwrap();
+    let compiled = JSONSchema::compile(&schema).unwrap_or_else(|err| panic!("{} schema does not compile: {}", kind.name(), err));
+    let instance: Value = serde_json::from_str(json).unwrap();
+    let messages: Vec<String> = match compiled.validate(&instance) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.map(|err| format!("{} at {}", err, err.instance_path)).collect(),
+    };
+    assert!(messages.is_empty(), "{} does not match its schema:\n{}", kind.name(), messages.join("\n"));
+}
+
+#[test]
+fn bundled_data_matches_the_schemas() {
+    assert_valid(SchemaKind::Classes, include_str!("../data/classes.json"));
+    assert_valid(SchemaKind::Weapons, include_str!("../data/weapons.json"));
+    assert_valid(SchemaKind::Abilities, include_str!("../data/abilities.json"));
+
+    // A ruleset file is also a pack without a manifest
+    let ruleset = Ruleset::bundled().to_json().unwrap();
+    assert_valid(SchemaKind::Ruleset, &ruleset);
+    assert_valid(SchemaKind::Pack, &ruleset);
+}
+
+#[test]
+fn save_data_matches_the_schema() {
+    let class = fsd28_lib::get_classes("").classes[0].clone();
+    let profile = Profile::new("Raider".to_string(), class);
+    let mut roster = Roster::new("Warband".to_string());
+    roster.add_profile(&profile.id);
+    let save_data = SaveData::new(vec![profile], vec![roster]).with_ruleset(Some(Ruleset::bundled()));
+    assert_valid(SchemaKind::Save, &save_data.to_json().unwrap());
+}
+
+#[test]
+fn unknown_schema_kind_is_an_error() {
+    assert_eq!("Pack".parse::<SchemaKind>(), Ok(SchemaKind::Pack));
+    assert!("profiles".parse::<SchemaKind>().is_err());
+}
+
+#[test]
+fn schema_rejects_a_malformed_weapon() {
+    let schema = serde_json::to_value(schema(SchemaKind::Weapons)).unwrap();
+    let compiled = JSONSchema::compile(&schema).unwrap();
+    let instance = serde_json::json!({ "weapons": [{ "name": "Club", "options": "none" }] });
+    assert!(!compiled.is_valid(&instance));
+}